@@ -49,6 +49,10 @@ impl From<mtgjson_sdk::MtgjsonError> for AppError {
     fn from(e: mtgjson_sdk::MtgjsonError) -> Self {
         match &e {
             mtgjson_sdk::MtgjsonError::NotFound(msg) => AppError::not_found(msg.clone()),
+            mtgjson_sdk::MtgjsonError::InvalidArgument(msg) => AppError::bad_request(msg.clone()),
+            mtgjson_sdk::MtgjsonError::Http(_) => {
+                AppError::bad_gateway(format!("Failed to fetch from CDN: {e}"))
+            }
             _ => AppError::internal(e.to_string()),
         }
     }
@@ -2,8 +2,7 @@ mod error;
 mod routes;
 mod state;
 
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 
 use axum::routing::get;
 use axum::Router;
@@ -20,11 +19,7 @@ async fn main() {
         .expect("Failed to initialize MTGJSON SDK");
     eprintln!("SDK ready.");
 
-    let state = Arc::new(AppState {
-        sdk,
-        http: reqwest::Client::new(),
-        deck_cache: Mutex::new(HashMap::new()),
-    });
+    let state = Arc::new(AppState { sdk });
 
     let app = Router::new()
         .route("/api/meta", get(routes::meta::get_meta))
@@ -8,8 +8,6 @@ use serde_json::{json, Value};
 use crate::error::AppError;
 use crate::state::AppState;
 
-const CDN_BASE: &str = "https://mtgjson.com/api/v5";
-
 #[derive(Deserialize)]
 pub struct ListDecksParams {
     pub set_code: Option<String>,
@@ -65,80 +63,19 @@ pub async fn search_decks(
 /// GET /api/decks/:file_name
 ///
 /// Get full deck contents (mainBoard, sideBoard, commander, etc.) by
-/// the deck's `fileName` from DeckList. Fetches the individual deck JSON
-/// from the MTGJSON CDN on first access, then caches in memory.
+/// the deck's `fileName` from DeckList. The SDK downloads the individual
+/// deck JSON on first access and caches it with the rest of the data files.
 pub async fn get_deck(
     State(state): State<Arc<AppState>>,
     Path(file_name): Path<String>,
 ) -> Result<Json<Value>, AppError> {
-    // 1. Check the in-memory cache.
-    {
-        let cache = state
-            .deck_cache
-            .lock()
-            .map_err(|_| AppError::internal("Cache lock poisoned"))?;
-        if let Some(cached) = cache.get(&file_name) {
-            return Ok(Json(json!({ "data": cached })));
-        }
-    }
-
-    // 2. Validate that this fileName exists in the deck list.
-    let file_name_check = file_name.clone();
-    let exists = state
-        .sdk
-        .run(move |s| {
-            let all_decks = s.decks().list(None, None)?;
-            Ok(all_decks.iter().any(|d| {
-                d.get("fileName")
-                    .and_then(|v| v.as_str())
-                    .map(|f| f == file_name_check)
-                    .unwrap_or(false)
-            }))
-        })
-        .await?;
+    let name = file_name.clone();
+    let deck = state.sdk.run(move |s| s.decks().get(&name)).await?;
 
-    if !exists {
-        return Err(AppError::not_found(format!(
+    match deck {
+        Some(deck) => Ok(Json(json!({ "data": deck }))),
+        None => Err(AppError::not_found(format!(
             "No deck with fileName '{file_name}' found in DeckList"
-        )));
-    }
-
-    // 3. Fetch from CDN.
-    let url = format!("{CDN_BASE}/decks/{file_name}.json");
-    let resp = state.http.get(&url).send().await.map_err(|e| {
-        AppError::bad_gateway(format!("Failed to fetch deck from CDN: {e}"))
-    })?;
-
-    if resp.status() == reqwest::StatusCode::NOT_FOUND {
-        return Err(AppError::not_found(format!(
-            "Deck file '{file_name}.json' not found on CDN"
-        )));
-    }
-    if !resp.status().is_success() {
-        return Err(AppError::bad_gateway(format!(
-            "CDN returned status {}",
-            resp.status()
-        )));
-    }
-
-    let deck_json: Value = resp.json().await.map_err(|e| {
-        AppError::bad_gateway(format!("Invalid JSON from CDN: {e}"))
-    })?;
-
-    // 4. Unwrap the {"data": {...}} envelope if present.
-    let deck_data = match deck_json.get("data") {
-        Some(inner) => inner.clone(),
-        None => deck_json,
-    };
-
-    // 5. Cache and return.
-    {
-        let mut cache = state
-            .deck_cache
-            .lock()
-            .map_err(|_| AppError::internal("Cache lock poisoned"))?;
-        cache.insert(file_name, deck_data.clone());
+        ))),
     }
-
-    Ok(Json(json!({ "data": deck_data })))
 }
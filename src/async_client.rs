@@ -3,6 +3,8 @@
 //! Runs all SDK operations on a blocking thread pool via
 //! [`tokio::task::spawn_blocking`], keeping the async event loop free.
 //! DuckDB queries are CPU-bound but fast, making this approach efficient.
+//! Operations from concurrent tasks run in parallel, each on its blocking
+//! thread's own DuckDB connection.
 //!
 //! # Example
 //!
@@ -25,7 +27,7 @@
 
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 use std::time::Duration;
 
 use crate::error::{MtgjsonError, Result};
@@ -40,6 +42,18 @@ pub struct AsyncMtgjsonSdkBuilder {
     cache_dir: Option<PathBuf>,
     offline: bool,
     timeout: Duration,
+    bundle: Option<PathBuf>,
+    cdn_base: Option<String>,
+    mirrors: Vec<String>,
+    max_retries: Option<u32>,
+    retry_backoff: Option<Duration>,
+    keep_versions: Option<usize>,
+    pinned_version: Option<String>,
+    lock_timeout: Option<Duration>,
+    quota: Option<u64>,
+    download_progress: Option<crate::DownloadProgressFn>,
+    download_concurrency: Option<usize>,
+    materialize: bool,
 }
 
 impl Default for AsyncMtgjsonSdkBuilder {
@@ -48,6 +62,18 @@ impl Default for AsyncMtgjsonSdkBuilder {
             cache_dir: None,
             offline: false,
             timeout: Duration::from_secs(120),
+            bundle: None,
+            cdn_base: None,
+            mirrors: Vec::new(),
+            max_retries: None,
+            retry_backoff: None,
+            keep_versions: None,
+            pinned_version: None,
+            lock_timeout: None,
+            quota: None,
+            download_progress: None,
+            download_concurrency: None,
+            materialize: false,
         }
     }
 }
@@ -71,6 +97,85 @@ impl AsyncMtgjsonSdkBuilder {
         self
     }
 
+    /// Set the CDN base URL; see [`MtgjsonSdkBuilder::cdn_base`](crate::MtgjsonSdkBuilder::cdn_base).
+    pub fn cdn_base(mut self, url: impl Into<String>) -> Self {
+        self.cdn_base = Some(url.into());
+        self
+    }
+
+    /// Set fallback mirrors; see [`MtgjsonSdkBuilder::mirrors`](crate::MtgjsonSdkBuilder::mirrors).
+    pub fn mirrors<I, S>(mut self, urls: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.mirrors = urls.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set the download retry count; see [`MtgjsonSdkBuilder::max_retries`](crate::MtgjsonSdkBuilder::max_retries).
+    pub fn max_retries(mut self, retries: u32) -> Self {
+        self.max_retries = Some(retries);
+        self
+    }
+
+    /// Set the initial retry delay; see [`MtgjsonSdkBuilder::retry_backoff`](crate::MtgjsonSdkBuilder::retry_backoff).
+    pub fn retry_backoff(mut self, backoff: Duration) -> Self {
+        self.retry_backoff = Some(backoff);
+        self
+    }
+
+    /// Set how many replaced versions are kept; see [`MtgjsonSdkBuilder::keep_versions`](crate::MtgjsonSdkBuilder::keep_versions).
+    pub fn keep_versions(mut self, versions: usize) -> Self {
+        self.keep_versions = Some(versions);
+        self
+    }
+
+    /// Set the cache lock timeout; see [`MtgjsonSdkBuilder::lock_timeout`](crate::MtgjsonSdkBuilder::lock_timeout).
+    pub fn lock_timeout(mut self, timeout: Duration) -> Self {
+        self.lock_timeout = Some(timeout);
+        self
+    }
+
+    /// Limit the cache size; see [`MtgjsonSdkBuilder::cache_quota`](crate::MtgjsonSdkBuilder::cache_quota).
+    pub fn cache_quota(mut self, bytes: u64) -> Self {
+        self.quota = Some(bytes);
+        self
+    }
+
+    /// Report download progress; see [`MtgjsonSdkBuilder::download_progress`](crate::MtgjsonSdkBuilder::download_progress).
+    pub fn download_progress<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&crate::DownloadProgress<'_>) + Send + Sync + 'static,
+    {
+        self.download_progress = Some(Arc::new(callback));
+        self
+    }
+
+    /// Set how many files are downloaded at once; see [`MtgjsonSdkBuilder::download_concurrency`](crate::MtgjsonSdkBuilder::download_concurrency).
+    pub fn download_concurrency(mut self, downloads: usize) -> Self {
+        self.download_concurrency = Some(downloads);
+        self
+    }
+
+    /// Materialize views into a persistent database; see [`MtgjsonSdkBuilder::materialize`](crate::MtgjsonSdkBuilder::materialize).
+    pub fn materialize(mut self, materialize: bool) -> Self {
+        self.materialize = materialize;
+        self
+    }
+
+    /// Serve a specific MTGJSON version; see [`MtgjsonSdkBuilder::pin_version`](crate::MtgjsonSdkBuilder::pin_version).
+    pub fn pin_version(mut self, version: impl Into<String>) -> Self {
+        self.pinned_version = Some(version.into());
+        self
+    }
+
+    /// Load the cache from a bundle; see [`MtgjsonSdkBuilder::bundle`](crate::MtgjsonSdkBuilder::bundle).
+    pub fn bundle<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.bundle = Some(path.as_ref().to_path_buf());
+        self
+    }
+
     /// Build the async SDK, initializing the cache and DuckDB connection.
     ///
     /// Initialization runs on the blocking thread pool so it won't block
@@ -81,10 +186,44 @@ impl AsyncMtgjsonSdkBuilder {
             if let Some(dir) = self.cache_dir {
                 builder = builder.cache_dir(dir);
             }
-            builder = builder.offline(self.offline).timeout(self.timeout);
+            if let Some(bundle) = self.bundle {
+                builder = builder.bundle(bundle);
+            }
+            if let Some(base) = self.cdn_base {
+                builder = builder.cdn_base(base);
+            }
+            builder = builder.mirrors(self.mirrors);
+            if let Some(retries) = self.max_retries {
+                builder = builder.max_retries(retries);
+            }
+            if let Some(backoff) = self.retry_backoff {
+                builder = builder.retry_backoff(backoff);
+            }
+            if let Some(versions) = self.keep_versions {
+                builder = builder.keep_versions(versions);
+            }
+            if let Some(timeout) = self.lock_timeout {
+                builder = builder.lock_timeout(timeout);
+            }
+            if let Some(version) = self.pinned_version {
+                builder = builder.pin_version(version);
+            }
+            if let Some(bytes) = self.quota {
+                builder = builder.cache_quota(bytes);
+            }
+            if let Some(downloads) = self.download_concurrency {
+                builder = builder.download_concurrency(downloads);
+            }
+            if let Some(callback) = self.download_progress {
+                builder = builder.download_progress(move |p| callback(p));
+            }
+            builder = builder
+                .offline(self.offline)
+                .timeout(self.timeout)
+                .materialize(self.materialize);
             let sdk = builder.build()?;
             Ok(AsyncMtgjsonSdk {
-                inner: Arc::new(Mutex::new(sdk)),
+                inner: Arc::new(sdk),
             })
         })
         .await
@@ -99,8 +238,9 @@ impl AsyncMtgjsonSdkBuilder {
 /// Async wrapper around [`MtgjsonSdk`].
 ///
 /// All operations are dispatched to a blocking thread pool via
-/// [`tokio::task::spawn_blocking`]. The underlying [`MtgjsonSdk`] is
-/// protected by a [`Mutex`] since it uses `RefCell` internally.
+/// [`tokio::task::spawn_blocking`]. The underlying [`MtgjsonSdk`] is shared,
+/// not locked, so operations do not wait for each other. Cloning is cheap
+/// and the clones share the same SDK.
 ///
 /// # Usage
 ///
@@ -114,8 +254,9 @@ impl AsyncMtgjsonSdkBuilder {
 /// # Ok(())
 /// # }
 /// ```
+#[derive(Clone)]
 pub struct AsyncMtgjsonSdk {
-    inner: Arc<Mutex<MtgjsonSdk>>,
+    inner: Arc<MtgjsonSdk>,
 }
 
 impl AsyncMtgjsonSdk {
@@ -148,12 +289,7 @@ impl AsyncMtgjsonSdk {
         T: Send + 'static,
     {
         let sdk = self.inner.clone();
-        tokio::task::spawn_blocking(move || {
-            let guard = sdk
-                .lock()
-                .map_err(|_| MtgjsonError::InvalidArgument("SDK lock poisoned".into()))?;
-            f(&guard)
-        })
+        tokio::task::spawn_blocking(move || f(&sdk))
         .await
         .map_err(|e| MtgjsonError::InvalidArgument(format!("Task join error: {e}")))?
     }
@@ -165,40 +301,61 @@ impl AsyncMtgjsonSdk {
     pub async fn sql(
         &self,
         query: &str,
-        params: &[String],
+        params: &[crate::SqlValue],
     ) -> Result<Vec<HashMap<String, serde_json::Value>>> {
         let query = query.to_string();
         let params = params.to_vec();
         self.run(move |s| s.sql(&query, &params)).await
     }
 
+    /// Execute a raw SQL query asynchronously, returning Arrow record batches.
+    ///
+    /// Convenience wrapper around [`run()`](Self::run) for
+    /// [`MtgjsonSdk::sql_arrow()`].
+    #[cfg(feature = "arrow")]
+    pub async fn sql_arrow(
+        &self,
+        query: &str,
+        params: &[crate::SqlValue],
+    ) -> Result<Vec<crate::arrow::record_batch::RecordBatch>> {
+        let query = query.to_string();
+        let params = params.to_vec();
+        self.run(move |s| s.sql_arrow(&query, &params)).await
+    }
+
     /// Load and return the MTGJSON metadata asynchronously.
     pub async fn meta(&self) -> Result<serde_json::Value> {
         self.run(|s| s.meta()).await
     }
 
-    /// Check for a newer MTGJSON version and reset views if stale.
+    /// Check for a newer MTGJSON version and swap in updated files if stale.
     pub async fn refresh(&self) -> Result<bool> {
         self.run(|s| s.refresh()).await
     }
 
+    /// Undo the most recent refresh; see [`MtgjsonSdk::rollback`].
+    pub async fn rollback(&self) -> Result<Option<String>> {
+        self.run(|s| s.rollback()).await
+    }
+
+    /// Report what is cached; see [`MtgjsonSdk::cache_stats`].
+    pub async fn cache_stats(&self) -> Result<crate::CacheStats> {
+        self.run(|s| s.cache_stats()).await
+    }
+
     /// Return the list of currently registered DuckDB view names.
     pub async fn views(&self) -> Result<Vec<String>> {
         self.run(|s| Ok(s.views())).await
     }
 
-    /// Close the SDK, releasing all resources.
+    /// Close this handle to the SDK.
     ///
-    /// After calling this, subsequent operations will fail with a
-    /// poisoned lock error.
+    /// Resources are released once the last clone is closed or dropped and
+    /// no operation is still running.
     pub async fn close(self) -> Result<()> {
         tokio::task::spawn_blocking(move || {
-            let sdk = self
-                .inner
-                .lock()
-                .map_err(|_| MtgjsonError::InvalidArgument("SDK lock poisoned".into()))?;
-            // Dropping the MutexGuard drops the SDK
-            drop(sdk);
+            // Dropping the last Arc drops the SDK
+            drop(self.inner);
             Ok(())
         })
         .await
@@ -4,8 +4,9 @@
 //! tables and performing weighted random card selection, mirroring the
 //! distribution rules defined by MTGJSON.
 
-use crate::connection::Connection;
+use crate::connection::{Connection, ConnectionRef};
 use crate::error::{MtgjsonError, Result};
+use crate::sql_value::SqlValue;
 use rand::prelude::*;
 use std::collections::HashMap;
 
@@ -15,20 +16,29 @@ use std::collections::HashMap;
 /// contents, sheet cards, and sheet metadata) to faithfully reproduce the
 /// distribution of cards in sealed product.
 pub struct BoosterSimulator<'a> {
-    conn: &'a Connection,
+    conn: ConnectionRef<'a>,
 }
 
 impl<'a> BoosterSimulator<'a> {
     /// Create a new `BoosterSimulator` bound to the given connection.
     pub fn new(conn: &'a Connection) -> Self {
-        Self { conn }
+        Self {
+            conn: ConnectionRef::Direct(conn),
+        }
+    }
+
+    /// Create a new `BoosterSimulator` on the calling thread's SDK connection.
+    pub(crate) fn from_sdk(sdk: &'a crate::MtgjsonSdk) -> Self {
+        Self {
+            conn: ConnectionRef::Sdk(sdk),
+        }
     }
 
     /// Return the available booster type names for a set (e.g. `["draft", "collector"]`).
     ///
     /// Returns an empty vector if the set has no booster configuration data.
     pub fn available_types(&self, set_code: &str) -> Result<Vec<String>> {
-        self.conn.ensure_views(&["set_booster_content_weights"])?;
+        self.conn.get()?.ensure_views(&["set_booster_content_weights"])?;
 
         let upper = set_code.to_uppercase();
         let sql = r#"
@@ -38,7 +48,7 @@ impl<'a> BoosterSimulator<'a> {
             ORDER BY "boosterName"
         "#;
 
-        let rows = self.conn.execute(sql, &[upper.clone()])?;
+        let rows = self.conn.get()?.execute(sql, &[upper.into()])?;
 
         let types: Vec<String> = rows
             .into_iter()
@@ -131,7 +141,7 @@ impl<'a> BoosterSimulator<'a> {
         booster_type: &str,
         sheet_name: &str,
     ) -> Result<Option<HashMap<String, i64>>> {
-        self.conn.ensure_views(&["set_booster_sheet_cards"])?;
+        self.conn.get()?.ensure_views(&["set_booster_sheet_cards"])?;
 
         let upper = set_code.to_uppercase();
         let sql = r#"
@@ -142,7 +152,7 @@ impl<'a> BoosterSimulator<'a> {
               AND "sheetName" = ?
         "#;
 
-        let rows = self.conn.execute(sql, &[upper.clone(), booster_type.to_string(), sheet_name.to_string()])?;
+        let rows = self.conn.get()?.execute(sql, &[upper.clone().into(), booster_type.into(), sheet_name.into()])?;
 
         if rows.is_empty() {
             return Ok(None);
@@ -178,7 +188,7 @@ impl<'a> BoosterSimulator<'a> {
         set_code: &str,
         booster_type: &str,
     ) -> Result<Vec<serde_json::Value>> {
-        self.conn.ensure_views(&[
+        self.conn.get()?.ensure_views(&[
             "set_booster_content_weights",
             "set_booster_contents",
         ])?;
@@ -193,7 +203,7 @@ impl<'a> BoosterSimulator<'a> {
         "#;
 
         let weight_rows =
-            self.conn.execute(weight_sql, &[set_code.to_string(), booster_type.to_string()])?;
+            self.conn.get()?.execute(weight_sql, &[set_code.into(), booster_type.into()])?;
 
         if weight_rows.is_empty() {
             return Ok(Vec::new());
@@ -209,7 +219,7 @@ impl<'a> BoosterSimulator<'a> {
         "#;
 
         let contents_rows =
-            self.conn.execute(contents_sql, &[set_code.to_string(), booster_type.to_string()])?;
+            self.conn.get()?.execute(contents_sql, &[set_code.into(), booster_type.into()])?;
 
         // Group contents by booster index
         let mut contents_map: HashMap<i64, serde_json::Map<String, serde_json::Value>> =
@@ -269,7 +279,7 @@ impl<'a> BoosterSimulator<'a> {
         booster_type: &str,
         sheet_name: &str,
     ) -> Result<Option<serde_json::Value>> {
-        self.conn.ensure_views(&[
+        self.conn.get()?.ensure_views(&[
             "set_booster_sheet_cards",
             "set_booster_sheets",
         ])?;
@@ -286,7 +296,7 @@ impl<'a> BoosterSimulator<'a> {
         "#;
 
         let props_rows =
-            self.conn.execute(props_sql, &[set_code.to_string(), booster_type.to_string(), sheet_name.to_string()])?;
+            self.conn.get()?.execute(props_sql, &[set_code.into(), booster_type.into(), sheet_name.into()])?;
 
         let allow_duplicates = props_rows
             .first()
@@ -310,7 +320,7 @@ impl<'a> BoosterSimulator<'a> {
         "#;
 
         let card_rows =
-            self.conn.execute(cards_sql, &[set_code.to_string(), booster_type.to_string(), sheet_name.to_string()])?;
+            self.conn.get()?.execute(cards_sql, &[set_code.into(), booster_type.into(), sheet_name.into()])?;
 
         if card_rows.is_empty() {
             return Ok(None);
@@ -346,7 +356,7 @@ impl<'a> BoosterSimulator<'a> {
             return Ok(Vec::new());
         }
 
-        self.conn.ensure_views(&["cards"])?;
+        self.conn.get()?.ensure_views(&["cards"])?;
 
         // Build IN clause with positional params
         let placeholders: Vec<&str> = uuids.iter().map(|_| "?").collect();
@@ -356,7 +366,8 @@ impl<'a> BoosterSimulator<'a> {
             placeholders.join(", ")
         );
 
-        let rows = self.conn.execute(&sql, uuids)?;
+        let params: Vec<SqlValue> = uuids.iter().map(Into::into).collect();
+        let rows = self.conn.get()?.execute(&sql, &params)?;
 
         // Build a lookup map for ordering
         let mut card_map: HashMap<String, serde_json::Value> = HashMap::new();
@@ -2,16 +2,236 @@
 //!
 //! Downloads and caches MTGJSON data files from the CDN. Checks Meta.json for
 //! version changes and re-downloads when stale. Individual files are downloaded
-//! lazily on first access.
+//! lazily on first access, or all at once with [`CacheManager::prefetch_all`].
+//!
+//! Each download is recorded in a per-file manifest (version, size, ETag,
+//! Last-Modified, download time), so [`CacheManager::refresh`] re-fetches only
+//! the files whose version changed, with conditional GETs. Refreshed files
+//! are downloaded to a staging directory first and swapped in only once all
+//! of them arrived; the files of the previous version are kept side by side
+//! under `versions/<version>/`, where [`CacheManager::rollback`] can restore
+//! them and a cache manager with a
+//! [`pinned_version`](CacheManager::pinned_version) reads them.
+//!
+//! Downloads are checked against the `.sha256` checksum published next to each
+//! CDN artifact, and the checksum is kept beside the cached file so
+//! [`CacheManager::verify`] can recheck the cache later.
+//!
+//! Processes sharing a cache directory coordinate through advisory file
+//! locks under `locks/`: downloads hold a shared cache lock and a lock on the
+//! file being downloaded, while clears, bundle imports and the swap phase of
+//! a refresh hold the cache lock exclusively. A cache manager whose files
+//! back registered DuckDB views holds a shared views lock, which
+//! [`CacheManager::clear`] in another process waits for.
+//!
+//! With a [`quota`](CacheManager::quota), the least recently used files
+//! and archived versions are evicted whenever the cache grows past it,
+//! except files backing views registered through this cache manager.
+//! [`CacheManager::stats`] reports what is cached.
+//!
+//! A populated cache can be exported as a bundle (a tar archive with a
+//! [`BundleManifest`]) and imported on a machine without network access.
 
 use crate::config;
 use crate::error::{MtgjsonError, Result};
+use crate::lock::{self, FileLock, LockMode};
 use flate2::read::GzDecoder;
 use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
-use std::io::{BufReader, Read};
-use std::path::{Path, PathBuf};
-use std::time::Duration;
+use std::io::{self, BufReader, Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Per-file download records, relative to the cache directory.
+const MANIFEST_FILE: &str = "cache_manifest.json";
+
+/// Directory, relative to the cache directory, that [`CacheManager::refresh`]
+/// downloads new files into before swapping them in.
+const STAGING_DIR: &str = "staging";
+
+/// Directory, relative to the cache directory, holding the files of each
+/// version replaced by a refresh in a `<version>/` subdirectory.
+const VERSIONS_DIR: &str = "versions";
+
+/// Directory, relative to the cache directory, holding the databases views
+/// are materialized into, one `<version>.duckdb` file per MTGJSON version.
+const DATABASES_DIR: &str = "databases";
+
+/// Archive name used when the replaced files had no recorded version.
+const UNVERSIONED: &str = "unversioned";
+
+/// Directory, relative to the cache directory, holding the lock files.
+const LOCKS_DIR: &str = "locks";
+
+/// Shared while a single file is downloaded, exclusive while many files are
+/// replaced or removed.
+const CACHE_LOCK: &str = "cache.lock";
+
+/// Exclusive while the per-file manifest and `version.txt` are rewritten.
+const MANIFEST_LOCK: &str = "manifest.lock";
+
+/// Exclusive for the whole of a refresh or rollback.
+const REFRESH_LOCK: &str = "refresh.lock";
+
+/// Shared while cached files back registered views, exclusive while the
+/// cache is cleared.
+const VIEWS_LOCK: &str = "views.lock";
+
+/// Name of the manifest entry at the start of every cache bundle.
+const BUNDLE_MANIFEST: &str = "manifest.json";
+
+/// Bundle layout version written by [`CacheManager::export_bundle`].
+const BUNDLE_FORMAT: u32 = 1;
+
+/// Size of the buffer a download is streamed through, and so how often
+/// [`DownloadProgress`] is reported.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Suffix of the checksum files published next to each CDN artifact and kept
+/// next to each cached file.
+const CHECKSUM_SUFFIX: &str = ".sha256";
+
+/// Progress report passed to the [`CacheManager::prefetch`] callback after
+/// each file is cached.
+#[derive(Debug, Clone)]
+pub struct PrefetchProgress<'a> {
+    /// Logical name of the file (e.g. `"cards"`, `"meta"`).
+    pub name: &'a str,
+    /// Local path of the cached file.
+    pub path: &'a Path,
+    /// Number of files cached so far, including this one.
+    pub completed: usize,
+    /// Total number of files in this prefetch.
+    pub total: usize,
+}
+
+/// Progress report passed to the
+/// [`download_progress`](CacheManager::download_progress) callback while a
+/// file is downloaded.
+#[derive(Debug, Clone)]
+pub struct DownloadProgress<'a> {
+    /// Path of the file relative to the cache directory (e.g.
+    /// `"parquet/cards.parquet"`).
+    pub file: &'a str,
+    /// Bytes received so far, including a resumed partial download.
+    pub downloaded: u64,
+    /// Size of the file in bytes, if the server reported it.
+    pub total: Option<u64>,
+}
+
+/// Callback receiving [`DownloadProgress`] reports.
+pub type DownloadProgressFn = Arc<dyn Fn(&DownloadProgress<'_>) + Send + Sync>;
+
+/// Contents of a cache bundle, stored as `manifest.json` inside the archive.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleManifest {
+    /// Bundle layout version.
+    pub format: u32,
+    /// MTGJSON version of the bundled data, if the exporting cache knew it.
+    pub version: Option<String>,
+    /// Bundled files, relative to the cache directory.
+    pub files: Vec<BundleFile>,
+}
+
+/// Per-file download records, stored as `cache_manifest.json` in the cache
+/// directory.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CacheManifest {
+    /// Records keyed by path relative to the cache directory.
+    pub files: BTreeMap<String, FileRecord>,
+}
+
+/// How and when one cached file was downloaded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileRecord {
+    /// MTGJSON version the file was current for, if known.
+    pub version: Option<String>,
+    /// File size in bytes.
+    pub size: u64,
+    /// `ETag` response header, used for `If-None-Match`.
+    pub etag: Option<String>,
+    /// `Last-Modified` response header, used for `If-Modified-Since`.
+    pub last_modified: Option<String>,
+    /// Download time as seconds since the Unix epoch.
+    pub downloaded_at: u64,
+    /// Last time the file was served from the cache, as seconds since the
+    /// Unix epoch.
+    #[serde(default)]
+    pub last_accessed: Option<u64>,
+}
+
+/// Outcome of [`CacheManager::verify`]. Paths are relative to the cache
+/// directory, `/`-separated.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VerifyReport {
+    /// Files whose contents match their checksum.
+    pub verified: Vec<String>,
+    /// Files whose contents do not match their checksum.
+    pub corrupt: Vec<String>,
+    /// Files with no known checksum, which could not be checked.
+    pub unchecked: Vec<String>,
+    /// Whether corrupt files were removed from the cache.
+    pub evicted: bool,
+    /// Corrupt files kept despite `evict` because they back registered
+    /// views.
+    pub in_use: Vec<String>,
+}
+
+impl VerifyReport {
+    /// `true` if no corrupt files were found.
+    pub fn is_ok(&self) -> bool {
+        self.corrupt.is_empty()
+    }
+}
+
+/// Outcome of [`CacheManager::stats`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CacheStats {
+    /// Current files, then the files of each archived version, by path.
+    pub files: Vec<CachedFile>,
+    /// Bytes used by the cached files and their checksums, and by the
+    /// materialized databases. Files hard-linked into a version archive are
+    /// counted once.
+    pub total_size: u64,
+    /// The configured [`quota`](CacheManager::quota).
+    pub quota: Option<u64>,
+}
+
+/// One file in a [`CacheStats`] report.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CachedFile {
+    /// Path relative to the cache directory, `/`-separated.
+    pub path: String,
+    /// Size in bytes, including the checksum kept next to the file or the
+    /// write-ahead log kept next to a materialized database.
+    pub size: u64,
+    /// MTGJSON version of the file, if known.
+    pub version: Option<String>,
+    /// Whether the file belongs to a version archived under `versions/`.
+    pub archived: bool,
+    /// Download time as seconds since the Unix epoch, if recorded.
+    pub downloaded_at: Option<u64>,
+    /// Last time the file was served from the cache, as seconds since the
+    /// Unix epoch, if recorded.
+    pub last_accessed: Option<u64>,
+    /// Whether the file backs a view registered through this cache manager,
+    /// or is a materialized database attached through it.
+    pub in_use: bool,
+}
+
+/// One file in a cache bundle.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleFile {
+    /// Path relative to the cache directory, `/`-separated.
+    pub path: String,
+    /// File size in bytes.
+    pub size: u64,
+}
 
 /// Downloads and caches MTGJSON data files from the CDN.
 ///
@@ -22,6 +242,50 @@ pub struct CacheManager {
     pub cache_dir: PathBuf,
     /// If true, never download from CDN (use cached files only).
     pub offline: bool,
+    /// Base URL data files and `Meta.json` are downloaded from.
+    ///
+    /// Defaults to [`config::CDN_BASE`].
+    pub cdn_base: String,
+    /// Fallback base URLs, tried in order when a request to
+    /// [`cdn_base`](Self::cdn_base) fails.
+    pub mirrors: Vec<String>,
+    /// How many times a failed download is retried against each base URL
+    /// before moving on to the next mirror. Defaults to 3.
+    pub max_retries: u32,
+    /// Delay before the first retry; doubled for each further retry.
+    /// Defaults to 1 second.
+    pub retry_backoff: Duration,
+    /// How many versions replaced by [`refresh`](Self::refresh) are kept for
+    /// [`rollback`](Self::rollback). Defaults to 2.
+    pub keep_versions: usize,
+    /// MTGJSON version to serve instead of the latest one.
+    ///
+    /// When set, files are read from the cache for exactly this version (see
+    /// [`version_file`](Self::version_file)), [`refresh`](Self::refresh)
+    /// does nothing, and nothing is downloaded unless the CDN still serves
+    /// this version.
+    pub pinned_version: Option<String>,
+    /// How long to wait for a lock held by another process sharing the
+    /// cache directory before failing with [`MtgjsonError::LockTimeout`].
+    /// Defaults to 5 minutes.
+    pub lock_timeout: Duration,
+    /// Maximum size of the cache directory in bytes, as reported by
+    /// [`stats`](Self::stats). `None` (the default) means unlimited.
+    ///
+    /// When a download or refresh takes the cache past the quota, the least
+    /// recently used files and archived versions are evicted until it fits
+    /// again; see [`enforce_quota`](Self::enforce_quota).
+    pub quota: Option<u64>,
+    /// Called as files are downloaded: once when the response arrives, then
+    /// after every chunk written. A download that is retried starts
+    /// reporting again from where it resumed.
+    pub download_progress: Option<DownloadProgressFn>,
+    /// How many files [`ensure_parquets`](Self::ensure_parquets) downloads
+    /// at once. Defaults to 4.
+    pub download_concurrency: usize,
+    views_lock: RefCell<Option<FileLock>>,
+    view_files: RefCell<HashSet<PathBuf>>,
+    attached_databases: RefCell<HashSet<PathBuf>>,
     timeout: Duration,
     client: Option<Client>,
     remote_ver: Option<String>,
@@ -38,6 +302,19 @@ impl CacheManager {
         Ok(Self {
             cache_dir: dir,
             offline,
+            cdn_base: config::CDN_BASE.to_string(),
+            mirrors: Vec::new(),
+            max_retries: 3,
+            retry_backoff: Duration::from_secs(1),
+            keep_versions: 2,
+            pinned_version: None,
+            lock_timeout: Duration::from_secs(300),
+            quota: None,
+            download_progress: None,
+            download_concurrency: 4,
+            views_lock: RefCell::new(None),
+            view_files: RefCell::new(HashSet::new()),
+            attached_databases: RefCell::new(HashSet::new()),
             timeout,
             client: None,
             remote_ver: None,
@@ -58,8 +335,63 @@ impl CacheManager {
         self.client.as_ref().unwrap()
     }
 
+    /// Base URLs to try in order: [`cdn_base`](Self::cdn_base), then each of
+    /// [`mirrors`](Self::mirrors), without trailing slashes.
+    fn bases(&self) -> Vec<String> {
+        std::iter::once(&self.cdn_base)
+            .chain(&self.mirrors)
+            .map(|base| base.trim_end_matches('/').to_string())
+            .collect()
+    }
+
+    /// Take the lock file `name` under `locks/`, waiting up to
+    /// [`lock_timeout`](Self::lock_timeout).
+    fn lock(&self, name: &str, mode: LockMode) -> Result<FileLock> {
+        let path = self.cache_dir.join(LOCKS_DIR).join(name);
+        lock::acquire(&path, mode, self.lock_timeout)
+    }
+
+    /// Record that `path` backs a registered view, so quota eviction skips
+    /// it, and hold a shared lock on the cache for as long as this cache
+    /// manager lives, so [`clear`](Self::clear) in other processes waits
+    /// instead of deleting files that back registered views.
+    ///
+    /// Called by [`Connection`](crate::Connection) when it registers a view.
+    pub(crate) fn hold_views(&self, path: &Path) -> Result<()> {
+        self.hold_views_lock()?;
+        self.view_files.borrow_mut().insert(path.to_path_buf());
+        Ok(())
+    }
+
+    /// Record that the materialized database at `path` is attached, so
+    /// quota eviction skips it, holding the same lock as
+    /// [`hold_views`](Self::hold_views).
+    ///
+    /// Called by [`Connection`](crate::Connection) when it attaches the
+    /// database; [`prune_databases`](Self::prune_databases) forgets the
+    /// databases it detached.
+    pub(crate) fn hold_database(&self, path: &Path) -> Result<()> {
+        self.hold_views_lock()?;
+        self.attached_databases.borrow_mut().insert(path.to_path_buf());
+        Ok(())
+    }
+
+    fn hold_views_lock(&self) -> Result<()> {
+        if self.views_lock.borrow().is_none() {
+            let held = self.lock(VIEWS_LOCK, LockMode::Shared)?;
+            *self.views_lock.borrow_mut() = Some(held);
+        }
+        Ok(())
+    }
+
+    /// Forget the files recorded by [`hold_views`](Self::hold_views), once
+    /// their views are no longer registered.
+    pub(crate) fn release_views(&self) {
+        self.view_files.borrow_mut().clear();
+    }
+
     /// Read the locally cached version string from `version.txt`.
-    fn local_version(&self) -> Option<String> {
+    pub(crate) fn local_version(&self) -> Option<String> {
         let version_file = self.cache_dir.join("version.txt");
         if version_file.exists() {
             fs::read_to_string(&version_file)
@@ -78,8 +410,9 @@ impl CacheManager {
 
     /// Fetch the current MTGJSON version from Meta.json on the CDN.
     ///
-    /// Returns the version string (e.g. `"5.2.2+20240101"`), or `None` if
-    /// offline or the CDN is unreachable. Caches the result for subsequent calls.
+    /// The CDN base and then each mirror are tried in order. Returns the
+    /// version string (e.g. `"5.2.2+20240101"`), or `None` if offline or no
+    /// base URL answered. Caches the result for subsequent calls.
     pub fn remote_version(&mut self) -> Result<Option<String>> {
         if self.remote_ver.is_some() {
             return Ok(self.remote_ver.clone());
@@ -88,29 +421,17 @@ impl CacheManager {
             return Ok(None);
         }
         let client = self.client().clone();
-        match client.get(config::META_URL).send() {
-            Ok(resp) => {
-                let resp = resp.error_for_status()?;
-                let data: serde_json::Value = resp.json()?;
-                // Try data.version first, then meta.version
-                let version = data
-                    .get("data")
-                    .and_then(|d| d.get("version"))
-                    .and_then(|v| v.as_str())
-                    .or_else(|| {
-                        data.get("meta")
-                            .and_then(|m| m.get("version"))
-                            .and_then(|v| v.as_str())
-                    })
-                    .map(|s| s.to_string());
-                self.remote_ver = version.clone();
-                Ok(version)
-            }
-            Err(e) => {
-                eprintln!("Failed to fetch MTGJSON version from CDN: {}", e);
-                Ok(None)
+        for base in self.bases() {
+            let url = format!("{}/Meta.json", base);
+            match fetch_version(&client, &url) {
+                Ok(version) => {
+                    self.remote_ver = version.clone();
+                    return Ok(version);
+                }
+                Err(e) => warn!(url = url, "Failed to fetch MTGJSON version: {}", e),
             }
         }
+        Ok(None)
     }
 
     /// Check if local cache is out of date compared to the CDN.
@@ -131,42 +452,169 @@ impl CacheManager {
         }
     }
 
-    /// Download a single file from the CDN.
+    /// Read the per-file manifest. A missing or unreadable manifest is empty.
+    pub fn manifest(&self) -> CacheManifest {
+        read_manifest(&self.cache_dir)
+    }
+
+    /// Write the per-file manifest via a temp file and rename.
+    fn save_manifest(&self, manifest: &CacheManifest) -> Result<()> {
+        let path = self.cache_dir.join(MANIFEST_FILE);
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_vec_pretty(manifest)?)?;
+        fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    /// Check if one cached file is out of date compared to the CDN.
     ///
-    /// Downloads to a temp file first and renames on success, so an
-    /// interrupted download never leaves a corrupt partial file behind.
-    fn download_file(&mut self, filename: &str, dest: &Path) -> Result<()> {
-        let url = format!("{}/{}", config::CDN_BASE, filename);
-        eprintln!("Downloading {}", url);
+    /// Files without a recorded version fall back to [`is_stale`](Self::is_stale).
+    fn is_file_stale(&mut self, filename: &str) -> Result<bool> {
+        let recorded = self
+            .manifest()
+            .files
+            .remove(filename)
+            .and_then(|r| r.version);
+        match recorded {
+            Some(version) => Ok(self.remote_version()?.is_some_and(|remote| remote != version)),
+            None => self.is_stale(),
+        }
+    }
 
-        if let Some(parent) = dest.parent() {
-            fs::create_dir_all(parent)?;
+    /// Download a single file from the CDN, falling back to each mirror in
+    /// turn. The error from the last base URL tried is returned.
+    ///
+    /// The response is streamed to a temp file and renamed on success, so an
+    /// interrupted download never leaves a corrupt partial file behind.
+    /// Transient failures (connection errors, timeouts, 5xx and 429
+    /// responses) are retried up to [`max_retries`](Self::max_retries) times
+    /// per base URL with exponential backoff, and a partial temp file left by
+    /// an earlier attempt is resumed with an HTTP `Range` request.
+    ///
+    /// The bytes are checked against the file's published SHA-256 checksum,
+    /// which is then saved next to `dest`; a mismatch fails with
+    /// [`MtgjsonError::Integrity`] and nothing is written.
+    ///
+    /// If `dest` exists and has a manifest record, the request is made
+    /// conditional on its ETag / Last-Modified. Returns `false` if the server
+    /// answered `304 Not Modified`. Either way the manifest record and
+    /// `version.txt` are updated to the current remote version.
+    ///
+    /// Only one process downloads a given file at a time. A process that had
+    /// to wait for the lock skips the download, returning `false`, if the
+    /// file is current for the remote version by then.
+    fn download_file(&mut self, filename: &str, dest: &Path) -> Result<bool> {
+        let _span = span!("download", file = filename);
+        let _cache = self.lock(CACHE_LOCK, LockMode::Shared)?;
+        let remote = self.remote_version()?;
+        let (_file, download) = self.fetcher().fetch_locked(filename, dest, remote.as_deref())?;
+        match download {
+            Download::Current => Ok(false),
+            Download::Fetched(validators) => self.record_download(filename, dest, validators),
         }
+    }
 
-        let tmp_dest = dest.with_extension(
-            format!(
-                "{}.tmp",
-                dest.extension()
-                    .and_then(|e| e.to_str())
-                    .unwrap_or("")
-            ),
-        );
+    /// Download several files at once, each as by
+    /// [`download_file`](Self::download_file), on up to
+    /// [`download_concurrency`](Self::download_concurrency) threads.
+    ///
+    /// Every file is attempted; the successful downloads are recorded even if
+    /// others failed, and the first failure (in `filenames` order) is
+    /// returned.
+    fn download_files(&mut self, filenames: &[&str]) -> Result<()> {
+        let _cache = self.lock(CACHE_LOCK, LockMode::Shared)?;
+        let remote = self.remote_version()?;
+        let fetcher = self.fetcher();
+        let queue = Mutex::new(filenames.iter().enumerate());
+        let results = Mutex::new(Vec::with_capacity(filenames.len()));
+        std::thread::scope(|scope| {
+            for _ in 0..self.download_concurrency.clamp(1, filenames.len().max(1)) {
+                scope.spawn(|| loop {
+                    let Some((i, filename)) = queue.lock().unwrap().next() else {
+                        break;
+                    };
+                    let _span = span!("download", file = filename);
+                    let dest = fetcher.cache_dir.join(filename);
+                    let result = fetcher.fetch_locked(filename, &dest, remote.as_deref());
+                    results.lock().unwrap().push((i, result));
+                });
+            }
+        });
 
-        let client = self.client().clone();
-        let result = (|| -> Result<()> {
-            let resp = client.get(&url).send()?.error_for_status()?;
-            let bytes = resp.bytes()?;
-            fs::write(&tmp_dest, &bytes)?;
-            fs::rename(&tmp_dest, dest)?;
-            Ok(())
-        })();
+        let mut results = results.into_inner().unwrap();
+        results.sort_by_key(|(i, _)| *i);
+        let mut first_err = None;
+        for (i, result) in results {
+            let filename = filenames[i];
+            // The file lock is held until the download is recorded
+            let recorded = result.and_then(|(_file, download)| match download {
+                Download::Current => Ok(false),
+                Download::Fetched(validators) => {
+                    let dest = self.cache_dir.join(filename);
+                    self.record_download(filename, &dest, validators)
+                }
+            });
+            if let Err(e) = recorded {
+                first_err.get_or_insert(e);
+            }
+        }
+        first_err.map_or(Ok(()), Err)
+    }
 
-        if result.is_err() {
-            // Clean up partial temp file on any error
-            let _ = fs::remove_file(&tmp_dest);
+    /// What the download threads need from this cache manager.
+    fn fetcher(&mut self) -> Fetcher {
+        Fetcher {
+            client: self.client().clone(),
+            bases: self.bases(),
+            max_retries: self.max_retries,
+            retry_backoff: self.retry_backoff,
+            progress: self.download_progress.clone(),
+            cache_dir: self.cache_dir.clone(),
+            lock_timeout: self.lock_timeout,
         }
+    }
 
-        result
+    /// Update the manifest after [`download_file`](Self::download_file);
+    /// `validators` is `None` for a `304 Not Modified` response.
+    fn record_download(
+        &mut self,
+        filename: &str,
+        dest: &Path,
+        validators: Option<Validators>,
+    ) -> Result<bool> {
+        let version = self.remote_version().ok().flatten();
+        let _manifest = self.lock(MANIFEST_LOCK, LockMode::Exclusive)?;
+        let mut manifest = self.manifest();
+        let modified = validators.is_some();
+        match validators {
+            Some(v) => {
+                manifest.files.insert(
+                    filename.to_string(),
+                    FileRecord {
+                        version: version.clone(),
+                        size: fs::metadata(dest)?.len(),
+                        etag: v.etag,
+                        last_modified: v.last_modified,
+                        downloaded_at: unix_now(),
+                        last_accessed: manifest
+                            .files
+                            .get(filename)
+                            .and_then(|r| r.last_accessed),
+                    },
+                );
+            }
+            None => {
+                debug!(file = filename, "Not modified");
+                if let Some(record) = manifest.files.get_mut(filename) {
+                    record.version = version.clone();
+                }
+            }
+        }
+        self.save_manifest(&manifest)?;
+        if let Some(version) = &version {
+            self.save_version(version);
+        }
+        Ok(modified)
     }
 
     /// Ensure a parquet file is cached locally, downloading if needed.
@@ -184,26 +632,49 @@ impl CacheManager {
             MtgjsonError::NotFound(format!("Unknown parquet view: {}", view_name))
         })?;
 
-        let local_path = self.cache_dir.join(filename);
+        self.ensure_file(filename, "Parquet file")
+    }
+
+    /// Ensure several parquet files are cached locally, downloading the
+    /// missing or stale ones concurrently.
+    ///
+    /// Up to [`download_concurrency`](Self::download_concurrency) files are
+    /// downloaded at once, each through the same temp-file-and-rename path
+    /// as [`ensure_parquet`](Self::ensure_parquet). If any download fails,
+    /// the others still complete and the first error is returned.
+    ///
+    /// # Returns
+    ///
+    /// Local filesystem paths to the cached parquet files, in the order of
+    /// `view_names`.
+    pub fn ensure_parquets(&mut self, view_names: &[&str]) -> Result<Vec<PathBuf>> {
+        let parquet_files = config::parquet_files();
+        let filenames = view_names
+            .iter()
+            .map(|view_name| {
+                parquet_files.get(view_name).copied().ok_or_else(|| {
+                    MtgjsonError::NotFound(format!("Unknown parquet view: {}", view_name))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
 
-        if !local_path.exists() || self.is_stale()? {
-            if self.offline {
-                if local_path.exists() {
-                    return Ok(local_path);
+        if !self.offline && self.pinned_version.is_none() {
+            let mut missing = Vec::new();
+            for filename in &filenames {
+                let stale = !self.cache_dir.join(filename).exists() || self.is_file_stale(filename)?;
+                if stale && !missing.contains(filename) {
+                    missing.push(*filename);
                 }
-                return Err(MtgjsonError::NotFound(format!(
-                    "Parquet file {} not cached and offline mode is enabled",
-                    filename
-                )));
             }
-            self.download_file(filename, &local_path)?;
-            // Update version after successful download
-            if let Ok(Some(version)) = self.remote_version() {
-                self.save_version(&version);
+            if missing.len() > 1 {
+                self.download_files(&missing)?;
+                self.evict_over_quota(&filenames)?;
             }
         }
-
-        Ok(local_path)
+        filenames
+            .iter()
+            .map(|filename| self.ensure_file(filename, "Parquet file"))
+            .collect()
     }
 
     /// Ensure a JSON file is cached locally, downloading if needed.
@@ -221,80 +692,1476 @@ impl CacheManager {
             MtgjsonError::NotFound(format!("Unknown JSON file: {}", name))
         })?;
 
+        self.ensure_file(filename, "JSON file")
+    }
+
+    /// Ensure an individual deck file is cached locally, downloading if needed.
+    ///
+    /// Deck files live under `decks/` on the CDN and share the cache's version
+    /// tracking with the parquet and JSON files.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_name` - The deck's `fileName` from `DeckList.json`
+    ///   (e.g. `"NecronDynasties_40K"`), without the `.json` extension.
+    ///
+    /// # Returns
+    ///
+    /// Local filesystem path to the cached deck file.
+    pub fn ensure_deck(&mut self, file_name: &str) -> Result<PathBuf> {
+        let filename = config::deck_file(file_name).ok_or_else(|| {
+            MtgjsonError::InvalidArgument(format!("Invalid deck file name: {:?}", file_name))
+        })?;
+        self.ensure_file(&filename, "Deck file")
+    }
+
+    /// Shared lookup for `ensure_*`: return the cached path for `filename`,
+    /// downloading it first when missing or stale.
+    ///
+    /// `kind` is only used in the offline error message.
+    fn ensure_file(&mut self, filename: &str, kind: &str) -> Result<PathBuf> {
+        if let Some(version) = self.pinned_version.clone() {
+            return self.version_file(filename, &version);
+        }
         let local_path = self.cache_dir.join(filename);
 
-        if !local_path.exists() || self.is_stale()? {
-            if self.offline {
-                if local_path.exists() {
-                    return Ok(local_path);
-                }
+        if !local_path.exists() || self.is_file_stale(filename)? {
+            if !self.offline {
+                self.download_file(filename, &local_path)?;
+                self.evict_over_quota(&[filename])?;
+            } else if !local_path.exists() {
                 return Err(MtgjsonError::NotFound(format!(
-                    "JSON file {} not cached and offline mode is enabled",
-                    filename
+                    "{} {} not cached and offline mode is enabled",
+                    kind, filename
                 )));
             }
-            self.download_file(filename, &local_path)?;
-            // Update version after successful download
-            if let Ok(Some(version)) = self.remote_version() {
-                self.save_version(&version);
-            }
         }
 
+        self.touch(filename)?;
         Ok(local_path)
     }
 
+    /// Record that `filename` was just served from the cache, for LRU
+    /// eviction. Files without a manifest record are left alone.
+    fn touch(&self, filename: &str) -> Result<()> {
+        let now = unix_now();
+        let current = |manifest: &CacheManifest| {
+            manifest
+                .files
+                .get(filename)
+                .is_none_or(|r| r.last_accessed == Some(now))
+        };
+        if current(&self.manifest()) {
+            return Ok(());
+        }
+        let _manifest = self.lock(MANIFEST_LOCK, LockMode::Exclusive)?;
+        let mut manifest = self.manifest();
+        if current(&manifest) {
+            return Ok(());
+        }
+        if let Some(record) = manifest.files.get_mut(filename) {
+            record.last_accessed = Some(now);
+        }
+        self.save_manifest(&manifest)
+    }
+
     /// Load and parse a JSON file (handles `.gz` transparently).
     ///
     /// If the cached file is corrupt (truncated download, disk error),
     /// it is deleted automatically so the next call re-downloads a fresh copy.
     pub fn load_json(&mut self, name: &str) -> Result<serde_json::Value> {
         let path = self.ensure_json(name)?;
+        read_json_file(&path)
+    }
 
-        let parse_result = if path.extension().and_then(|e| e.to_str()) == Some("gz") {
-            let file = fs::File::open(&path)?;
-            let reader = BufReader::new(file);
-            let decoder = GzDecoder::new(reader);
-            let mut buf_reader = BufReader::new(decoder);
-            let mut contents = String::new();
-            buf_reader.read_to_string(&mut contents)?;
-            serde_json::from_str(&contents).map_err(MtgjsonError::from)
-        } else {
-            let contents = fs::read_to_string(&path)?;
-            serde_json::from_str(&contents).map_err(MtgjsonError::from)
+    /// Load and parse an individual deck file.
+    ///
+    /// Corrupt cached deck files are removed the same way as in
+    /// [`load_json`](Self::load_json).
+    pub fn load_deck(&mut self, file_name: &str) -> Result<serde_json::Value> {
+        let path = self.ensure_deck(file_name)?;
+        read_json_file(&path)
+    }
+
+    /// Remove all cached data files.
+    ///
+    /// The collection database ([`config::COLLECTION_FILE`] and its WAL) is
+    /// kept. Waits for other processes to finish their downloads and to drop
+    /// any views over the cached files, failing with
+    /// [`MtgjsonError::LockTimeout`] after [`lock_timeout`](Self::lock_timeout).
+    pub fn clear(&self) -> Result<()> {
+        if !self.cache_dir.exists() {
+            return Ok(());
+        }
+        // Views registered through this cache manager are being cleared too
+        self.views_lock.borrow_mut().take();
+        let _views = self.lock(VIEWS_LOCK, LockMode::Exclusive)?;
+        let _cache = self.lock(CACHE_LOCK, LockMode::Exclusive)?;
+        for entry in fs::read_dir(&self.cache_dir)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            // The collection database is user data, not a cached download,
+            // and lock files must outlive their holders
+            if name
+                .to_str()
+                .is_some_and(|n| n.starts_with(config::COLLECTION_FILE) || n == LOCKS_DIR)
+            {
+                continue;
+            }
+            if entry.file_type()?.is_dir() {
+                fs::remove_dir_all(entry.path())?;
+            } else {
+                fs::remove_file(entry.path())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Re-check the CDN version and re-fetch the cached files that are not
+    /// current for it.
+    ///
+    /// Only files already in the cache are considered; files recorded at the
+    /// current version are skipped, and the rest are requested with
+    /// conditional GETs so unchanged files are not downloaded again. Does
+    /// nothing when offline or when the CDN cannot be reached.
+    ///
+    /// Changed files are downloaded into a staging directory while the cached
+    /// files stay in place, and are swapped in only after every download
+    /// succeeded; if any download fails the cache is left as it was. The
+    /// files of the previous version, changed or not, are archived under
+    /// `versions/<version>/` for [`rollback`](Self::rollback) and
+    /// [`pinned_version`](Self::pinned_version), keeping at most
+    /// [`keep_versions`](Self::keep_versions) archives. Does nothing for a
+    /// pinned cache.
+    ///
+    /// # Returns
+    ///
+    /// Paths (relative to the cache directory) of the files whose contents
+    /// changed.
+    pub fn refresh(&mut self) -> Result<Vec<String>> {
+        if self.offline || self.pinned_version.is_some() {
+            return Ok(Vec::new());
+        }
+        self.remote_ver = None;
+        let Some(remote) = self.remote_version()? else {
+            return Ok(Vec::new());
         };
+        let _span = span!("refresh", version = remote);
 
-        match parse_result {
-            Ok(value) => Ok(value),
-            Err(e) => {
-                eprintln!(
-                    "Corrupt cache file {}: {} -- removing",
-                    path.display(),
-                    e
-                );
-                let _ = fs::remove_file(&path);
-                Err(MtgjsonError::NotFound(format!(
-                    "Cache file '{}' was corrupt and has been removed. \
-                     Retry to re-download. Original error: {}",
-                    path.file_name()
-                        .and_then(|n| n.to_str())
-                        .unwrap_or("unknown"),
-                    e
-                )))
+        let _refresh = self.lock(REFRESH_LOCK, LockMode::Exclusive)?;
+        let cache_lock = self.lock(CACHE_LOCK, LockMode::Shared)?;
+        let manifest = self.manifest();
+        let local = self.local_version();
+        let staging = self.cache_dir.join(STAGING_DIR);
+        let fetcher = self.fetcher();
+        let mut staged = Vec::new();
+        let mut unchanged = Vec::new();
+        for (rel, path) in self.cached_files()? {
+            if rel.ends_with(CHECKSUM_SUFFIX) {
+                continue;
+            }
+            let record = manifest.files.get(&rel).cloned();
+            let recorded = match &record {
+                Some(record) => record.version.as_ref(),
+                None => local.as_ref(),
+            };
+            if recorded == Some(&remote) {
+                continue;
+            }
+            // Partial downloads stay in the staging directory on failure so
+            // the next refresh can resume them
+            let cached = record.filter(|_| path.exists());
+            let _file = self.lock(&file_lock(&rel), LockMode::Exclusive)?;
+            match fetcher.fetch(&rel, &staging.join(&rel), cached.as_ref())? {
+                Some(validators) => staged.push((rel, validators)),
+                None => unchanged.push(rel),
+            }
+        }
+
+        // Swap under the exclusive lock, with the manifest as other
+        // processes may have updated it while the files were staged
+        drop(cache_lock);
+        let cache_lock = self.lock(CACHE_LOCK, LockMode::Exclusive)?;
+        let mut manifest = self.manifest();
+        if !staged.is_empty() || !unchanged.is_empty() {
+            let archive = self
+                .cache_dir
+                .join(VERSIONS_DIR)
+                .join(local.as_deref().unwrap_or(UNVERSIONED));
+            fs::create_dir_all(&archive)?;
+            fs::write(archive.join(MANIFEST_FILE), serde_json::to_vec_pretty(&manifest)?)?;
+            let replaced = staged.iter().map(|(rel, _)| rel).chain(&unchanged);
+            for rel in replaced {
+                let live = self.cache_dir.join(rel);
+                archive_file(&live, &archive.join(rel))?;
+                let sidecar = checksum_path(&live);
+                if sidecar.exists() {
+                    archive_file(&sidecar, &checksum_path(&archive.join(rel)))?;
+                }
+            }
+        }
+
+        let mut updated = Vec::with_capacity(staged.len());
+        for (rel, validators) in staged {
+            let live = self.cache_dir.join(&rel);
+            let staged_path = staging.join(&rel);
+            fs::rename(&staged_path, &live)?;
+            let sidecar = checksum_path(&staged_path);
+            if sidecar.exists() {
+                fs::rename(&sidecar, checksum_path(&live))?;
+            } else {
+                let _ = fs::remove_file(checksum_path(&live));
+            }
+            manifest.files.insert(
+                rel.clone(),
+                FileRecord {
+                    version: Some(remote.clone()),
+                    size: fs::metadata(&live)?.len(),
+                    etag: validators.etag,
+                    last_modified: validators.last_modified,
+                    downloaded_at: unix_now(),
+                    last_accessed: manifest.files.get(&rel).and_then(|r| r.last_accessed),
+                },
+            );
+            updated.push(rel);
+        }
+        for rel in unchanged {
+            debug!(file = rel, "Not modified");
+            if let Some(record) = manifest.files.get_mut(&rel) {
+                record.version = Some(remote.clone());
             }
         }
+        self.save_manifest(&manifest)?;
+        self.save_version(&remote);
+        let _ = fs::remove_dir_all(&staging);
+        self.prune_versions()?;
+        drop(cache_lock);
+        self.evict_over_quota(&[])?;
+        Ok(updated)
     }
 
-    /// Remove all cached files and recreate the cache directory.
-    pub fn clear(&self) -> Result<()> {
-        if self.cache_dir.exists() {
-            fs::remove_dir_all(&self.cache_dir)?;
-            fs::create_dir_all(&self.cache_dir)?;
+    /// Versions archived by [`refresh`](Self::refresh) and available to
+    /// [`rollback`](Self::rollback), most recently archived first.
+    pub fn archived_versions(&self) -> Result<Vec<String>> {
+        let dir = self.cache_dir.join(VERSIONS_DIR);
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut versions = Vec::new();
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let Some(name) = entry.file_name().to_str().map(String::from) else {
+                continue;
+            };
+            // The archive's manifest is written when the archive is created
+            let archived_at = fs::metadata(entry.path().join(MANIFEST_FILE))
+                .and_then(|m| m.modified())
+                .unwrap_or(UNIX_EPOCH);
+            versions.push((archived_at, name));
+        }
+        versions.sort_by(|a, b| b.cmp(a));
+        Ok(versions.into_iter().map(|(_, name)| name).collect())
+    }
+
+    /// Directory holding the archived files of `version`, whether or not it
+    /// exists.
+    ///
+    /// Fails with [`MtgjsonError::InvalidArgument`] if `version` is not a
+    /// plain version string (e.g. `"5.2.2+20240101"`).
+    pub fn version_dir(&self, version: &str) -> Result<PathBuf> {
+        if !is_plain_version(version) {
+            return Err(MtgjsonError::InvalidArgument(format!(
+                "Invalid MTGJSON version: {:?}",
+                version
+            )));
+        }
+        Ok(self.cache_dir.join(VERSIONS_DIR).join(version))
+    }
+
+    /// Local path of `filename` (relative to the cache directory) as of
+    /// MTGJSON `version`.
+    ///
+    /// Looks in the version's archive first, then at the current cache if
+    /// its copy is recorded at `version`. Missing files are downloaded only
+    /// if the CDN still serves `version`; otherwise this fails with
+    /// [`MtgjsonError::NotFound`].
+    pub fn version_file(&mut self, filename: &str, version: &str) -> Result<PathBuf> {
+        let archived = self.version_dir(version)?.join(filename);
+        if archived.exists() {
+            return Ok(archived);
+        }
+
+        let live = self.cache_dir.join(filename);
+        let recorded = match self.manifest().files.remove(filename) {
+            Some(record) => record.version,
+            None => self.local_version(),
+        };
+        if live.exists() && recorded.as_deref() == Some(version) {
+            return Ok(live);
+        }
+        if !self.offline && self.remote_version()?.as_deref() == Some(version) {
+            self.download_file(filename, &live)?;
+            return Ok(live);
+        }
+        Err(MtgjsonError::NotFound(format!(
+            "{} is not cached for MTGJSON version {}",
+            filename, version
+        )))
+    }
+
+    /// Path of the database views are materialized into for the MTGJSON
+    /// version this cache serves: the pinned version, or else the version of
+    /// the cached files. `None` until that version is known, i.e. before the
+    /// first download.
+    ///
+    /// See [`MtgjsonSdkBuilder::materialize`](crate::MtgjsonSdkBuilder::materialize).
+    pub fn database_path(&self) -> Option<PathBuf> {
+        let version = self
+            .pinned_version
+            .clone()
+            .or_else(|| self.local_version())
+            .filter(|v| is_plain_version(v))?;
+        Some(
+            self.cache_dir
+                .join(DATABASES_DIR)
+                .join(format!("{}.duckdb", version)),
+        )
+    }
+
+    /// Delete the materialized databases (and their write-ahead logs) of
+    /// every version but the one at `keep`, which is the only one still
+    /// attached. Databases still open in other processes may fail to delete
+    /// and are left for a later call.
+    pub(crate) fn prune_databases(&self, keep: &Path) {
+        self.attached_databases.borrow_mut().retain(|p| p == keep);
+        let Ok(entries) = fs::read_dir(self.cache_dir.join(DATABASES_DIR)) else {
+            return;
+        };
+        let keep = keep.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name();
+            if !name.to_str().is_some_and(|n| n.starts_with(keep)) {
+                match fs::remove_file(&path) {
+                    Ok(()) => debug!(file = path.display(), "Removed materialized database"),
+                    Err(e) => debug!(file = path.display(), "Could not remove database: {}", e),
+                }
+            }
+        }
+    }
+
+    /// Delete the oldest archived versions beyond
+    /// [`keep_versions`](Self::keep_versions).
+    fn prune_versions(&self) -> Result<()> {
+        let dir = self.cache_dir.join(VERSIONS_DIR);
+        for version in self.archived_versions()?.iter().skip(self.keep_versions) {
+            fs::remove_dir_all(dir.join(version))?;
         }
         Ok(())
     }
 
+    /// Restore the files archived by the most recent
+    /// [`refresh`](Self::refresh) and remove them from the archive.
+    ///
+    /// The restored version is treated as current by this cache manager
+    /// until the next [`refresh`](Self::refresh); a new cache manager
+    /// revalidates against the CDN as usual. Views already registered on a
+    /// [`Connection`](crate::Connection) must be reloaded with
+    /// [`reload_views`](crate::Connection::reload_views).
+    ///
+    /// # Returns
+    ///
+    /// The restored version, or `None` if no version was archived.
+    pub fn rollback(&mut self) -> Result<Option<String>> {
+        let _refresh = self.lock(REFRESH_LOCK, LockMode::Exclusive)?;
+        let _cache = self.lock(CACHE_LOCK, LockMode::Exclusive)?;
+        let Some(version) = self.archived_versions()?.into_iter().next() else {
+            return Ok(None);
+        };
+        let archive = self.cache_dir.join(VERSIONS_DIR).join(&version);
+        let archived = read_manifest(&archive);
+
+        let mut files = Vec::new();
+        walk_files(&archive, "", &mut files)?;
+        files.retain(|(rel, _)| rel != MANIFEST_FILE);
+        for (rel, _) in &files {
+            if !rel.ends_with(CHECKSUM_SUFFIX) {
+                let _ = fs::remove_file(checksum_path(&self.cache_dir.join(rel)));
+            }
+        }
+        for (rel, path) in &files {
+            let live = self.cache_dir.join(rel);
+            if let Some(parent) = live.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::rename(path, live)?;
+        }
+
+        let mut manifest = self.manifest();
+        manifest.files.extend(archived.files);
+        self.save_manifest(&manifest)?;
+        fs::remove_dir_all(&archive)?;
+        if version != UNVERSIONED {
+            self.save_version(&version);
+            self.remote_ver = Some(version.clone());
+        }
+        info!(version = version, "Rolled back {} cached file(s)", files.len());
+        Ok(Some(version))
+    }
+
+    // -----------------------------------------------------------------------
+    // Prefetch
+    // -----------------------------------------------------------------------
+
+    /// Cache the given parquet views and JSON files up front, downloading any
+    /// that are missing or stale.
+    ///
+    /// `names` are logical names from [`config::parquet_files`] or
+    /// [`config::json_files`]. `progress` is called after each file is cached.
+    /// Unknown names fail with [`MtgjsonError::NotFound`] before anything is
+    /// downloaded.
+    ///
+    /// # Returns
+    ///
+    /// Local paths of the cached files, in the order of `names`.
+    pub fn prefetch<F>(&mut self, names: &[&str], mut progress: F) -> Result<Vec<PathBuf>>
+    where
+        F: FnMut(&PrefetchProgress<'_>),
+    {
+        let parquet_files = config::parquet_files();
+        let json_files = config::json_files();
+        let files = names
+            .iter()
+            .map(|name| {
+                if let Some(filename) = parquet_files.get(name) {
+                    Ok((*name, *filename, "Parquet file"))
+                } else if let Some(filename) = json_files.get(name) {
+                    Ok((*name, *filename, "JSON file"))
+                } else {
+                    Err(MtgjsonError::NotFound(format!(
+                        "Unknown parquet view or JSON file: {}",
+                        name
+                    )))
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let total = files.len();
+        let mut paths = Vec::with_capacity(total);
+        for (i, (name, filename, kind)) in files.into_iter().enumerate() {
+            let path = self.ensure_file(filename, kind)?;
+            progress(&PrefetchProgress {
+                name,
+                path: &path,
+                completed: i + 1,
+                total,
+            });
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+
+    /// Cache every file in [`config::parquet_files`] and
+    /// [`config::json_files`], in name order.
+    ///
+    /// Individual deck files are not included; see
+    /// [`ensure_deck`](Self::ensure_deck).
+    pub fn prefetch_all<F>(&mut self, progress: F) -> Result<Vec<PathBuf>>
+    where
+        F: FnMut(&PrefetchProgress<'_>),
+    {
+        let mut names: Vec<&str> = config::parquet_files()
+            .into_keys()
+            .chain(config::json_files().into_keys())
+            .collect();
+        names.sort_unstable();
+        self.prefetch(&names, progress)
+    }
+
+    // -----------------------------------------------------------------------
+    // Bundles
+    // -----------------------------------------------------------------------
+
+    /// Cached data files as `(relative path, absolute path)`, sorted.
+    ///
+    /// Skips `version.txt`, the per-file manifest, the collection database,
+    /// the staging, archived-version, materialized-database and lock
+    /// directories and leftover `.tmp` downloads.
+    fn cached_files(&self) -> Result<Vec<(String, PathBuf)>> {
+        let mut files = Vec::new();
+        walk_files(&self.cache_dir, "", &mut files)?;
+        let staging = format!("{}/", STAGING_DIR);
+        let versions = format!("{}/", VERSIONS_DIR);
+        let locks = format!("{}/", LOCKS_DIR);
+        let databases = format!("{}/", DATABASES_DIR);
+        files.retain(|(rel, _)| {
+            rel != "version.txt"
+                && rel != MANIFEST_FILE
+                && !rel.starts_with(config::COLLECTION_FILE)
+                && !rel.starts_with(&staging)
+                && !rel.starts_with(&versions)
+                && !rel.starts_with(&locks)
+                && !rel.starts_with(&databases)
+        });
+        files.sort();
+        Ok(files)
+    }
+
+    /// Write every cached data file to a tar archive at `dest`, preceded by a
+    /// [`BundleManifest`] recording the cached MTGJSON version.
+    ///
+    /// The collection database is not included. Load the bundle elsewhere
+    /// with [`import_bundle`](Self::import_bundle) or
+    /// [`MtgjsonSdkBuilder::bundle`](crate::MtgjsonSdkBuilder::bundle).
+    pub fn export_bundle<P: AsRef<Path>>(&self, dest: P) -> Result<BundleManifest> {
+        let files = self.cached_files()?;
+        let manifest = BundleManifest {
+            format: BUNDLE_FORMAT,
+            version: self.local_version(),
+            files: files
+                .iter()
+                .map(|(rel, path)| {
+                    Ok(BundleFile {
+                        path: rel.clone(),
+                        size: fs::metadata(path)?.len(),
+                    })
+                })
+                .collect::<Result<_>>()?,
+        };
+
+        let mut tar = tar::Builder::new(fs::File::create(dest.as_ref())?);
+        let manifest_json = serde_json::to_vec_pretty(&manifest)?;
+        let mut header = tar::Header::new_gnu();
+        header.set_size(manifest_json.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        tar.append_data(&mut header, BUNDLE_MANIFEST, manifest_json.as_slice())?;
+        for (rel, path) in &files {
+            tar.append_path_with_name(path, rel)?;
+        }
+        tar.into_inner()?;
+        Ok(manifest)
+    }
+
+    /// Read the manifest of a bundle written by
+    /// [`export_bundle`](Self::export_bundle) without extracting it.
+    pub fn read_bundle_manifest<P: AsRef<Path>>(bundle: P) -> Result<BundleManifest> {
+        let mut archive = tar::Archive::new(fs::File::open(bundle.as_ref())?);
+        let mut entries = archive.entries()?;
+        read_manifest_entry(entries.next(), bundle.as_ref())
+    }
+
+    /// Extract a bundle into the cache directory, replacing cached files and
+    /// `version.txt` with the bundled ones.
+    ///
+    /// Files are unpacked to a staging directory and checked against the
+    /// manifest before anything in the cache is replaced. Entries not listed
+    /// in the manifest, and manifest paths that are absolute or contain `..`,
+    /// are rejected.
+    pub fn import_bundle<P: AsRef<Path>>(&mut self, bundle: P) -> Result<BundleManifest> {
+        let bundle = bundle.as_ref();
+        let invalid = |msg: String| {
+            MtgjsonError::InvalidArgument(format!("Invalid bundle {}: {}", bundle.display(), msg))
+        };
+
+        let mut archive = tar::Archive::new(fs::File::open(bundle)?);
+        let mut entries = archive.entries()?;
+        let manifest = read_manifest_entry(entries.next(), bundle)?;
+        if manifest.format != BUNDLE_FORMAT {
+            return Err(invalid(format!("unsupported format {}", manifest.format)));
+        }
+
+        // Paths are joined onto the cache directory below, so each must stay
+        // inside it
+        for file in &manifest.files {
+            let path = Path::new(&file.path);
+            let relative = path.components().next().is_some()
+                && path.components().all(|c| matches!(c, Component::Normal(_)));
+            if !relative {
+                return Err(invalid(format!(
+                    "manifest path '{}' escapes the cache directory",
+                    file.path
+                )));
+            }
+        }
+
+        let listed: HashSet<&str> = manifest.files.iter().map(|f| f.path.as_str()).collect();
+        let staging = tempfile::tempdir_in(&self.cache_dir)?;
+        for entry in entries {
+            let mut entry = entry?;
+            let rel = entry.path()?.to_string_lossy().replace('\\', "/");
+            if !listed.contains(rel.as_str()) {
+                return Err(invalid(format!("unexpected entry '{}'", rel)));
+            }
+            if !entry.unpack_in(staging.path())? {
+                return Err(invalid(format!("entry '{}' escapes the cache directory", rel)));
+            }
+        }
+
+        for file in &manifest.files {
+            let staged = staging.path().join(&file.path);
+            let size = fs::metadata(&staged).map(|m| m.len()).ok();
+            if size != Some(file.size) {
+                return Err(invalid(format!(
+                    "'{}' is missing or has the wrong size",
+                    file.path
+                )));
+            }
+        }
+        let _cache = self.lock(CACHE_LOCK, LockMode::Exclusive)?;
+        for file in &manifest.files {
+            let dest = self.cache_dir.join(&file.path);
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::rename(staging.path().join(&file.path), dest)?;
+        }
+
+        let mut records = self.manifest();
+        for file in &manifest.files {
+            records.files.insert(
+                file.path.clone(),
+                FileRecord {
+                    version: manifest.version.clone(),
+                    size: file.size,
+                    etag: None,
+                    last_modified: None,
+                    downloaded_at: unix_now(),
+                    last_accessed: None,
+                },
+            );
+        }
+        self.save_manifest(&records)?;
+        if let Some(version) = &manifest.version {
+            self.save_version(version);
+        }
+        self.remote_ver = None;
+        Ok(manifest)
+    }
+
+    // -----------------------------------------------------------------------
+    // Disk usage
+    // -----------------------------------------------------------------------
+
+    /// Report every cached file with its size, version and last access
+    /// time, and the total size of the cache.
+    ///
+    /// Covers the current files, the versions archived under `versions/`
+    /// and the databases views are materialized into under `databases/`;
+    /// the collection database and bookkeeping files are not counted.
+    pub fn stats(&self) -> Result<CacheStats> {
+        let manifest = self.manifest();
+        let local = self.local_version();
+        let in_use = self.view_files.borrow();
+        let attached = self.attached_databases.borrow();
+        let mut files = Vec::new();
+        let mut counted = HashSet::new();
+        let mut total_size = 0;
+        // `parts` are the file and the checksum or write-ahead log beside it
+        let mut add = |mut file: CachedFile, parts: [PathBuf; 2]| {
+            for part in parts {
+                let Ok(meta) = fs::metadata(&part) else {
+                    continue;
+                };
+                file.size += meta.len();
+                if file_id(&meta).is_none_or(|id| counted.insert(id)) {
+                    total_size += meta.len();
+                }
+            }
+            files.push(file);
+        };
+        let cached = |rel: String,
+                      path: &Path,
+                      version: Option<String>,
+                      archived: bool,
+                      record: Option<&FileRecord>| CachedFile {
+            path: rel,
+            size: 0,
+            version,
+            archived,
+            downloaded_at: record.map(|r| r.downloaded_at),
+            last_accessed: record.and_then(|r| r.last_accessed),
+            in_use: in_use.contains(path),
+        };
+
+        for (rel, path) in self.cached_files()? {
+            if rel.ends_with(CHECKSUM_SUFFIX) {
+                continue;
+            }
+            let record = manifest.files.get(&rel);
+            let version = match record {
+                Some(record) => record.version.clone(),
+                None => local.clone(),
+            };
+            add(
+                cached(rel, &path, version, false, record),
+                [path.clone(), checksum_path(&path)],
+            );
+        }
+
+        let mut versions = self.archived_versions()?;
+        versions.sort();
+        for version in versions {
+            let archive = self.cache_dir.join(VERSIONS_DIR).join(&version);
+            let records = read_manifest(&archive);
+            let mut archived = Vec::new();
+            walk_files(&archive, "", &mut archived)?;
+            archived.sort();
+            for (rel, path) in archived {
+                if rel == MANIFEST_FILE || rel.ends_with(CHECKSUM_SUFFIX) {
+                    continue;
+                }
+                add(
+                    cached(
+                        format!("{}/{}/{}", VERSIONS_DIR, version, rel),
+                        &path,
+                        (version != UNVERSIONED).then(|| version.clone()),
+                        true,
+                        records.files.get(&rel),
+                    ),
+                    [path.clone(), checksum_path(&path)],
+                );
+            }
+        }
+
+        let mut databases = Vec::new();
+        if let Ok(entries) = fs::read_dir(self.cache_dir.join(DATABASES_DIR)) {
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                if let Some(version) = name.strip_suffix(".duckdb") {
+                    databases.push((version.to_string(), entry.path()));
+                }
+            }
+        }
+        databases.sort();
+        for (version, path) in databases {
+            // Written to whenever views are materialized into it
+            let modified = fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs());
+            add(
+                CachedFile {
+                    path: format!("{}/{}.duckdb", DATABASES_DIR, version),
+                    size: 0,
+                    version: Some(version),
+                    archived: false,
+                    downloaded_at: None,
+                    last_accessed: modified,
+                    in_use: attached.contains(&path),
+                },
+                [path.clone(), database_wal(&path)],
+            );
+        }
+
+        Ok(CacheStats {
+            files,
+            total_size,
+            quota: self.quota,
+        })
+    }
+
+    /// Evict the least recently used files until the cache fits within
+    /// [`quota`](Self::quota). Does nothing without a quota.
+    ///
+    /// Current files and materialized databases are evicted one by one and
+    /// archived versions as a whole; files backing views registered through
+    /// this cache manager, and databases attached through it, are never
+    /// evicted. Views registered by other processes sharing the cache
+    /// are not known here, so give every process the same quota or none.
+    ///
+    /// # Returns
+    ///
+    /// Paths (relative to the cache directory) of the evicted files and
+    /// archived version directories.
+    pub fn enforce_quota(&mut self) -> Result<Vec<String>> {
+        self.evict_over_quota(&[])
+    }
+
+    /// [`enforce_quota`](Self::enforce_quota), never evicting the files in
+    /// `keep` (paths relative to the cache directory).
+    fn evict_over_quota(&mut self, keep: &[&str]) -> Result<Vec<String>> {
+        let Some(quota) = self.quota else {
+            return Ok(Vec::new());
+        };
+        if self.stats()?.total_size <= quota {
+            return Ok(Vec::new());
+        }
+
+        let _cache = self.lock(CACHE_LOCK, LockMode::Exclusive)?;
+        let mut evicted = Vec::new();
+        loop {
+            let stats = self.stats()?;
+            if stats.total_size <= quota {
+                break;
+            }
+            // Eviction units: each current file, and each archived version
+            // as a whole, last used when any of its files was
+            let mut units: BTreeMap<String, (u64, bool)> = BTreeMap::new();
+            for file in &stats.files {
+                let unit = if file.archived {
+                    file.path.splitn(3, '/').take(2).collect::<Vec<_>>().join("/")
+                } else {
+                    file.path.clone()
+                };
+                let used = file.last_accessed.unwrap_or(0).max(file.downloaded_at.unwrap_or(0));
+                let entry = units.entry(unit).or_insert((0, false));
+                entry.0 = entry.0.max(used);
+                entry.1 |= file.in_use;
+            }
+            let victim = units
+                .into_iter()
+                .filter(|(unit, (_, in_use))| !in_use && !keep.contains(&unit.as_str()))
+                .min_by_key(|(_, (used, _))| *used)
+                .map(|(unit, _)| unit);
+            let Some(victim) = victim else {
+                warn!(
+                    "Cache uses {} bytes, over its quota of {}, but every file is in use",
+                    stats.total_size,
+                    quota
+                );
+                break;
+            };
+
+            let path = self.cache_dir.join(&victim);
+            if victim.starts_with(&format!("{}/", VERSIONS_DIR)) {
+                fs::remove_dir_all(&path)?;
+            } else if victim.starts_with(&format!("{}/", DATABASES_DIR)) {
+                fs::remove_file(&path)?;
+                let _ = fs::remove_file(database_wal(&path));
+            } else {
+                fs::remove_file(&path)?;
+                let _ = fs::remove_file(checksum_path(&path));
+                let _manifest = self.lock(MANIFEST_LOCK, LockMode::Exclusive)?;
+                let mut manifest = self.manifest();
+                if manifest.files.remove(&victim).is_some() {
+                    self.save_manifest(&manifest)?;
+                }
+            }
+            info!(file = victim, "Evicted from the cache");
+            evicted.push(victim);
+        }
+        Ok(evicted)
+    }
+
+    // -----------------------------------------------------------------------
+    // Verification
+    // -----------------------------------------------------------------------
+
+    /// Recheck every cached data file against its SHA-256 checksum.
+    ///
+    /// Checksums saved at download time are used first. For files without
+    /// one, the published checksum is fetched from the CDN unless offline,
+    /// but only if the file's manifest record is for the version the CDN
+    /// serves now; older files are reported as unchecked. With `evict`,
+    /// corrupt files (with their checksums and manifest records) are deleted
+    /// so the next access re-downloads them, except files backing views
+    /// registered through this cache manager, which are listed in
+    /// [`VerifyReport::in_use`]; call
+    /// [`reset_views`](crate::Connection::reset_views) and verify again to
+    /// evict those.
+    pub fn verify(&mut self, evict: bool) -> Result<VerifyReport> {
+        let mut report = VerifyReport {
+            evicted: evict,
+            ..VerifyReport::default()
+        };
+        let manifest = self.manifest();
+        let mut corrupt = Vec::new();
+
+        for (rel, path) in self.cached_files()? {
+            if rel.ends_with(CHECKSUM_SUFFIX) {
+                continue;
+            }
+            let sidecar = checksum_path(&path);
+            let saved = fs::read_to_string(&sidecar)
+                .ok()
+                .and_then(|s| parse_checksum(&s));
+            let expected = match saved {
+                Some(sum) => Some(sum),
+                None if self.offline => None,
+                None => {
+                    // The CDN only publishes checksums for its current files
+                    let recorded = manifest.files.get(&rel).and_then(|r| r.version.clone());
+                    if recorded.is_some() && recorded == self.remote_version()? {
+                        let published = self.published_checksum(&rel)?;
+                        if let Some(sum) = &published {
+                            fs::write(&sidecar, sum)?;
+                        }
+                        published
+                    } else {
+                        None
+                    }
+                }
+            };
+
+            let Some(expected) = expected else {
+                report.unchecked.push(rel);
+                continue;
+            };
+            if sha256_file(&path)? == expected {
+                report.verified.push(rel);
+            } else {
+                warn!(file = rel, "Corrupt cache file");
+                report.corrupt.push(rel.clone());
+                corrupt.push((rel, path, expected));
+            }
+        }
+
+        if evict && !corrupt.is_empty() {
+            let _cache = self.lock(CACHE_LOCK, LockMode::Exclusive)?;
+            let mut evicted = Vec::new();
+            for (rel, path, expected) in corrupt {
+                if self.view_files.borrow().contains(&path) {
+                    report.in_use.push(rel);
+                    continue;
+                }
+                // Another process may have downloaded a good copy since
+                if !path.exists() || sha256_file(&path)? == expected {
+                    continue;
+                }
+                fs::remove_file(&path)?;
+                let _ = fs::remove_file(checksum_path(&path));
+                info!(file = rel, "Evicted from the cache");
+                evicted.push(rel);
+            }
+
+            let _manifest = self.lock(MANIFEST_LOCK, LockMode::Exclusive)?;
+            let mut manifest = self.manifest();
+            let before = manifest.files.len();
+            for rel in &evicted {
+                manifest.files.remove(rel);
+            }
+            if manifest.files.len() != before {
+                self.save_manifest(&manifest)?;
+            }
+        }
+        Ok(report)
+    }
+
+    /// Fetch the published checksum for `filename` from the first base URL
+    /// that answers. Fails only if every base URL failed.
+    fn published_checksum(&mut self, filename: &str) -> Result<Option<String>> {
+        let client = self.client().clone();
+        let mut answered = false;
+        let mut last_err = None;
+        for base in self.bases() {
+            match fetch_checksum(&client, &format!("{}/{}", base, filename)) {
+                Ok(Some(sum)) => return Ok(Some(sum)),
+                Ok(None) => answered = true,
+                Err(e) => last_err = Some(e),
+            }
+        }
+        match last_err {
+            Some(e) if !answered => Err(e),
+            _ => Ok(None),
+        }
+    }
+
     /// Close the HTTP client, if open.
     pub fn close(&mut self) {
         self.client = None;
     }
 }
+
+/// Download settings of a [`CacheManager`], shareable between download
+/// threads.
+struct Fetcher {
+    client: Client,
+    bases: Vec<String>,
+    max_retries: u32,
+    retry_backoff: Duration,
+    progress: Option<DownloadProgressFn>,
+    cache_dir: PathBuf,
+    lock_timeout: Duration,
+}
+
+/// Outcome of [`Fetcher::fetch_locked`].
+enum Download {
+    /// The cached file became current while waiting for its lock.
+    Current,
+    /// The file was fetched; see [`Fetcher::fetch`].
+    Fetched(Option<Validators>),
+}
+
+impl Fetcher {
+    /// Take the lock on `filename` and fetch it to `dest` unless it is
+    /// current for `remote` by then, revalidating it against its manifest
+    /// record. The lock is returned so it can be held until the download is
+    /// recorded.
+    fn fetch_locked(
+        &self,
+        filename: &str,
+        dest: &Path,
+        remote: Option<&str>,
+    ) -> Result<(FileLock, Download)> {
+        let lock_path = self.cache_dir.join(LOCKS_DIR).join(file_lock(filename));
+        let held = lock::acquire(&lock_path, LockMode::Exclusive, self.lock_timeout)?;
+        let cached = if dest.exists() {
+            read_manifest(&self.cache_dir).files.remove(filename)
+        } else {
+            None
+        };
+        if remote.is_some() && cached.as_ref().and_then(|r| r.version.as_deref()) == remote {
+            return Ok((held, Download::Current));
+        }
+        let validators = self.fetch(filename, dest, cached.as_ref())?;
+        Ok((held, Download::Fetched(validators)))
+    }
+
+    /// Download `filename` to `dest` with retries and mirror fallback,
+    /// without touching the manifest. See
+    /// [`CacheManager::download_file`].
+    ///
+    /// With a `cached` record the request is conditional; returns `None` if
+    /// the server answered `304 Not Modified`.
+    fn fetch(
+        &self,
+        filename: &str,
+        dest: &Path,
+        cached: Option<&FileRecord>,
+    ) -> Result<Option<Validators>> {
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let tmp_dest = dest.with_extension(
+            format!(
+                "{}.tmp",
+                dest.extension()
+                    .and_then(|e| e.to_str())
+                    .unwrap_or("")
+            ),
+        );
+
+        let progress = |downloaded, total| {
+            if let Some(callback) = &self.progress {
+                callback(&DownloadProgress {
+                    file: filename,
+                    downloaded,
+                    total,
+                });
+            }
+        };
+        let mut last_err = None;
+        for base in &self.bases {
+            let url = format!("{}/{}", base, filename);
+            for attempt in 0..=self.max_retries {
+                if attempt > 0 {
+                    let delay = self.retry_backoff.saturating_mul(1 << (attempt - 1).min(16));
+                    warn!(url = url, attempt = attempt, "Retrying in {:?}", delay);
+                    std::thread::sleep(delay);
+                }
+                info!(url = url, "Downloading {}", filename);
+                match download_to(&self.client, &url, filename, &tmp_dest, dest, cached, &progress) {
+                    Ok(validators) => return Ok(validators),
+                    Err(e) => {
+                        warn!(url = url, "Download failed: {}", e);
+                        let retry = is_transient(&e);
+                        last_err = Some(e);
+                        if !retry {
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        Err(last_err.expect("bases always include cdn_base"))
+    }
+}
+
+/// Download `url` into `tmp_dest`, check it against the published checksum
+/// and move it to `dest`. `filename` is only used in error messages.
+///
+/// An existing `tmp_dest` is resumed with a `Range` request. If the resumed
+/// file fails its checksum (the partial data may be from an older release),
+/// it is discarded and downloaded once more from the start. The temp file is
+/// kept after transient errors so the next attempt can resume it, and
+/// removed after any other error.
+///
+/// With a `cached` record the request is conditional; returns `None` if the
+/// server answered `304 Not Modified`, else the new response's validators.
+/// `progress` receives the bytes written so far and the total size.
+fn download_to(
+    client: &Client,
+    url: &str,
+    filename: &str,
+    tmp_dest: &Path,
+    dest: &Path,
+    cached: Option<&FileRecord>,
+    progress: &dyn Fn(u64, Option<u64>),
+) -> Result<Option<Validators>> {
+    let result = (|| {
+        let fetched = fetch_to_tmp(client, url, tmp_dest, cached, progress)?;
+        let (resumed, mut validators) = match fetched {
+            Fetched::NotModified => return Ok(None),
+            Fetched::Body {
+                resumed,
+                validators,
+            } => (resumed, validators),
+        };
+        let mut actual = sha256_file(tmp_dest)?;
+        let expected = fetch_checksum(client, url)?;
+        if resumed && expected.as_ref().is_some_and(|e| *e != actual) {
+            warn!(file = filename, "Resumed download is corrupt; starting over");
+            fs::remove_file(tmp_dest)?;
+            if let Fetched::Body { validators: v, .. } =
+                fetch_to_tmp(client, url, tmp_dest, None, progress)?
+            {
+                validators = v;
+            }
+            actual = sha256_file(tmp_dest)?;
+        }
+        if let Some(expected) = &expected {
+            if *expected != actual {
+                return Err(MtgjsonError::Integrity(format!(
+                    "{} has SHA-256 {}, expected {}",
+                    filename, actual, expected
+                )));
+            }
+        }
+        Ok(Some((expected, validators)))
+    })();
+    let (expected, validators) = match result {
+        Ok(Some(fetched)) => fetched,
+        Ok(None) => return Ok(None),
+        Err(e) => {
+            if !is_transient(&e) {
+                let _ = fs::remove_file(tmp_dest);
+            }
+            return Err(e);
+        }
+    };
+
+    fs::rename(tmp_dest, dest)?;
+    match expected {
+        Some(sum) => fs::write(checksum_path(dest), sum)?,
+        None => {
+            let _ = fs::remove_file(checksum_path(dest));
+        }
+    }
+    Ok(Some(validators))
+}
+
+/// Cache validators from a download response.
+#[derive(Debug, Default)]
+struct Validators {
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+/// Result of [`fetch_to_tmp`].
+enum Fetched {
+    /// `304 Not Modified`; nothing was written.
+    NotModified,
+    /// The body was written to the temp file.
+    Body { resumed: bool, validators: Validators },
+}
+
+/// Stream `url` into `tmp_dest`, appending to it if it already holds the
+/// start of the file and the server honors the `Range` request.
+///
+/// Without a partial file, a `cached` record makes the request conditional.
+/// `progress` is called with the bytes written so far and the total size,
+/// once before the body is read and after every chunk.
+fn fetch_to_tmp(
+    client: &Client,
+    url: &str,
+    tmp_dest: &Path,
+    cached: Option<&FileRecord>,
+    progress: &dyn Fn(u64, Option<u64>),
+) -> Result<Fetched> {
+    use reqwest::header;
+
+    let offset = fs::metadata(tmp_dest).map(|m| m.len()).unwrap_or(0);
+    // Ranges refer to the stored bytes, so ask for them unencoded
+    let mut request = client.get(url).header(header::ACCEPT_ENCODING, "identity");
+    if offset > 0 {
+        request = request.header(header::RANGE, format!("bytes={}-", offset));
+    } else if let Some(record) = cached {
+        if let Some(etag) = &record.etag {
+            request = request.header(header::IF_NONE_MATCH, etag);
+        }
+        if let Some(modified) = &record.last_modified {
+            request = request.header(header::IF_MODIFIED_SINCE, modified);
+        }
+    }
+    let mut resp = request.send()?;
+
+    if offset == 0 && resp.status() == reqwest::StatusCode::NOT_MODIFIED {
+        return Ok(Fetched::NotModified);
+    }
+    if offset > 0 && resp.status() == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+        // The partial file is not a prefix of the current one
+        fs::remove_file(tmp_dest)?;
+        return fetch_to_tmp(client, url, tmp_dest, cached, progress);
+    }
+    resp = resp.error_for_status()?;
+    let header_value = |name: header::HeaderName| {
+        resp.headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(String::from)
+    };
+    let validators = Validators {
+        etag: header_value(header::ETAG),
+        last_modified: header_value(header::LAST_MODIFIED),
+    };
+
+    let resumed = offset > 0
+        && resp.status() == reqwest::StatusCode::PARTIAL_CONTENT
+        && resp
+            .headers()
+            .get(reqwest::header::CONTENT_RANGE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with(&format!("bytes {}-", offset)));
+    if offset > 0 && !resumed {
+        debug!(url = url, "Server ignored range request; restarting");
+    }
+
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(tmp_dest)?;
+    let mut downloaded = if resumed { offset } else { 0 };
+    let total = resp.content_length().map(|len| downloaded + len);
+    progress(downloaded, total);
+    let mut buf = vec![0; CHUNK_SIZE];
+    loop {
+        let n = match resp.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        };
+        file.write_all(&buf[..n])?;
+        downloaded += n as u64;
+        progress(downloaded, total);
+    }
+    file.sync_all()?;
+    Ok(Fetched::Body {
+        resumed,
+        validators,
+    })
+}
+
+/// Whether a failed download is worth retrying: network and I/O errors,
+/// timeouts, `429 Too Many Requests` and 5xx responses.
+fn is_transient(err: &MtgjsonError) -> bool {
+    match err {
+        MtgjsonError::Http(e) => match e.status() {
+            Some(status) => {
+                status.is_server_error()
+                    || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                    || status == reqwest::StatusCode::REQUEST_TIMEOUT
+            }
+            None => !e.is_builder() && !e.is_redirect(),
+        },
+        MtgjsonError::Io(_) => true,
+        _ => false,
+    }
+}
+
+/// Read the per-file manifest in `dir`. A missing or unreadable manifest is
+/// empty.
+fn read_manifest(dir: &Path) -> CacheManifest {
+    fs::read_to_string(dir.join(MANIFEST_FILE))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+/// Whether `version` is a plain version string (e.g. `"5.2.2+20240101"`)
+/// that is safe to use as a file name.
+fn is_plain_version(version: &str) -> bool {
+    !version.is_empty()
+        && version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '+' | '-' | '_'))
+        && !version.contains("..")
+}
+
+/// Files under `dir` as `(prefix + relative path, absolute path)`, with `/`
+/// separators, skipping leftover `.tmp` downloads.
+fn walk_files(dir: &Path, prefix: &str, out: &mut Vec<(String, PathBuf)>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let Some(name) = entry.file_name().to_str().map(String::from) else {
+            continue;
+        };
+        let rel = format!("{}{}", prefix, name);
+        if entry.file_type()?.is_dir() {
+            walk_files(&entry.path(), &format!("{}/", rel), out)?;
+        } else if !name.ends_with(".tmp") {
+            out.push((rel, entry.path()));
+        }
+    }
+    Ok(())
+}
+
+/// Keep a copy of the cached file `live` at `dest` without removing `live`,
+/// so it keeps serving until a staged file is renamed over it.
+///
+/// Hard-links where the filesystem allows it and copies otherwise.
+fn archive_file(live: &Path, dest: &Path) -> Result<()> {
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let _ = fs::remove_file(dest);
+    if fs::hard_link(live, dest).is_err() {
+        fs::copy(live, dest)?;
+    }
+    Ok(())
+}
+
+/// Write-ahead log DuckDB keeps next to the database at `path`.
+fn database_wal(path: &Path) -> PathBuf {
+    let mut wal = path.as_os_str().to_owned();
+    wal.push(".wal");
+    PathBuf::from(wal)
+}
+
+/// Identity of the file behind `meta`, so hard links are counted once.
+#[cfg(unix)]
+fn file_id(meta: &fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((meta.dev(), meta.ino()))
+}
+
+/// Identity of the file behind `meta`; unknown on this platform.
+#[cfg(not(unix))]
+fn file_id(_meta: &fs::Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+/// Name of the lock serializing downloads of `filename`, relative to the
+/// lock directory.
+fn file_lock(filename: &str) -> String {
+    format!("files/{}.lock", filename)
+}
+
+/// Current time as seconds since the Unix epoch.
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Fetch `Meta.json` from `url` and extract the MTGJSON version.
+fn fetch_version(client: &Client, url: &str) -> Result<Option<String>> {
+    let data: serde_json::Value = client.get(url).send()?.error_for_status()?.json()?;
+    // Try data.version first, then meta.version
+    let version = data
+        .get("data")
+        .and_then(|d| d.get("version"))
+        .and_then(|v| v.as_str())
+        .or_else(|| {
+            data.get("meta")
+                .and_then(|m| m.get("version"))
+                .and_then(|v| v.as_str())
+        })
+        .map(|s| s.to_string());
+    Ok(version)
+}
+
+/// Local path of the checksum kept next to a cached file.
+fn checksum_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(CHECKSUM_SUFFIX);
+    PathBuf::from(name)
+}
+
+/// Extract the digest from `sha256sum`-style text (`<hex>  <filename>`).
+///
+/// Returns `None` unless the first token is 64 hex digits.
+fn parse_checksum(text: &str) -> Option<String> {
+    let token = text.split_whitespace().next()?;
+    (token.len() == 64 && token.chars().all(|c| c.is_ascii_hexdigit()))
+        .then(|| token.to_ascii_lowercase())
+}
+
+/// Fetch the published checksum for the artifact at `url`.
+///
+/// Returns `None` if no checksum is published (HTTP 404). A checksum file
+/// that does not parse fails with [`MtgjsonError::Integrity`], since it
+/// usually means a proxy or captive portal answered instead of the CDN.
+fn fetch_checksum(client: &Client, url: &str) -> Result<Option<String>> {
+    let resp = client.get(format!("{}{}", url, CHECKSUM_SUFFIX)).send()?;
+    if resp.status() == reqwest::StatusCode::NOT_FOUND {
+        debug!(url = url, "No checksum published");
+        return Ok(None);
+    }
+    let text = resp.error_for_status()?.text()?;
+    parse_checksum(&text).map(Some).ok_or_else(|| {
+        MtgjsonError::Integrity(format!("Invalid checksum file for {}", url))
+    })
+}
+
+/// SHA-256 of a file's contents as lowercase hex.
+fn sha256_file(path: &Path) -> Result<String> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut fs::File::open(path)?, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Parse the first entry of a bundle archive as its manifest.
+fn read_manifest_entry<R: Read>(
+    entry: Option<std::io::Result<tar::Entry<'_, R>>>,
+    bundle: &Path,
+) -> Result<BundleManifest> {
+    let mut entry = match entry {
+        Some(entry) => entry?,
+        None => {
+            return Err(MtgjsonError::InvalidArgument(format!(
+                "Invalid bundle {}: archive is empty",
+                bundle.display()
+            )))
+        }
+    };
+    if entry.path()?.as_ref() != Path::new(BUNDLE_MANIFEST) {
+        return Err(MtgjsonError::InvalidArgument(format!(
+            "Invalid bundle {}: missing {}",
+            bundle.display(),
+            BUNDLE_MANIFEST
+        )));
+    }
+    let mut contents = String::new();
+    entry.read_to_string(&mut contents)?;
+    Ok(serde_json::from_str(&contents)?)
+}
+
+/// Parse a cached JSON file (handles `.gz` transparently).
+///
+/// A file that fails to parse is deleted so the next call re-downloads it.
+fn read_json_file(path: &Path) -> Result<serde_json::Value> {
+    let parse_result = if path.extension().and_then(|e| e.to_str()) == Some("gz") {
+        let file = fs::File::open(path)?;
+        let reader = BufReader::new(file);
+        let decoder = GzDecoder::new(reader);
+        let mut buf_reader = BufReader::new(decoder);
+        let mut contents = String::new();
+        buf_reader.read_to_string(&mut contents)?;
+        serde_json::from_str(&contents).map_err(MtgjsonError::from)
+    } else {
+        let contents = fs::read_to_string(path)?;
+        serde_json::from_str(&contents).map_err(MtgjsonError::from)
+    };
+
+    match parse_result {
+        Ok(value) => Ok(value),
+        Err(e) => {
+            warn!(file = path.display(), "Corrupt cache file, removing: {}", e);
+            let _ = fs::remove_file(path);
+            Err(MtgjsonError::NotFound(format!(
+                "Cache file '{}' was corrupt and has been removed. \
+                 Retry to re-download. Original error: {}",
+                path.file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or("unknown"),
+                e
+            )))
+        }
+    }
+}
@@ -50,6 +50,25 @@ pub fn json_files() -> HashMap<&'static str, &'static str> {
     ])
 }
 
+/// Relative CDN path for an individual deck file (`decks/{file_name}.json`).
+///
+/// Returns `None` if `file_name` is empty or could escape the `decks/`
+/// directory (path separators, `..`).
+pub fn deck_file(file_name: &str) -> Option<String> {
+    let valid = !file_name.is_empty()
+        && file_name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+        && !file_name.contains("..");
+    valid.then(|| format!("decks/{}.json", file_name))
+}
+
+/// DuckDB database file in the cache directory holding the user's collection.
+///
+/// Unlike the downloaded data files, this file is never removed by
+/// [`CacheManager::clear`](crate::cache::CacheManager::clear).
+pub const COLLECTION_FILE: &str = "collection.duckdb";
+
 pub fn default_cache_dir() -> PathBuf {
     if let Some(cache) = dirs::cache_dir() {
         cache.join("mtgjson-sdk")
@@ -5,11 +5,18 @@
 //! - Wide-format legalities are auto-UNPIVOTed to (uuid, format, status) rows
 
 use crate::cache::CacheManager;
-use crate::error::Result;
-use duckdb::{types::ValueRef, Connection as DuckDbConnection};
+use crate::config;
+use crate::error::{MtgjsonError, Result};
+use crate::sql_expr::{is_column, is_identifier};
+use crate::sql_value::{self, SqlValue};
+use crate::stream::{RowStream, TypedRowStream};
+use duckdb::types::{TimeUnit, Value as DuckDbValue, ValueRef};
+use duckdb::Connection as DuckDbConnection;
 use serde::de::DeserializeOwned;
-use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 /// Known list columns that don't follow the plural naming convention
 /// (e.g. colorIdentity, availability, producedMana). Always converted
@@ -90,6 +97,7 @@ fn ignored_columns() -> HashSet<&'static str> {
         "format",
         "uris",
         "scryfallUri",
+        "tokenProducts",
     ])
 }
 
@@ -106,19 +114,59 @@ fn json_cast_columns() -> HashSet<&'static str> {
         "sourceProducts",
         "foreignData",
         "translations",
+        "tokenProducts",
     ])
 }
 
+/// Schema of the persistent collection database.
+///
+/// One row per distinct (card, finish, condition, language); adding the same
+/// combination again increases `quantity`.
+const COLLECTION_SCHEMA: &str = r#"
+    CREATE TABLE IF NOT EXISTS collection.owned_cards (
+        uuid VARCHAR NOT NULL,
+        finish VARCHAR NOT NULL,
+        condition VARCHAR NOT NULL,
+        language VARCHAR NOT NULL,
+        quantity BIGINT NOT NULL CHECK (quantity > 0),
+        acquired_price DOUBLE,
+        acquired_date DATE,
+        PRIMARY KEY (uuid, finish, condition, language)
+    );
+"#;
+
+/// State shared by a connection and every clone made with
+/// [`Connection::try_clone`].
+struct Shared {
+    cache: Mutex<CacheManager>,
+    registered_views: RwLock<HashSet<String>>,
+    collection_attached: AtomicBool,
+    /// Held while views, tables and attachments are created, so clones
+    /// never race to register the same view.
+    catalog: Mutex<()>,
+    /// Whether parquet views are materialized into a persistent database.
+    materialize: bool,
+    /// Aliases of the attached materialized databases; the current one last.
+    stores: Mutex<Vec<String>>,
+    /// Column names of views introspected by `view_columns`.
+    columns: RwLock<HashMap<String, Vec<String>>>,
+}
+
+/// Columns of materialized tables that get an index, when present.
+const MATERIALIZED_INDEXES: [&str; 3] = ["uuid", "name", "setCode"];
+
 /// Wraps a DuckDB connection and registers parquet files as views.
 ///
 /// Uses schema introspection to adapt views dynamically:
 /// - CSV VARCHAR columns are auto-detected and converted to arrays
 /// - Wide-format legalities are auto-UNPIVOTed to (uuid, format, status) rows
+///
+/// A `Connection` is `Send` but not `Sync`. To query from several threads,
+/// give each thread its own [`try_clone`](Self::try_clone): clones share the
+/// database, the view registry and the cache, and run queries in parallel.
 pub struct Connection {
     conn: DuckDbConnection,
-    /// The cache manager used to download/locate data files.
-    pub cache: RefCell<CacheManager>,
-    registered_views: RefCell<HashSet<String>>,
+    shared: Arc<Shared>,
 }
 
 impl Connection {
@@ -126,24 +174,112 @@ impl Connection {
     ///
     /// Opens an in-memory DuckDB database.
     pub fn new(cache: CacheManager) -> Result<Self> {
+        Self::open(cache, false)
+    }
+
+    /// Create a connection that materializes parquet views into a
+    /// persistent database.
+    ///
+    /// The first time a view is registered for an MTGJSON version, its
+    /// parquet file is loaded into a table of the database at
+    /// [`CacheManager::database_path`], indexed on the `uuid`, `name` and
+    /// `setCode` columns it has. Later connections find the table there and
+    /// register the view over it without reading the parquet file, or
+    /// checking the CDN for a newer version; use
+    /// [`MtgjsonSdk::refresh`](crate::MtgjsonSdk::refresh) for that, which
+    /// builds a new database for the new version.
+    ///
+    /// A database already opened by another process cannot be attached;
+    /// views then fall back to reading the parquet files directly.
+    pub fn new_materialized(cache: CacheManager) -> Result<Self> {
+        Self::open(cache, true)
+    }
+
+    fn open(cache: CacheManager, materialize: bool) -> Result<Self> {
         let conn = DuckDbConnection::open_in_memory()?;
         Ok(Self {
             conn,
-            cache: RefCell::new(cache),
-            registered_views: RefCell::new(HashSet::new()),
+            shared: Arc::new(Shared {
+                cache: Mutex::new(cache),
+                registered_views: RwLock::new(HashSet::new()),
+                collection_attached: AtomicBool::new(false),
+                catalog: Mutex::new(()),
+                materialize,
+                stores: Mutex::new(Vec::new()),
+                columns: RwLock::new(HashMap::new()),
+            }),
         })
     }
 
+    /// Open another connection to the same database.
+    ///
+    /// The clone shares the cache and the registered views with `self`, so
+    /// a view registered through either is visible to both.
+    pub fn try_clone(&self) -> Result<Self> {
+        Ok(Self {
+            conn: self.conn.try_clone()?,
+            shared: Arc::clone(&self.shared),
+        })
+    }
+
+    /// Lock the cache manager used to download/locate data files.
+    ///
+    /// The cache is shared by all clones of this connection; keep the guard
+    /// short-lived, as downloads through other clones wait for it.
+    pub fn cache(&self) -> MutexGuard<'_, CacheManager> {
+        self.shared
+            .cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
     /// Ensure one or more views are registered, downloading data if needed.
+    ///
+    /// When several views need files that are not cached yet, the files are
+    /// downloaded concurrently first; see [`CacheManager::ensure_parquets`].
     pub fn ensure_views(&self, views: &[&str]) -> Result<()> {
+        if views.iter().all(|v| self.has_view(v)) {
+            return Ok(());
+        }
+        let _catalog = self.lock_catalog();
+        let parquet_files = config::parquet_files();
+        let pending: Vec<&str> = views
+            .iter()
+            .copied()
+            .filter(|v| parquet_files.contains_key(v) && !self.has_view(v))
+            .collect();
+        if pending.len() > 1 {
+            self.cache().ensure_parquets(&pending)?;
+        }
         for name in views {
-            if !self.registered_views.borrow().contains(*name) {
-                self.ensure_view(name)?;
-            }
+            self.ensure_view(name)?;
         }
         Ok(())
     }
 
+    /// Attach the persistent collection database, creating it if needed.
+    ///
+    /// The database lives at [`config::COLLECTION_FILE`] in the cache
+    /// directory and is attached as `collection`, so its `owned_cards` table
+    /// can be joined directly against the data views.
+    pub fn ensure_collection(&self) -> Result<()> {
+        if self.shared.collection_attached.load(Ordering::Acquire) {
+            return Ok(());
+        }
+
+        let _catalog = self.lock_catalog();
+        let path = self.cache().cache_dir.join(config::COLLECTION_FILE);
+        let path_str = path.to_string_lossy().replace('\\', "/").replace('\'', "''");
+
+        self.conn.execute_batch(&format!(
+            "ATTACH IF NOT EXISTS '{}' AS collection; {}",
+            path_str, COLLECTION_SCHEMA
+        ))?;
+        self.shared.collection_attached.store(true, Ordering::Release);
+
+        Ok(())
+    }
+
     /// Execute SQL and return results as a `Vec` of `HashMap`s.
     ///
     /// Each row is represented as a `HashMap<String, serde_json::Value>`.
@@ -151,13 +287,14 @@ impl Connection {
     pub fn execute(
         &self,
         sql: &str,
-        params: &[String],
+        params: &[SqlValue],
     ) -> Result<Vec<HashMap<String, serde_json::Value>>> {
-        let mut stmt = self.conn.prepare(sql)?;
+        let (sql, params) = sql_value::bind(sql, params)?;
+        let mut stmt = self.conn.prepare(&sql)?;
 
         let param_values: Vec<&dyn duckdb::ToSql> = params
             .iter()
-            .map(|p| p as &dyn duckdb::ToSql)
+            .map(|p| *p as &dyn duckdb::ToSql)
             .collect();
 
         let mut rows_result = stmt.query(param_values.as_slice())?;
@@ -176,8 +313,7 @@ impl Connection {
 
         while let Some(row) = rows_result.next()? {
             let mut map = HashMap::new();
-            for i in 0..column_count {
-                let col_name = &column_names[i];
+            for (i, col_name) in column_names.iter().enumerate().take(column_count) {
                 let value = convert_value_ref(row.get_ref(i)?);
                 map.insert(col_name.clone(), value);
             }
@@ -194,7 +330,7 @@ impl Connection {
     pub fn execute_into<T: DeserializeOwned>(
         &self,
         sql: &str,
-        params: &[String],
+        params: &[SqlValue],
     ) -> Result<Vec<T>> {
         let rows = self.execute(sql, params)?;
         let mut results = Vec::with_capacity(rows.len());
@@ -208,18 +344,82 @@ impl Connection {
         Ok(results)
     }
 
+    /// Execute SQL and return a lazy iterator over the result rows.
+    ///
+    /// Unlike [`execute`](Self::execute), rows are fetched from DuckDB one
+    /// data chunk at a time as the iterator is advanced, so full-table scans
+    /// run in bounded memory. `sql` must be a single `SELECT` statement.
+    ///
+    /// DuckDB closes a streaming result when another query runs on the same
+    /// connection, so consume the stream before issuing further queries.
+    pub fn stream(&self, sql: &str, params: &[SqlValue]) -> Result<RowStream<'_>> {
+        let (sql, params) = sql_value::bind(sql, params)?;
+        let param_values: Vec<&dyn duckdb::ToSql> = params
+            .iter()
+            .map(|p| *p as &dyn duckdb::ToSql)
+            .collect();
+
+        // The streaming API needs the Arrow schema up front; get it from a
+        // zero-row run of the same query.
+        let query = sql.trim().trim_end_matches(';');
+        let mut probe = self
+            .conn
+            .prepare(&format!("SELECT * FROM ({}) LIMIT 0", query))?;
+        let _ = probe.query_arrow(param_values.as_slice())?;
+        let schema = probe.schema();
+
+        let mut stmt = self.conn.prepare(query)?;
+        let _ = stmt.stream_arrow(param_values.as_slice(), schema.clone())?;
+        Ok(RowStream::new(stmt, schema))
+    }
+
+    /// Execute SQL and return a lazy iterator deserializing each row into `T`.
+    ///
+    /// Streaming counterpart of [`execute_into`](Self::execute_into); see
+    /// [`stream`](Self::stream).
+    pub fn execute_iter<T: DeserializeOwned>(
+        &self,
+        sql: &str,
+        params: &[SqlValue],
+    ) -> Result<TypedRowStream<'_, T>> {
+        Ok(TypedRowStream::new(self.stream(sql, params)?))
+    }
+
+    /// Execute SQL and return the result as Arrow record batches.
+    ///
+    /// Batches come straight from DuckDB with their native column types, so
+    /// no per-row conversion to `serde_json::Value` takes place. Suitable for
+    /// handing results to arrow or polars based analytics code.
+    #[cfg(feature = "arrow")]
+    pub fn execute_arrow(
+        &self,
+        sql: &str,
+        params: &[SqlValue],
+    ) -> Result<Vec<duckdb::arrow::record_batch::RecordBatch>> {
+        let (sql, params) = sql_value::bind(sql, params)?;
+        let mut stmt = self.conn.prepare(&sql)?;
+
+        let param_values: Vec<&dyn duckdb::ToSql> = params
+            .iter()
+            .map(|p| *p as &dyn duckdb::ToSql)
+            .collect();
+
+        Ok(stmt.query_arrow(param_values.as_slice())?.collect())
+    }
+
     /// Execute SQL and return the first column of the first row.
     ///
     /// Returns `None` if the result set is empty.
     pub fn execute_scalar(
         &self,
         sql: &str,
-        params: &[String],
+        params: &[SqlValue],
     ) -> Result<Option<serde_json::Value>> {
-        let mut stmt = self.conn.prepare(sql)?;
+        let (sql, params) = sql_value::bind(sql, params)?;
+        let mut stmt = self.conn.prepare(&sql)?;
         let param_values: Vec<&dyn duckdb::ToSql> = params
             .iter()
-            .map(|p| p as &dyn duckdb::ToSql)
+            .map(|p| *p as &dyn duckdb::ToSql)
             .collect();
 
         let mut rows = stmt.query(param_values.as_slice())?;
@@ -242,28 +442,215 @@ impl Connection {
         ndjson_path: &str,
     ) -> Result<()> {
         let path_fwd = ndjson_path.replace('\\', "/");
+        let _catalog = self.lock_catalog();
         self.conn.execute_batch(&format!(
             "DROP TABLE IF EXISTS {}; \
              CREATE TABLE {} AS SELECT * FROM read_json_auto('{}', format='newline_delimited')",
             table_name, table_name, path_fwd
         ))?;
-        self.registered_views.borrow_mut().insert(table_name.to_string());
+        self.registry_mut().insert(table_name.to_string());
+        self.columns_mut().remove(table_name);
         Ok(())
     }
 
     /// Check whether a view has been registered.
     pub fn has_view(&self, name: &str) -> bool {
-        self.registered_views.borrow().contains(name)
+        self.registry().contains(name)
     }
 
     /// Return a list of all registered view names.
     pub fn views(&self) -> Vec<String> {
-        self.registered_views.borrow().iter().cloned().collect()
+        self.registry().iter().cloned().collect()
     }
 
     /// Clear all registered views so they will be re-created on next access.
     pub fn reset_views(&self) {
-        self.registered_views.borrow_mut().clear();
+        let _catalog = self.lock_catalog();
+        self.registry_mut().clear();
+        self.columns_mut().clear();
+        self.cache().release_views();
+    }
+
+    /// Re-create every registered parquet view in a single transaction.
+    ///
+    /// Use after the cached files under the views changed (see
+    /// [`CacheManager::refresh`]): queries see either all old or all new view
+    /// definitions, never a mix, and views are rebuilt from the new files'
+    /// schemas. If any view fails to register, the old definitions are kept.
+    /// Tables created by
+    /// [`register_table_from_ndjson`](Self::register_table_from_ndjson) are
+    /// forgotten, as with [`reset_views`](Self::reset_views).
+    pub fn reload_views(&self) -> Result<()> {
+        let _catalog = self.lock_catalog();
+        let parquet_files = config::parquet_files();
+        let previous = std::mem::take(&mut *self.registry_mut());
+        self.columns_mut().clear();
+        let mut views: Vec<&str> = previous
+            .iter()
+            .map(String::as_str)
+            .filter(|v| parquet_files.contains_key(v))
+            .collect();
+        views.sort_unstable();
+
+        // A transaction may only write to one database, so tables for the
+        // new version are materialized before the views are swapped
+        let materialized = self
+            .store()
+            .and_then(|store| match &store {
+                Some(alias) => views.iter().try_for_each(|v| self.materialize(alias, v)),
+                None => Ok(()),
+            });
+        if let Err(e) = materialized {
+            *self.registry_mut() = previous;
+            return Err(e);
+        }
+
+        self.conn.execute_batch("BEGIN TRANSACTION")?;
+        match views.iter().try_for_each(|v| self.ensure_view(v)) {
+            Ok(()) => {
+                self.conn.execute_batch("COMMIT")?;
+                self.detach_stale_stores();
+                Ok(())
+            }
+            Err(e) => {
+                let _ = self.conn.execute_batch("ROLLBACK");
+                *self.registry_mut() = previous;
+                Err(e)
+            }
+        }
+    }
+
+    /// Column names of a registered view or table, registering it first if
+    /// needed.
+    ///
+    /// The schema is introspected on first use and remembered until the
+    /// views are reset or reloaded. Fails with
+    /// [`MtgjsonError::InvalidArgument`] if `view` is not a plain identifier.
+    pub fn view_columns(&self, view: &str) -> Result<Vec<String>> {
+        if !is_identifier(view) {
+            return Err(MtgjsonError::InvalidArgument(format!(
+                "Invalid view name: {:?}",
+                view
+            )));
+        }
+        if let Some(columns) = self.columns().get(view) {
+            return Ok(columns.clone());
+        }
+        self.ensure_views(&[view])?;
+        let columns: Vec<String> = self
+            .describe(&format!("SELECT * FROM {}", view))?
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        self.columns_mut()
+            .insert(view.to_string(), columns.clone());
+        Ok(columns)
+    }
+
+    /// Check that each of `columns` names a column of one of `views`.
+    ///
+    /// Each view may be followed by the alias the query gives it, as in
+    /// [`SqlBuilder::new`](crate::SqlBuilder::new), e.g. `"cards c"`. A
+    /// qualified column such as `c.name` must then be a column of the view
+    /// with that alias or name; an unqualified one may belong to any of the
+    /// views. Names that are not plain identifiers, or that match no column,
+    /// are rejected with [`MtgjsonError::InvalidArgument`]. Use this before
+    /// building a query from user-supplied column names, e.g. with
+    /// [`SqlBuilder::columns`](crate::SqlBuilder::columns).
+    pub fn check_columns<S: AsRef<str>>(&self, views: &[&str], columns: &[S]) -> Result<()> {
+        let mut known = Vec::new();
+        for entry in views {
+            let (view, alias) = match entry.split_whitespace().collect::<Vec<_>>()[..] {
+                [view] => (view, None),
+                [view, alias] if is_identifier(alias) => (view, Some(alias)),
+                _ => {
+                    return Err(MtgjsonError::InvalidArgument(format!(
+                        "Invalid view: {:?}",
+                        entry
+                    )))
+                }
+            };
+            known.push((view, alias, self.view_columns(view)?));
+        }
+        for column in columns {
+            let column = column.as_ref();
+            if !is_column(column) {
+                return Err(MtgjsonError::InvalidArgument(format!(
+                    "Invalid column name: {:?}",
+                    column
+                )));
+            }
+            let (qualifier, name) = match column.split_once('.') {
+                Some((qualifier, name)) => (Some(qualifier), name),
+                None => (None, column),
+            };
+            let found = known
+                .iter()
+                .filter(|(view, alias, _)| {
+                    qualifier.is_none_or(|q| {
+                        alias.unwrap_or(view).eq_ignore_ascii_case(q)
+                    })
+                })
+                .any(|(_, _, names)| names.iter().any(|k| k.eq_ignore_ascii_case(name)));
+            if !found {
+                return Err(MtgjsonError::InvalidArgument(format!(
+                    "Unknown column {:?} in {}",
+                    column,
+                    views.join(", ")
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Register views over the files of a specific MTGJSON version in the
+    /// schema `alias`, e.g. `v_old.cards`, alongside the regular views.
+    ///
+    /// Files are located with [`CacheManager::version_file`], so `version`
+    /// must be cached (or still served by the CDN). Attaching two versions
+    /// under different aliases lets them be compared in SQL. `alias` must be
+    /// a plain identifier (letters, digits, `_`).
+    pub fn attach_version(&self, alias: &str, version: &str, views: &[&str]) -> Result<()> {
+        if !is_identifier(alias) {
+            return Err(MtgjsonError::InvalidArgument(format!(
+                "Invalid schema alias: {:?}",
+                alias
+            )));
+        }
+        let parquet_files = config::parquet_files();
+        let paths = views
+            .iter()
+            .map(|view| {
+                let filename = parquet_files.get(view).ok_or_else(|| {
+                    MtgjsonError::NotFound(format!("Unknown parquet view: {}", view))
+                })?;
+                let path = self.cache().version_file(filename, version)?;
+                Ok((*view, path))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let _catalog = self.lock_catalog();
+        self.conn
+            .execute_batch(&format!("CREATE SCHEMA IF NOT EXISTS {}", alias))?;
+        for (view, path) in paths {
+            self.create_parquet_view(&format!("{}.{}", alias, view), view, &path)?;
+        }
+        Ok(())
+    }
+
+    /// Drop the schema `alias` and the views
+    /// [`attach_version`](Self::attach_version) registered in it.
+    pub fn detach_version(&self, alias: &str) -> Result<()> {
+        if !is_identifier(alias) {
+            return Err(MtgjsonError::InvalidArgument(format!(
+                "Invalid schema alias: {:?}",
+                alias
+            )));
+        }
+        let _catalog = self.lock_catalog();
+        self.conn
+            .execute_batch(&format!("DROP SCHEMA IF EXISTS {} CASCADE", alias))?;
+        Ok(())
     }
 
     /// Access the underlying DuckDB connection for advanced usage.
@@ -271,36 +658,226 @@ impl Connection {
         &self.conn
     }
 
+    fn registry(&self) -> RwLockReadGuard<'_, HashSet<String>> {
+        self.shared
+            .registered_views
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn registry_mut(&self) -> RwLockWriteGuard<'_, HashSet<String>> {
+        self.shared
+            .registered_views
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn columns(&self) -> RwLockReadGuard<'_, HashMap<String, Vec<String>>> {
+        self.shared
+            .columns
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn columns_mut(&self) -> RwLockWriteGuard<'_, HashMap<String, Vec<String>>> {
+        self.shared
+            .columns
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn lock_catalog(&self) -> MutexGuard<'_, ()> {
+        self.shared
+            .catalog
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
     /// Lazily register a parquet file as a DuckDB view.
     ///
     /// Introspects the parquet schema on first registration and builds
     /// the view SQL dynamically, so the SDK adapts to upstream schema
-    /// changes without code updates.
+    /// changes without code updates. Callers hold the catalog lock.
     fn ensure_view(&self, view_name: &str) -> Result<()> {
-        if self.registered_views.borrow().contains(view_name) {
+        if self.has_view(view_name) {
+            return Ok(());
+        }
+
+        if self.shared.materialize && self.cache().database_path().is_none() {
+            // Nothing is cached yet: download first so the version is known
+            self.cache().ensure_parquet(view_name)?;
+        }
+        match self.store()? {
+            Some(alias) => {
+                self.materialize(&alias, view_name)?;
+                self.conn.execute_batch(&format!(
+                    "CREATE OR REPLACE VIEW {} AS SELECT * FROM {}.{}",
+                    view_name, alias, view_name
+                ))?;
+                debug!(view = view_name, database = alias, "Registered view");
+            }
+            None => {
+                let path = self.cache().ensure_parquet(view_name)?;
+                self.create_parquet_view(view_name, view_name, &path)?;
+            }
+        }
+        self.registry_mut().insert(view_name.to_string());
+        Ok(())
+    }
+
+    /// Attach the materialized database for the cache's current MTGJSON
+    /// version, returning its alias, or `None` if views are not
+    /// materialized or the database cannot be opened.
+    fn store(&self) -> Result<Option<String>> {
+        if !self.shared.materialize {
+            return Ok(None);
+        }
+        let Some(path) = self.cache().database_path() else {
+            return Ok(None);
+        };
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let alias = format!(
+            "mtgjson_{}",
+            stem.replace(|c: char| !c.is_ascii_alphanumeric(), "_")
+        );
+        let mut stores = self
+            .shared
+            .stores
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if stores.last() == Some(&alias) {
+            return Ok(Some(alias));
+        }
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let path_str = path.to_string_lossy().replace('\\', "/").replace('\'', "''");
+        match self
+            .conn
+            .execute_batch(&format!("ATTACH IF NOT EXISTS '{}' AS {}", path_str, alias))
+        {
+            Ok(()) => {
+                self.cache().hold_database(&path)?;
+                debug!(database = path_str, "Attached materialized database");
+                stores.retain(|a| *a != alias);
+                stores.push(alias.clone());
+                Ok(Some(alias))
+            }
+            Err(e) => {
+                warn!(
+                    database = path_str,
+                    "Cannot open materialized database, reading parquet files instead: {}", e
+                );
+                Ok(None)
+            }
+        }
+    }
+
+    /// Load the parquet file behind `view_name` into the table
+    /// `alias.view_name`, with indexes, unless it is already there.
+    fn materialize(&self, alias: &str, view_name: &str) -> Result<()> {
+        let columns: Vec<String> = {
+            let mut stmt = self.conn.prepare(
+                "SELECT column_name FROM duckdb_columns() \
+                 WHERE database_name = ? AND schema_name = 'main' AND table_name = ?",
+            )?;
+            let rows = stmt.query_map([alias, view_name], |row| row.get(0))?;
+            rows.collect::<std::result::Result<_, _>>()?
+        };
+        if !columns.is_empty() {
             return Ok(());
         }
 
-        let path = self.cache.borrow_mut().ensure_parquet(view_name)?;
+        let _span = span!("materialize", view = view_name);
+        let path = self.cache().ensure_parquet(view_name)?;
+        let select = self.parquet_select(view_name, &path)?;
+        let mut sql = format!(
+            "BEGIN TRANSACTION; CREATE TABLE {}.{} AS {};",
+            alias, view_name, select
+        );
+        let described = self.describe(&select)?;
+        for column in MATERIALIZED_INDEXES {
+            if described.iter().any(|(c, _)| c == column) {
+                sql.push_str(&format!(
+                    "CREATE INDEX {}_{}_idx ON {}.{} (\"{}\");",
+                    view_name, column, alias, view_name, column
+                ));
+            }
+        }
+        sql.push_str("COMMIT;");
+        if let Err(e) = self.conn.execute_batch(&sql) {
+            let _ = self.conn.execute_batch("ROLLBACK");
+            return Err(e.into());
+        }
+        info!(view = view_name, database = alias, "Materialized view");
+        Ok(())
+    }
+
+    /// Detach the materialized databases of versions no longer served and
+    /// delete their files.
+    fn detach_stale_stores(&self) {
+        let mut stores = self
+            .shared
+            .stores
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let Some(current) = stores.pop() else {
+            return;
+        };
+        for alias in stores.drain(..) {
+            if let Err(e) = self.conn.execute_batch(&format!("DETACH {}", alias)) {
+                warn!(database = alias, "Failed to detach materialized database: {}", e);
+            }
+        }
+        stores.push(current);
+        drop(stores);
+        let cache = self.cache();
+        if let Some(path) = cache.database_path() {
+            cache.prune_databases(&path);
+        }
+    }
+
+    /// Create (or replace) the view `target` over the parquet file `path`,
+    /// shaped like the logical view `view_name`.
+    fn create_parquet_view(&self, target: &str, view_name: &str, path: &Path) -> Result<()> {
+        let _span = span!("register_view", view = target);
+        self.cache().hold_views(path)?;
+        let select = self.parquet_select(view_name, path)?;
+        self.conn
+            .execute_batch(&format!("CREATE OR REPLACE VIEW {} AS {}", target, select))?;
+        debug!(view = target, file = path.display(), "Registered view");
+        Ok(())
+    }
+
+    /// Build the query that reads the parquet file `path` shaped like the
+    /// logical view `view_name`.
+    fn parquet_select(&self, view_name: &str, path: &Path) -> Result<String> {
         // Use forward slashes for DuckDB compatibility
         let path_str = path.to_string_lossy().replace('\\', "/");
 
         if view_name == "card_legalities" {
-            self.register_legalities_view(&path_str)?;
-            return Ok(());
+            return self.legalities_select(&path_str);
         }
 
         // Hybrid CSV->array detection: static baseline + dynamic heuristic
         let replace_clause = self.build_csv_replace(&path_str, view_name)?;
+        Ok(format!(
+            "SELECT *{} FROM read_parquet('{}')",
+            replace_clause, path_str
+        ))
+    }
 
-        self.conn.execute_batch(&format!(
-            "CREATE OR REPLACE VIEW {} AS SELECT *{} FROM read_parquet('{}')",
-            view_name, replace_clause, path_str
+    /// Column names and types of the rows `select` returns.
+    ///
+    /// Only reads metadata (for parquet, the file footer); no data is scanned.
+    fn describe(&self, select: &str) -> Result<Vec<(String, String)>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT column_name, column_type FROM (DESCRIBE {})",
+            select
         ))?;
-        self.registered_views.borrow_mut().insert(view_name.to_string());
-        eprintln!("Registered view: {} -> {}", view_name, path_str);
-
-        Ok(())
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        Ok(rows.collect::<std::result::Result<_, _>>()?)
     }
 
     /// Build a REPLACE clause using a hybrid static + dynamic approach.
@@ -313,22 +890,9 @@ impl Connection {
     ///
     /// Only reads the parquet footer (DESCRIBE) -- no data scanning needed.
     fn build_csv_replace(&self, path_str: &str, view_name: &str) -> Result<String> {
-        let mut stmt = self.conn.prepare(&format!(
-            "SELECT column_name, column_type FROM \
-             (DESCRIBE SELECT * FROM read_parquet('{}'))",
-            path_str
-        ))?;
-
-        let mut rows = stmt.query([])?;
-        let mut schema: Vec<(String, String)> = Vec::new();
-        let mut schema_map: HashMap<String, String> = HashMap::new();
-
-        while let Some(row) = rows.next()? {
-            let col_name: String = row.get(0)?;
-            let col_type: String = row.get(1)?;
-            schema_map.insert(col_name.clone(), col_type.clone());
-            schema.push((col_name, col_type));
-        }
+        let schema =
+            self.describe(&format!("SELECT * FROM read_parquet('{}')", path_str))?;
+        let schema_map: HashMap<String, String> = schema.iter().cloned().collect();
 
         let static_lists = static_list_columns();
         let ignored = ignored_columns();
@@ -391,62 +955,63 @@ impl Connection {
         }
     }
 
-    /// Register card_legalities by dynamically UNPIVOTing wide format.
+    /// Build the card_legalities query by dynamically UNPIVOTing wide format.
     ///
     /// Introspects the parquet schema and UNPIVOTs all columns except 'uuid'
     /// into (uuid, format, status) rows. Automatically picks up new formats
     /// (e.g. 'timeless', 'oathbreaker') as they appear in the data.
-    fn register_legalities_view(&self, path_str: &str) -> Result<()> {
-        let mut stmt = self.conn.prepare(&format!(
-            "SELECT column_name FROM \
-             (DESCRIBE SELECT * FROM read_parquet('{}'))",
-            path_str
-        ))?;
-
-        let mut rows = stmt.query([])?;
-        let mut all_cols: Vec<String> = Vec::new();
-
-        while let Some(row) = rows.next()? {
-            let col_name: String = row.get(0)?;
-            all_cols.push(col_name);
-        }
+    fn legalities_select(&self, path_str: &str) -> Result<String> {
+        let all_cols: Vec<String> = self
+            .describe(&format!("SELECT * FROM read_parquet('{}')", path_str))?
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
 
         // Everything except 'uuid' is a format column
         let format_cols: Vec<&String> = all_cols.iter().filter(|c| c.as_str() != "uuid").collect();
 
         if format_cols.is_empty() {
             // Fallback: assume row format (test data or different schema)
-            self.conn.execute_batch(&format!(
-                "CREATE OR REPLACE VIEW card_legalities AS \
-                 SELECT * FROM read_parquet('{}')",
-                path_str
-            ))?;
-        } else {
-            let cols_sql: String = format_cols
-                .iter()
-                .map(|c| format!("\"{}\"", c))
-                .collect::<Vec<_>>()
-                .join(", ");
-
-            self.conn.execute_batch(&format!(
-                "CREATE OR REPLACE VIEW card_legalities AS \
-                 SELECT uuid, format, status FROM (\
-                   UNPIVOT (SELECT * FROM read_parquet('{}'))\
-                   ON {}\
-                   INTO NAME format VALUE status\
-                 ) WHERE status IS NOT NULL",
-                path_str, cols_sql
-            ))?;
-        }
-
-        self.registered_views.borrow_mut().insert("card_legalities".to_string());
-        eprintln!(
-            "Registered legalities view (UNPIVOT {} formats): {}",
-            format_cols.len(),
-            path_str
-        );
+            return Ok(format!("SELECT * FROM read_parquet('{}')", path_str));
+        }
 
-        Ok(())
+        let cols_sql: String = format_cols
+            .iter()
+            .map(|c| format!("\"{}\"", c))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        debug!(file = path_str, "UNPIVOT {} legality formats", format_cols.len());
+
+        Ok(format!(
+            "SELECT uuid, format, status FROM (\
+               UNPIVOT (SELECT * FROM read_parquet('{}'))\
+               ON {}\
+               INTO NAME format VALUE status\
+             ) WHERE status IS NOT NULL",
+            path_str, cols_sql
+        ))
+    }
+}
+
+/// The connection a query interface runs on.
+///
+/// Interfaces handed out by [`MtgjsonSdk`](crate::MtgjsonSdk) resolve the
+/// calling thread's connection on each query, so creating one cannot fail
+/// and a connection error surfaces from the query method instead.
+#[derive(Clone, Copy)]
+pub(crate) enum ConnectionRef<'a> {
+    Direct(&'a Connection),
+    Sdk(&'a crate::MtgjsonSdk),
+}
+
+impl<'a> ConnectionRef<'a> {
+    /// Resolve the connection, cloning one for this thread if needed.
+    pub(crate) fn get(self) -> Result<&'a Connection> {
+        match self {
+            Self::Direct(conn) => Ok(conn),
+            Self::Sdk(sdk) => sdk.connection(),
+        }
     }
 }
 
@@ -475,20 +1040,118 @@ fn convert_value_ref(val: ValueRef<'_>) -> serde_json::Value {
             .unwrap_or(serde_json::Value::Null),
         ValueRef::Text(bytes) => {
             let s = String::from_utf8_lossy(bytes).to_string();
-            // Try to parse as JSON if it looks like a JSON structure
             serde_json::Value::String(s)
         }
-        ValueRef::Blob(bytes) => {
-            // Encode blob as base64 or hex string
-            serde_json::Value::String(format!(
-                "blob:{}",
-                bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>()
-            ))
+        ValueRef::Blob(bytes) => serde_json::Value::String(format_blob(bytes)),
+        // Nested, temporal and unsigned types go through the owned representation
+        other => convert_value(other.to_owned()),
+    }
+}
+
+/// Convert an owned DuckDB `Value` to a `serde_json::Value`.
+///
+/// Lists (e.g. the `VARCHAR[]` columns produced by CSV-to-array rewriting)
+/// become JSON arrays, structs and maps become objects, and dates and
+/// timestamps become ISO-8601 strings.
+fn convert_value(val: DuckDbValue) -> serde_json::Value {
+    match val {
+        DuckDbValue::Null => serde_json::Value::Null,
+        DuckDbValue::Boolean(b) => serde_json::Value::Bool(b),
+        DuckDbValue::TinyInt(n) => serde_json::Value::Number(n.into()),
+        DuckDbValue::SmallInt(n) => serde_json::Value::Number(n.into()),
+        DuckDbValue::Int(n) => serde_json::Value::Number(n.into()),
+        DuckDbValue::BigInt(n) => serde_json::Value::Number(n.into()),
+        DuckDbValue::UTinyInt(n) => serde_json::Value::Number(n.into()),
+        DuckDbValue::USmallInt(n) => serde_json::Value::Number(n.into()),
+        DuckDbValue::UInt(n) => serde_json::Value::Number(n.into()),
+        DuckDbValue::UBigInt(n) => serde_json::Value::Number(n.into()),
+        DuckDbValue::HugeInt(n) => i64::try_from(n)
+            .map(|i| serde_json::Value::Number(i.into()))
+            .unwrap_or_else(|_| serde_json::Value::String(n.to_string())),
+        DuckDbValue::Float(f) => serde_json::Number::from_f64(f as f64)
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::Null),
+        DuckDbValue::Double(f) => serde_json::Number::from_f64(f)
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::Null),
+        DuckDbValue::Decimal(d) => d
+            .to_string()
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::Null),
+        DuckDbValue::Text(s) | DuckDbValue::Enum(s) => serde_json::Value::String(s),
+        DuckDbValue::Date32(days) => serde_json::Value::String(format_date(days as i64)),
+        DuckDbValue::Timestamp(unit, ts) => {
+            let micros = match unit {
+                TimeUnit::Second => ts.saturating_mul(1_000_000),
+                TimeUnit::Millisecond => ts.saturating_mul(1_000),
+                TimeUnit::Microsecond => ts,
+                TimeUnit::Nanosecond => ts / 1_000,
+            };
+            serde_json::Value::String(format_timestamp(micros))
         }
-        _ => {
-            // For other types (Date, Time, Timestamp, Interval, List, etc.),
-            // convert to string representation
-            serde_json::Value::Null
+        DuckDbValue::List(items) | DuckDbValue::Array(items) => {
+            serde_json::Value::Array(items.into_iter().map(convert_value).collect())
         }
+        DuckDbValue::Struct(fields) => serde_json::Value::Object(
+            fields
+                .iter()
+                .map(|(k, v)| (k.clone(), convert_value(v.clone())))
+                .collect(),
+        ),
+        DuckDbValue::Map(entries) => serde_json::Value::Object(
+            entries
+                .iter()
+                .map(|(k, v)| {
+                    let key = match convert_value(k.clone()) {
+                        serde_json::Value::String(s) => s,
+                        other => other.to_string(),
+                    };
+                    (key, convert_value(v.clone()))
+                })
+                .collect(),
+        ),
+        DuckDbValue::Union(inner) => convert_value(*inner),
+        DuckDbValue::Blob(bytes) => serde_json::Value::String(format_blob(&bytes)),
+        // Time-of-day and interval values have no natural JSON representation
+        _ => serde_json::Value::Null,
     }
 }
+
+/// Encode a blob as a `blob:`-prefixed hex string.
+pub(crate) fn format_blob(bytes: &[u8]) -> String {
+    format!(
+        "blob:{}",
+        bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>()
+    )
+}
+
+/// Format a microsecond offset from the Unix epoch as `YYYY-MM-DD HH:MM:SS`.
+pub(crate) fn format_timestamp(micros: i64) -> String {
+    let days = micros.div_euclid(86_400_000_000);
+    let secs = micros.rem_euclid(86_400_000_000) / 1_000_000;
+    format!(
+        "{} {:02}:{:02}:{:02}",
+        format_date(days),
+        secs / 3600,
+        (secs % 3600) / 60,
+        secs % 60
+    )
+}
+
+/// Format a day offset from the Unix epoch as `YYYY-MM-DD`.
+pub(crate) fn format_date(days_since_epoch: i64) -> String {
+    // Civil-from-days (Howard Hinnant's algorithm)
+    let z = days_since_epoch + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
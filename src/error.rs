@@ -17,6 +17,12 @@ pub enum MtgjsonError {
 
     #[error("Invalid argument: {0}")]
     InvalidArgument(String),
+
+    #[error("Integrity check failed: {0}")]
+    Integrity(String),
+
+    #[error("Timed out waiting for cache lock: {0}")]
+    LockTimeout(String),
 }
 
 pub type Result<T> = std::result::Result<T, MtgjsonError>;
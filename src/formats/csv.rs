@@ -0,0 +1,266 @@
+//! Collection CSV files as exported by collection-tracking apps.
+//!
+//! Columns are matched by header name, case-insensitively and ignoring `_`
+//! and `-`, so exports from most apps import without changes:
+//!
+//! | Field | Accepted headers |
+//! |-------|------------------|
+//! | quantity | `Quantity`, `Count`, `Qty`, `Amount` (default 1) |
+//! | name | `Name`, `Card Name`, `Card` |
+//! | set code | `Set Code`, `Set`, `Edition Code`, `Setcode` |
+//! | collector number | `Collector Number`, `Card Number`, `Number`, `CN` |
+//! | finish | `Finish`, `Foil` (`foil`/`true`/`yes`/`1`, `etched`, else normal) |
+//! | condition | `Condition` (default `NM`) |
+//! | language | `Language` (default `English`) |
+//! | acquired price | `Purchase Price`, `Acquired Price`, `Price Bought` |
+//! | acquired date | `Purchase Date`, `Acquired Date`, `Date Bought` |
+//! | UUID | `UUID`, `MTGJSON UUID` |
+//!
+//! Rows with a UUID are taken as-is; other rows are resolved by name, set
+//! code and collector number.
+
+use std::collections::HashMap;
+
+use crate::error::{MtgjsonError, Result};
+use crate::models::CollectionEntry;
+use crate::queries::cards::CardQuery;
+
+use super::{ImportError, ImportErrorKind, ImportResult, Resolver};
+
+/// Header written by [`export_collection`].
+const EXPORT_HEADER: [&str; 10] = [
+    "Quantity",
+    "Name",
+    "Set Code",
+    "Collector Number",
+    "Finish",
+    "Condition",
+    "Language",
+    "Purchase Price",
+    "Purchase Date",
+    "UUID",
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Field {
+    Quantity,
+    Name,
+    SetCode,
+    Number,
+    Finish,
+    Condition,
+    Language,
+    Price,
+    Date,
+    Uuid,
+}
+
+fn field_for_header(header: &str) -> Option<Field> {
+    let normalized = header
+        .trim()
+        .to_ascii_lowercase()
+        .replace(['_', '-'], " ");
+    let field = match normalized.as_str() {
+        "quantity" | "count" | "qty" | "amount" => Field::Quantity,
+        "name" | "card name" | "card" => Field::Name,
+        "set code" | "set" | "edition code" | "setcode" => Field::SetCode,
+        "collector number" | "card number" | "number" | "cn" => Field::Number,
+        "finish" | "foil" => Field::Finish,
+        "condition" => Field::Condition,
+        "language" => Field::Language,
+        "purchase price" | "acquired price" | "price bought" => Field::Price,
+        "purchase date" | "acquired date" | "date bought" => Field::Date,
+        "uuid" | "mtgjson uuid" => Field::Uuid,
+        _ => return None,
+    };
+    Some(field)
+}
+
+// ---------------------------------------------------------------------------
+// Import
+// ---------------------------------------------------------------------------
+
+/// Parse a collection CSV and resolve each row to a [`CollectionEntry`].
+///
+/// Returns [`MtgjsonError::InvalidArgument`] if the header has neither a
+/// name nor a UUID column. Row-level problems are reported in
+/// [`ImportResult::errors`] with the row's line number.
+pub fn import_collection(
+    cards: &CardQuery<'_>,
+    text: &str,
+) -> Result<ImportResult<CollectionEntry>> {
+    let mut reader = ::csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(::csv::Trim::All)
+        .from_reader(text.as_bytes());
+
+    let headers = reader
+        .headers()
+        .map_err(|e| MtgjsonError::InvalidArgument(format!("Invalid CSV header: {}", e)))?
+        .clone();
+    let mut columns: HashMap<Field, usize> = HashMap::new();
+    for (idx, header) in headers.iter().enumerate() {
+        if let Some(field) = field_for_header(header) {
+            columns.entry(field).or_insert(idx);
+        }
+    }
+    if !columns.contains_key(&Field::Name) && !columns.contains_key(&Field::Uuid) {
+        return Err(MtgjsonError::InvalidArgument(
+            "CSV needs a 'Name' or 'UUID' column".to_string(),
+        ));
+    }
+
+    let mut resolver = Resolver::new(cards);
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+
+    for record in reader.records() {
+        let record = match record {
+            Ok(r) => r,
+            Err(e) => {
+                errors.push(ImportError {
+                    line: e.position().map_or(0, |p| p.line() as usize),
+                    text: String::new(),
+                    kind: ImportErrorKind::Malformed(e.to_string()),
+                });
+                continue;
+            }
+        };
+        let line = record.position().map_or(0, |p| p.line() as usize);
+        let text = record.iter().collect::<Vec<_>>().join(",");
+        let get = |field: Field| {
+            columns
+                .get(&field)
+                .and_then(|&i| record.get(i))
+                .filter(|v| !v.is_empty())
+        };
+
+        let mut entry = match parse_row(get) {
+            Ok(entry) => entry,
+            Err(msg) => {
+                errors.push(ImportError {
+                    line,
+                    text,
+                    kind: ImportErrorKind::Malformed(msg),
+                });
+                continue;
+            }
+        };
+
+        if entry.uuid.is_empty() {
+            let name = get(Field::Name).unwrap_or_default();
+            match resolver.resolve(name, get(Field::SetCode), get(Field::Number))? {
+                Ok(uuid) => entry.uuid = uuid,
+                Err(kind) => {
+                    errors.push(ImportError { line, text, kind });
+                    continue;
+                }
+            }
+        }
+        entries.push(entry);
+    }
+
+    Ok(ImportResult { entries, errors })
+}
+
+/// Build an entry from a row's fields; `uuid` is left empty when the row has
+/// no UUID column value.
+fn parse_row<'r>(
+    get: impl Fn(Field) -> Option<&'r str>,
+) -> std::result::Result<CollectionEntry, String> {
+    let uuid = get(Field::Uuid).unwrap_or_default();
+    if uuid.is_empty() && get(Field::Name).is_none() {
+        return Err("missing card name".to_string());
+    }
+
+    let quantity = match get(Field::Quantity) {
+        Some(q) => q
+            .parse::<i64>()
+            .ok()
+            .filter(|q| *q > 0)
+            .ok_or_else(|| format!("invalid quantity '{}'", q))?,
+        None => 1,
+    };
+
+    let finish = match get(Field::Finish).map(str::to_ascii_lowercase).as_deref() {
+        None | Some("normal" | "nonfoil" | "false" | "no" | "0") => "normal",
+        Some("foil" | "true" | "yes" | "1") => "foil",
+        Some("etched") => "etched",
+        Some(other) => return Err(format!("unknown finish '{}'", other)),
+    };
+
+    let acquired_price = match get(Field::Price) {
+        Some(p) => Some(
+            p.trim_start_matches(['$', '€', '£'])
+                .parse::<f64>()
+                .map_err(|_| format!("invalid purchase price '{}'", p))?,
+        ),
+        None => None,
+    };
+
+    let mut entry = CollectionEntry::new(uuid, quantity);
+    entry.finish = finish.to_string();
+    if let Some(c) = get(Field::Condition) {
+        entry.condition = c.to_string();
+    }
+    if let Some(l) = get(Field::Language) {
+        entry.language = l.to_string();
+    }
+    entry.acquired_price = acquired_price;
+    entry.acquired_date = get(Field::Date).map(String::from);
+    Ok(entry)
+}
+
+// ---------------------------------------------------------------------------
+// Export
+// ---------------------------------------------------------------------------
+
+/// Write collection entries as CSV, looking up each card's name, set code
+/// and collector number.
+///
+/// The output re-imports losslessly with [`import_collection`].
+pub fn export_collection(cards: &CardQuery<'_>, entries: &[CollectionEntry]) -> Result<String> {
+    let uuids: Vec<&str> = entries.iter().map(|e| e.uuid.as_str()).collect();
+    let printings: HashMap<String, serde_json::Value> = if uuids.is_empty() {
+        HashMap::new()
+    } else {
+        cards
+            .get_by_uuids(&uuids)?
+            .into_iter()
+            .filter_map(|c| Some((c.get("uuid")?.as_str()?.to_string(), c)))
+            .collect()
+    };
+
+    let mut writer = ::csv::Writer::from_writer(Vec::new());
+    let io_err = |e: ::csv::Error| MtgjsonError::Io(e.into());
+    writer.write_record(EXPORT_HEADER).map_err(io_err)?;
+
+    for entry in entries {
+        let card = printings.get(&entry.uuid);
+        let field = |key: &str| {
+            card.and_then(|c| c.get(key))
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string()
+        };
+        writer
+            .write_record([
+                entry.quantity.to_string(),
+                field("name"),
+                field("setCode"),
+                field("number"),
+                entry.finish.clone(),
+                entry.condition.clone(),
+                entry.language.clone(),
+                entry.acquired_price.map(|p| p.to_string()).unwrap_or_default(),
+                entry.acquired_date.clone().unwrap_or_default(),
+                entry.uuid.clone(),
+            ])
+            .map_err(io_err)?;
+    }
+
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| MtgjsonError::Io(e.into_error()))?;
+    Ok(String::from_utf8(bytes).expect("CSV writer output is UTF-8"))
+}
@@ -0,0 +1,227 @@
+//! Import and export of decks and collections in common text formats.
+//!
+//! - [`text`]: plain decklists (`4 Lightning Bolt (M10) 146`) and the MTG
+//!   Arena export format with `Deck` / `Sideboard` section headers.
+//! - [`csv`]: collection spreadsheets as exported by collection apps, mapped
+//!   to [`CollectionEntry`](crate::models::CollectionEntry) rows.
+//!
+//! Imports resolve every line to a card UUID through
+//! [`CardQuery::get_by_name`], narrowed by set code and collector number when
+//! the line has them. Lines that cannot be parsed or resolved are returned as
+//! [`ImportError`]s alongside the resolved entries rather than dropped.
+
+pub mod csv;
+pub mod text;
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::error::Result;
+use crate::models::Deck;
+use crate::queries::cards::CardQuery;
+
+// ---------------------------------------------------------------------------
+// DeckEntry
+// ---------------------------------------------------------------------------
+
+/// Deck section a decklist line belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Section {
+    Commander,
+    Companion,
+    Main,
+    Sideboard,
+}
+
+impl Section {
+    /// Header used for this section in decklist text.
+    pub fn header(&self) -> &'static str {
+        match self {
+            Section::Commander => "Commander",
+            Section::Companion => "Companion",
+            Section::Main => "Deck",
+            Section::Sideboard => "Sideboard",
+        }
+    }
+}
+
+/// One decklist line: a quantity of a card, optionally pinned to a printing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeckEntry {
+    pub quantity: i64,
+    pub name: String,
+    pub set_code: Option<String>,
+    pub number: Option<String>,
+    pub section: Section,
+    /// Resolved card UUID; set on entries returned by an import.
+    pub uuid: Option<String>,
+}
+
+impl DeckEntry {
+    /// Flatten a [`Deck`] into entries, commander first and sideboard last.
+    pub fn from_deck(deck: &Deck) -> Vec<DeckEntry> {
+        let boards = [
+            (Section::Commander, &deck.commander),
+            (Section::Main, &deck.main_board),
+            (Section::Sideboard, &deck.side_board),
+        ];
+        boards
+            .into_iter()
+            .flat_map(|(section, cards)| {
+                cards.iter().map(move |c| DeckEntry {
+                    quantity: c.count,
+                    name: c.name.clone(),
+                    set_code: Some(c.set_code.clone()),
+                    number: Some(c.number.clone()),
+                    section,
+                    uuid: Some(c.uuid.clone()),
+                })
+            })
+            .collect()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Import results
+// ---------------------------------------------------------------------------
+
+/// Outcome of an import: resolved entries plus one error per rejected line.
+#[derive(Debug, Clone)]
+pub struct ImportResult<T> {
+    pub entries: Vec<T>,
+    pub errors: Vec<ImportError>,
+}
+
+impl<T> ImportResult<T> {
+    /// `true` if every line was imported.
+    pub fn is_complete(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// A line that could not be imported.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportError {
+    /// 1-based line number in the input.
+    pub line: usize,
+    /// The offending line, trimmed.
+    pub text: String,
+    pub kind: ImportErrorKind,
+}
+
+/// Why a line could not be imported.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportErrorKind {
+    /// The line does not match the expected format.
+    Malformed(String),
+    /// No card with this exact name exists.
+    UnknownCard { name: String },
+    /// The card exists, but not with the given set code / collector number.
+    UnknownPrinting {
+        name: String,
+        set_code: Option<String>,
+        number: Option<String>,
+    },
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            ImportErrorKind::Malformed(msg) => write!(f, "{}", msg)?,
+            ImportErrorKind::UnknownCard { name } => write!(f, "unknown card '{}'", name)?,
+            ImportErrorKind::UnknownPrinting {
+                name,
+                set_code,
+                number,
+            } => {
+                write!(f, "no printing of '{}'", name)?;
+                if let Some(sc) = set_code {
+                    write!(f, " in set {}", sc)?;
+                }
+                if let Some(n) = number {
+                    write!(f, " with number {}", n)?;
+                }
+            }
+        }
+        write!(f, " ({:?})", self.text)
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+// ---------------------------------------------------------------------------
+// Resolver
+// ---------------------------------------------------------------------------
+
+/// Card lookup key: name, set code, collector number.
+type PrintingKey = (String, Option<String>, Option<String>);
+
+/// Resolves (name, set code, number) to a UUID, memoizing repeated lines.
+pub(crate) struct Resolver<'q, 'a> {
+    cards: &'q CardQuery<'a>,
+    seen: HashMap<PrintingKey, std::result::Result<String, ImportErrorKind>>,
+}
+
+impl<'q, 'a> Resolver<'q, 'a> {
+    pub(crate) fn new(cards: &'q CardQuery<'a>) -> Self {
+        Self {
+            cards,
+            seen: HashMap::new(),
+        }
+    }
+
+    /// Look up the UUID for a card line.
+    ///
+    /// Without a set code an arbitrary but stable printing is chosen (the
+    /// lowest set code and collector number). The outer `Result` carries
+    /// query failures; the inner one a resolution failure for this line.
+    pub(crate) fn resolve(
+        &mut self,
+        name: &str,
+        set_code: Option<&str>,
+        number: Option<&str>,
+    ) -> Result<std::result::Result<String, ImportErrorKind>> {
+        let set_code = set_code.map(str::to_uppercase);
+        let key = (name.to_string(), set_code.clone(), number.map(String::from));
+        if let Some(hit) = self.seen.get(&key) {
+            return Ok(hit.clone());
+        }
+
+        let field = |card: &serde_json::Value, key: &str| {
+            card.get(key)
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string()
+        };
+        let mut printings: Vec<(String, String, String)> = self
+            .cards
+            .get_by_name(name, set_code.as_deref())?
+            .iter()
+            .map(|c| (field(c, "setCode"), field(c, "number"), field(c, "uuid")))
+            .filter(|(_, n, _)| number.is_none_or(|want| n.eq_ignore_ascii_case(want)))
+            .collect();
+        printings.sort();
+
+        let outcome = match printings.into_iter().next() {
+            Some((_, _, uuid)) => Ok(uuid),
+            None if set_code.is_none() && number.is_none() => {
+                Err(ImportErrorKind::UnknownCard {
+                    name: name.to_string(),
+                })
+            }
+            None if self.cards.get_by_name(name, None)?.is_empty() => {
+                Err(ImportErrorKind::UnknownCard {
+                    name: name.to_string(),
+                })
+            }
+            None => Err(ImportErrorKind::UnknownPrinting {
+                name: name.to_string(),
+                set_code,
+                number: number.map(String::from),
+            }),
+        };
+        self.seen.insert(key, outcome.clone());
+        Ok(outcome)
+    }
+}
@@ -0,0 +1,268 @@
+//! Plain-text decklists and the MTG Arena import/export format.
+//!
+//! Both formats share one grammar, so [`parse`] accepts either:
+//!
+//! ```text
+//! Deck
+//! 4 Lightning Bolt (M10) 146
+//! 4x Counterspell
+//! 2 Fire // Ice (MH2) 290
+//!
+//! Sideboard
+//! SB: 2 Pyroblast
+//! ```
+//!
+//! - A line is `<quantity>[x] <name> [(<SET>) [<number>]]`. Trailing Arena
+//!   finish markers such as `*F*` are ignored.
+//! - `Deck`/`Main`, `Sideboard`, `Commander` and `Companion` (optionally with
+//!   a trailing `:` or leading `//`) switch sections. `About` starts Arena
+//!   metadata that is skipped until the next section header.
+//! - `SB:` puts a single line in the sideboard.
+//! - In a list without headers, a blank line after the main deck starts the
+//!   sideboard, as in Arena exports.
+//! - Other lines starting with `//` or `#` are comments.
+
+use crate::error::Result;
+use crate::queries::cards::CardQuery;
+
+use super::{DeckEntry, ImportError, ImportErrorKind, ImportResult, Resolver, Section};
+
+// ---------------------------------------------------------------------------
+// Parsing
+// ---------------------------------------------------------------------------
+
+/// Parse decklist or Arena text without resolving cards.
+///
+/// Returned entries have `uuid: None`. Lines that do not match the grammar
+/// are reported as [`ImportErrorKind::Malformed`].
+pub fn parse(text: &str) -> ImportResult<DeckEntry> {
+    let (lines, errors) = parse_lines(text);
+    ImportResult {
+        entries: lines.into_iter().map(|(_, _, entry)| entry).collect(),
+        errors,
+    }
+}
+
+/// Parse decklist or Arena text and resolve each line to a card UUID.
+///
+/// Lines naming a set (and optionally a collector number) resolve to that
+/// printing; bare names resolve to a stable arbitrary printing. Unresolved
+/// lines are returned in [`ImportResult::errors`].
+pub fn import(cards: &CardQuery<'_>, text: &str) -> Result<ImportResult<DeckEntry>> {
+    let (lines, mut errors) = parse_lines(text);
+    let mut entries = Vec::with_capacity(lines.len());
+    let mut resolver = Resolver::new(cards);
+
+    for (line, text, mut entry) in lines {
+        match resolver.resolve(&entry.name, entry.set_code.as_deref(), entry.number.as_deref())? {
+            Ok(uuid) => {
+                entry.uuid = Some(uuid);
+                entries.push(entry);
+            }
+            Err(kind) => errors.push(ImportError { line, text, kind }),
+        }
+    }
+    errors.sort_by_key(|e| e.line);
+
+    Ok(ImportResult { entries, errors })
+}
+
+/// Parse every line, keeping the 1-based line number and text of each entry
+/// so resolution errors can point back at the input.
+fn parse_lines(text: &str) -> (Vec<(usize, String, DeckEntry)>, Vec<ImportError>) {
+    let mut entries: Vec<(usize, String, DeckEntry)> = Vec::new();
+    let mut errors = Vec::new();
+    // `None` while inside an Arena `About` block
+    let mut section = Some(Section::Main);
+    let mut saw_header = false;
+
+    for (idx, raw) in text.lines().enumerate() {
+        let line = raw.trim();
+
+        if line.is_empty() {
+            let main_started = entries.iter().any(|(_, _, e)| e.section == Section::Main);
+            if !saw_header && section == Some(Section::Main) && main_started {
+                section = Some(Section::Sideboard);
+            }
+            continue;
+        }
+
+        if let Some(header) = parse_header(line) {
+            saw_header = true;
+            section = header;
+            continue;
+        }
+        if line.starts_with("//") || line.starts_with('#') {
+            continue;
+        }
+        let Some(current) = section else {
+            continue;
+        };
+
+        let (line_section, body) = match strip_prefix_ignore_case(line, "SB:") {
+            Some(rest) => (Section::Sideboard, rest.trim_start()),
+            None => (current, line),
+        };
+        match parse_card_line(body) {
+            Ok((quantity, name, set_code, number)) => entries.push((
+                idx + 1,
+                line.to_string(),
+                DeckEntry {
+                    quantity,
+                    name,
+                    set_code,
+                    number,
+                    section: line_section,
+                    uuid: None,
+                },
+            )),
+            Err(msg) => errors.push(ImportError {
+                line: idx + 1,
+                text: line.to_string(),
+                kind: ImportErrorKind::Malformed(msg),
+            }),
+        }
+    }
+
+    (entries, errors)
+}
+
+/// Recognize a section header, returning the new section (`None` for `About`).
+fn parse_header(line: &str) -> Option<Option<Section>> {
+    let word = line
+        .trim_start_matches("//")
+        .trim()
+        .trim_end_matches(':')
+        .trim()
+        .to_ascii_lowercase();
+    match word.as_str() {
+        "deck" | "main" | "maindeck" | "mainboard" => Some(Some(Section::Main)),
+        "sideboard" | "side" => Some(Some(Section::Sideboard)),
+        "commander" => Some(Some(Section::Commander)),
+        "companion" => Some(Some(Section::Companion)),
+        "about" => Some(None),
+        _ => None,
+    }
+}
+
+/// Parsed card line: quantity, name, set code, collector number.
+type CardLine = (i64, String, Option<String>, Option<String>);
+
+/// Split `<quantity>[x] <name> [(<SET>) [<number>]]`.
+fn parse_card_line(line: &str) -> std::result::Result<CardLine, String> {
+    let (qty_token, rest) = line
+        .split_once(char::is_whitespace)
+        .ok_or_else(|| "expected '<quantity> <card name>'".to_string())?;
+    let quantity: i64 = qty_token
+        .trim_end_matches(['x', 'X'])
+        .parse()
+        .map_err(|_| format!("invalid quantity '{}'", qty_token))?;
+    if quantity <= 0 {
+        return Err(format!("quantity must be positive, got {}", quantity));
+    }
+
+    // Drop a trailing Arena finish marker (`*F*`, `*E*`)
+    let mut rest = rest.trim();
+    if let Some((head, marker)) = rest.strip_suffix('*').and_then(|r| r.rsplit_once(" *")) {
+        if marker.len() <= 2 {
+            rest = head.trim_end();
+        }
+    }
+
+    let (name, set_code, number) = match rest.rfind(" (") {
+        Some(open) => {
+            let after = &rest[open + 2..];
+            match after.split_once(')') {
+                Some((code, tail))
+                    if !code.is_empty()
+                        && code.chars().all(|c| c.is_ascii_alphanumeric())
+                        && !tail.trim().contains(char::is_whitespace) =>
+                {
+                    let tail = tail.trim();
+                    (
+                        rest[..open].trim(),
+                        Some(code.to_uppercase()),
+                        (!tail.is_empty()).then(|| tail.to_string()),
+                    )
+                }
+                _ => (rest, None, None),
+            }
+        }
+        None => (rest, None, None),
+    };
+
+    if name.is_empty() {
+        return Err("missing card name".to_string());
+    }
+    Ok((quantity, name.to_string(), set_code, number))
+}
+
+fn strip_prefix_ignore_case<'s>(line: &'s str, prefix: &str) -> Option<&'s str> {
+    let head = line.get(..prefix.len())?;
+    head.eq_ignore_ascii_case(prefix)
+        .then(|| &line[prefix.len()..])
+}
+
+// ---------------------------------------------------------------------------
+// Writing
+// ---------------------------------------------------------------------------
+
+/// Write entries as a plain decklist.
+///
+/// A deck with only main and sideboard cards is written without a main-deck
+/// header, with the sideboard after a blank line and `Sideboard` header.
+/// Decks with a commander or companion get a header for every section.
+pub fn write_decklist(entries: &[DeckEntry]) -> String {
+    let only_main_and_side = entries
+        .iter()
+        .all(|e| matches!(e.section, Section::Main | Section::Sideboard));
+    write_sections(entries, !only_main_and_side)
+}
+
+/// Write entries in MTG Arena format, with a header for every section.
+pub fn write_mtga(entries: &[DeckEntry]) -> String {
+    write_sections(entries, true)
+}
+
+fn write_sections(entries: &[DeckEntry], main_header: bool) -> String {
+    let order = [
+        Section::Commander,
+        Section::Companion,
+        Section::Main,
+        Section::Sideboard,
+    ];
+    let mut blocks = Vec::new();
+    for section in order {
+        let lines: Vec<String> = entries
+            .iter()
+            .filter(|e| e.section == section)
+            .map(format_line)
+            .collect();
+        if lines.is_empty() {
+            continue;
+        }
+        let mut block = String::new();
+        if section != Section::Main || main_header {
+            block.push_str(section.header());
+            block.push('\n');
+        }
+        for line in lines {
+            block.push_str(&line);
+            block.push('\n');
+        }
+        blocks.push(block);
+    }
+    blocks.join("\n")
+}
+
+fn format_line(entry: &DeckEntry) -> String {
+    let mut line = format!("{} {}", entry.quantity, entry.name);
+    if let Some(sc) = &entry.set_code {
+        line.push_str(&format!(" ({})", sc));
+        if let Some(n) = &entry.number {
+            line.push(' ');
+            line.push_str(n);
+        }
+    }
+    line
+}
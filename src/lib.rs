@@ -18,6 +18,10 @@
 //! let pack = sdk.booster().open_pack("MH3", "draft").unwrap();
 //! ```
 
+// Declared first so its macros are visible in every other module
+#[macro_use]
+mod logging;
+
 #[cfg(feature = "async")]
 pub mod async_client;
 pub mod booster;
@@ -25,21 +29,40 @@ pub mod cache;
 pub mod config;
 pub mod connection;
 pub mod error;
+pub mod formats;
+pub(crate) mod lock;
 pub mod models;
 pub mod queries;
 pub mod sql_builder;
+pub mod sql_expr;
+pub mod sql_value;
+pub mod stream;
+pub mod validator;
 
 #[cfg(feature = "async")]
 pub use async_client::AsyncMtgjsonSdk;
-pub use cache::CacheManager;
+pub use cache::{
+    BundleManifest, CacheManager, CacheManifest, CacheStats, CachedFile, DownloadProgress,
+    DownloadProgressFn, FileRecord, PrefetchProgress, VerifyReport,
+};
 pub use connection::Connection;
 pub use error::{MtgjsonError, Result};
 pub use sql_builder::SqlBuilder;
+pub use sql_expr::SqlExpr;
+pub use sql_value::SqlValue;
+pub use stream::{RowStream, TypedRowStream};
+pub use validator::DeckValidator;
+
+/// Re-export of the Arrow crates used by the `_arrow` query methods.
+#[cfg(feature = "arrow")]
+pub use duckdb::arrow;
 
 use std::collections::HashMap;
 use std::fmt;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
 use std::time::Duration;
+use thread_local::ThreadLocal;
 
 // ---------------------------------------------------------------------------
 // MtgjsonSdkBuilder
@@ -53,6 +76,18 @@ pub struct MtgjsonSdkBuilder {
     cache_dir: Option<PathBuf>,
     offline: bool,
     timeout: Duration,
+    bundle: Option<PathBuf>,
+    cdn_base: Option<String>,
+    mirrors: Vec<String>,
+    max_retries: Option<u32>,
+    retry_backoff: Option<Duration>,
+    keep_versions: Option<usize>,
+    pinned_version: Option<String>,
+    lock_timeout: Option<Duration>,
+    quota: Option<u64>,
+    download_progress: Option<DownloadProgressFn>,
+    download_concurrency: Option<usize>,
+    materialize: bool,
 }
 
 impl Default for MtgjsonSdkBuilder {
@@ -61,6 +96,18 @@ impl Default for MtgjsonSdkBuilder {
             cache_dir: None,
             offline: false,
             timeout: Duration::from_secs(120),
+            bundle: None,
+            cdn_base: None,
+            mirrors: Vec::new(),
+            max_retries: None,
+            retry_backoff: None,
+            keep_versions: None,
+            pinned_version: None,
+            lock_timeout: None,
+            quota: None,
+            download_progress: None,
+            download_concurrency: None,
+            materialize: false,
         }
     }
 }
@@ -93,15 +140,185 @@ impl MtgjsonSdkBuilder {
         self
     }
 
+    /// Set the base URL data files and `Meta.json` are downloaded from.
+    ///
+    /// Defaults to [`config::CDN_BASE`]. Use this to point the SDK at an
+    /// internal mirror of the MTGJSON CDN with the same layout.
+    pub fn cdn_base(mut self, url: impl Into<String>) -> Self {
+        self.cdn_base = Some(url.into());
+        self
+    }
+
+    /// Set fallback base URLs, tried in order whenever a request to the
+    /// [`cdn_base`](Self::cdn_base) fails.
+    pub fn mirrors<I, S>(mut self, urls: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.mirrors = urls.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set how many times a failed download is retried against each base
+    /// URL before falling back to the next mirror.
+    ///
+    /// Defaults to 3. Only transient failures (connection errors, timeouts,
+    /// 5xx and 429 responses) are retried.
+    pub fn max_retries(mut self, retries: u32) -> Self {
+        self.max_retries = Some(retries);
+        self
+    }
+
+    /// Set the delay before the first download retry; it doubles with each
+    /// further retry.
+    ///
+    /// Defaults to 1 second.
+    pub fn retry_backoff(mut self, backoff: Duration) -> Self {
+        self.retry_backoff = Some(backoff);
+        self
+    }
+
+    /// Set how many versions replaced by [`MtgjsonSdk::refresh`] are kept
+    /// for [`MtgjsonSdk::rollback`].
+    ///
+    /// Defaults to 2. With 0, refresh keeps no old files and rollback is
+    /// unavailable.
+    pub fn keep_versions(mut self, versions: usize) -> Self {
+        self.keep_versions = Some(versions);
+        self
+    }
+
+    /// Set how long to wait for cache locks held by other processes sharing
+    /// the cache directory.
+    ///
+    /// Defaults to 5 minutes; see [`CacheManager::lock_timeout`].
+    pub fn lock_timeout(mut self, timeout: Duration) -> Self {
+        self.lock_timeout = Some(timeout);
+        self
+    }
+
+    /// Limit the cache directory to `bytes`, evicting the least recently
+    /// used files and archived versions when it grows past that.
+    ///
+    /// Files backing registered views are never evicted. The quota is also
+    /// enforced on [`build()`](Self::build). See [`CacheManager::quota`].
+    pub fn cache_quota(mut self, bytes: u64) -> Self {
+        self.quota = Some(bytes);
+        self
+    }
+
+    /// Report the progress of every file download to `callback`, e.g. to
+    /// drive a progress bar.
+    ///
+    /// `callback` receives the file's path, the bytes received so far and,
+    /// if the server reported it, the file's size. See
+    /// [`CacheManager::download_progress`].
+    pub fn download_progress<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&DownloadProgress<'_>) + Send + Sync + 'static,
+    {
+        self.download_progress = Some(Arc::new(callback));
+        self
+    }
+
+    /// Set how many files are downloaded at once when several views need
+    /// files that are not cached yet.
+    ///
+    /// Defaults to 4; 1 downloads one file at a time. See
+    /// [`CacheManager::download_concurrency`].
+    pub fn download_concurrency(mut self, downloads: usize) -> Self {
+        self.download_concurrency = Some(downloads);
+        self
+    }
+
+    /// Materialize views into a persistent DuckDB database per MTGJSON
+    /// version, for fast startup and queries.
+    ///
+    /// Each view's parquet file is loaded once into an indexed table of
+    /// `databases/<version>.duckdb` in the cache directory; later runs
+    /// reopen it instead of reading the parquet files. New versions are only
+    /// picked up by [`MtgjsonSdk::refresh`], which builds a new database.
+    /// Defaults to `false`. See [`Connection::new_materialized`].
+    pub fn materialize(mut self, materialize: bool) -> Self {
+        self.materialize = materialize;
+        self
+    }
+
+    /// Serve a specific MTGJSON version (e.g. `"5.2.2+20240101"`) instead
+    /// of the latest one.
+    ///
+    /// The version must be in the cache: either current, or kept under
+    /// `versions/` by an earlier refresh (see [`keep_versions`](Self::keep_versions)).
+    /// Nothing is downloaded unless the CDN still serves this version, and
+    /// [`MtgjsonSdk::refresh`] does nothing. See
+    /// [`CacheManager::pinned_version`].
+    pub fn pin_version(mut self, version: impl Into<String>) -> Self {
+        self.pinned_version = Some(version.into());
+        self
+    }
+
+    /// Load the cache from a bundle written by
+    /// [`CacheManager::export_bundle`].
+    ///
+    /// On [`build()`](Self::build) the bundle is extracted into the cache
+    /// directory unless the cache already holds the bundled version. Combine
+    /// with [`offline(true)`](Self::offline) on machines without CDN access.
+    pub fn bundle<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.bundle = Some(path.as_ref().to_path_buf());
+        self
+    }
+
     /// Build the SDK, initializing the cache and DuckDB connection.
     ///
     /// This may trigger a version check against the CDN (unless offline mode
     /// is enabled) but does **not** download any data files eagerly -- they
     /// are fetched lazily on first query.
     pub fn build(self) -> Result<MtgjsonSdk> {
-        let cache = CacheManager::new(self.cache_dir, self.offline, self.timeout)?;
-        let conn = Connection::new(cache)?;
-        Ok(MtgjsonSdk { conn })
+        let mut cache = CacheManager::new(self.cache_dir, self.offline, self.timeout)?;
+        if let Some(base) = self.cdn_base {
+            cache.cdn_base = base;
+        }
+        cache.mirrors = self.mirrors;
+        cache.download_progress = self.download_progress;
+        if let Some(downloads) = self.download_concurrency {
+            cache.download_concurrency = downloads;
+        }
+        if let Some(retries) = self.max_retries {
+            cache.max_retries = retries;
+        }
+        if let Some(backoff) = self.retry_backoff {
+            cache.retry_backoff = backoff;
+        }
+        if let Some(versions) = self.keep_versions {
+            cache.keep_versions = versions;
+        }
+        if let Some(timeout) = self.lock_timeout {
+            cache.lock_timeout = timeout;
+        }
+        if let Some(version) = self.pinned_version {
+            cache.version_dir(&version)?;
+            cache.pinned_version = Some(version);
+        }
+        if let Some(bundle) = &self.bundle {
+            let manifest = CacheManager::read_bundle_manifest(bundle)?;
+            if manifest.version.is_none() || manifest.version != cache.local_version() {
+                cache.import_bundle(bundle)?;
+            }
+        }
+        if self.quota.is_some() {
+            cache.quota = self.quota;
+            cache.enforce_quota()?;
+        }
+        let conn = if self.materialize {
+            Connection::new_materialized(cache)?
+        } else {
+            Connection::new(cache)?
+        };
+        Ok(MtgjsonSdk {
+            origin: Mutex::new(conn),
+            pool: ThreadLocal::new(),
+        })
     }
 }
 
@@ -114,9 +331,22 @@ impl MtgjsonSdkBuilder {
 /// Wraps a [`Connection`] (which owns the [`CacheManager`] and DuckDB database)
 /// and exposes domain-specific query interfaces as lightweight borrowing wrappers.
 ///
+/// The SDK is `Send + Sync`: share it across threads (e.g. in an `Arc`) and
+/// each thread queries through its own DuckDB connection, cloned with
+/// [`Connection::try_clone`] on first use. All of them share one view
+/// registry and cache, so queries from rayon or tokio tasks run in parallel.
+///
+/// Per-thread connections live as long as the SDK. A thread that exits
+/// leaves its connection to the next thread spawned, so the SDK holds at most
+/// as many connections as threads that have used it at the same time; use a
+/// fixed-size pool (rayon, tokio's blocking pool) rather than a thread per
+/// task to keep that number small.
+///
 /// Created via [`MtgjsonSdk::builder()`].
 pub struct MtgjsonSdk {
-    conn: Connection,
+    /// Connection the per-thread connections are cloned from.
+    origin: Mutex<Connection>,
+    pool: ThreadLocal<Connection>,
 }
 
 impl MtgjsonSdk {
@@ -132,53 +362,69 @@ impl MtgjsonSdk {
     /// Returns a lightweight wrapper that borrows from the underlying
     /// connection and provides methods for querying card data.
     pub fn cards(&self) -> queries::cards::CardQuery<'_> {
-        queries::cards::CardQuery::new(&self.conn)
+        queries::cards::CardQuery::from_sdk(self)
     }
 
     /// Access the set query interface.
     pub fn sets(&self) -> queries::sets::SetQuery<'_> {
-        queries::sets::SetQuery::new(&self.conn)
+        queries::sets::SetQuery::from_sdk(self)
     }
 
     /// Access the token query interface.
     pub fn tokens(&self) -> queries::tokens::TokenQuery<'_> {
-        queries::tokens::TokenQuery::new(&self.conn)
+        queries::tokens::TokenQuery::from_sdk(self)
     }
 
     /// Access the price query interface.
     ///
     /// Requires the `prices_today` table to have been loaded into DuckDB.
     pub fn prices(&self) -> queries::prices::PriceQuery<'_> {
-        queries::prices::PriceQuery::new(&self.conn)
+        queries::prices::PriceQuery::from_sdk(self)
     }
 
     /// Access the legality query interface.
     pub fn legalities(&self) -> queries::legalities::LegalityQuery<'_> {
-        queries::legalities::LegalityQuery::new(&self.conn)
+        queries::legalities::LegalityQuery::from_sdk(self)
     }
 
     /// Access the identifier query interface.
     pub fn identifiers(&self) -> queries::identifiers::IdentifierQuery<'_> {
-        queries::identifiers::IdentifierQuery::new(&self.conn)
+        queries::identifiers::IdentifierQuery::from_sdk(self)
     }
 
     /// Access the deck query interface.
     ///
     /// Deck data is loaded from `DeckList.json` via the cache manager.
     pub fn decks(&self) -> queries::decks::DeckQuery<'_> {
-        queries::decks::DeckQuery::new(&self.conn)
+        queries::decks::DeckQuery::from_sdk(self)
+    }
+
+    /// Access the version diff interface.
+    ///
+    /// Compares two cached MTGJSON versions (see
+    /// [`MtgjsonSdkBuilder::keep_versions`]) and lists what changed.
+    pub fn diff(&self) -> queries::diff::DiffQuery<'_> {
+        queries::diff::DiffQuery::from_sdk(self)
+    }
+
+    /// Access the collection tracking interface.
+    ///
+    /// The collection is stored in `collection.duckdb` in the cache directory
+    /// and persists across sessions and cache clears.
+    pub fn collection(&self) -> queries::collection::CollectionQuery<'_> {
+        queries::collection::CollectionQuery::from_sdk(self)
     }
 
     /// Access the sealed product query interface.
     pub fn sealed(&self) -> queries::sealed::SealedQuery<'_> {
-        queries::sealed::SealedQuery::new(&self.conn)
+        queries::sealed::SealedQuery::from_sdk(self)
     }
 
     /// Access the TCGplayer SKU query interface.
     ///
     /// Requires the `tcgplayer_skus` table to have been loaded into DuckDB.
     pub fn skus(&self) -> queries::skus::SkuQuery<'_> {
-        queries::skus::SkuQuery::new(&self.conn)
+        queries::skus::SkuQuery::from_sdk(self)
     }
 
     /// Access the enum/keyword query interface.
@@ -186,7 +432,7 @@ impl MtgjsonSdk {
     /// Enum data is loaded from JSON files (`Keywords.json`, `CardTypes.json`,
     /// `EnumValues.json`) via the cache manager.
     pub fn enums(&self) -> queries::enums::EnumQuery<'_> {
-        queries::enums::EnumQuery::new(&self.conn)
+        queries::enums::EnumQuery::from_sdk(self)
     }
 
     /// Access the booster pack simulator.
@@ -194,7 +440,15 @@ impl MtgjsonSdk {
     /// The simulator reads from the set booster parquet tables to generate
     /// randomized booster packs matching real-world distribution rules.
     pub fn booster(&self) -> booster::BoosterSimulator<'_> {
-        booster::BoosterSimulator::new(&self.conn)
+        booster::BoosterSimulator::from_sdk(self)
+    }
+
+    /// Access the deck validator.
+    ///
+    /// Checks whole decks against a format's construction rules using the
+    /// `cards` and `card_legalities` views.
+    pub fn validator(&self) -> validator::DeckValidator<'_> {
+        validator::DeckValidator::from_sdk(self)
     }
 
     // -- Metadata and utility methods --------------------------------------
@@ -204,7 +458,7 @@ impl MtgjsonSdk {
     /// Fetches `Meta.json` from the cache (downloading if necessary) and
     /// returns the parsed JSON object.
     pub fn meta(&self) -> Result<serde_json::Value> {
-        self.conn.cache.borrow_mut().load_json("meta")
+        self.connection()?.cache().load_json("meta")
     }
 
     /// Return the list of currently registered DuckDB view names.
@@ -212,7 +466,19 @@ impl MtgjsonSdk {
     /// Views are registered lazily on first query, so this list grows as
     /// different query interfaces are used.
     pub fn views(&self) -> Vec<String> {
-        self.conn.views()
+        // The registry is shared, so the origin answers for every thread
+        self.origin
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .views()
+    }
+
+    /// Report what is cached: each file's size, MTGJSON version and last
+    /// access time, and whether it backs a registered view.
+    ///
+    /// See [`CacheManager::stats`].
+    pub fn cache_stats(&self) -> Result<CacheStats> {
+        self.connection()?.cache().stats()
     }
 
     /// Execute a raw SQL query against the DuckDB database.
@@ -231,24 +497,57 @@ impl MtgjsonSdk {
     pub fn sql(
         &self,
         query: &str,
-        params: &[String],
+        params: &[SqlValue],
     ) -> Result<Vec<HashMap<String, serde_json::Value>>> {
-        self.conn.execute(query, params)
+        self.connection()?.execute(query, params)
     }
 
-    /// Check for a newer MTGJSON version and reset views if stale.
+    /// Execute a raw SQL query and return the result as Arrow record batches.
     ///
-    /// Returns `true` if the data was stale and views were reset (meaning
-    /// subsequent queries will re-download data), or `false` if already
-    /// up to date.
+    /// Arrow counterpart of [`sql`](Self::sql); column types are preserved
+    /// exactly as DuckDB produces them.
+    #[cfg(feature = "arrow")]
+    pub fn sql_arrow(
+        &self,
+        query: &str,
+        params: &[SqlValue],
+    ) -> Result<Vec<arrow::record_batch::RecordBatch>> {
+        self.connection()?.execute_arrow(query, params)
+    }
+
+    /// Check for a newer MTGJSON version and update stale cached files.
+    ///
+    /// Only cached files that are not current for the CDN's version are
+    /// re-fetched, with conditional GETs so unchanged files are not
+    /// downloaded again (see [`CacheManager::refresh`]). New files are staged
+    /// while the current views keep serving, then swapped in and all views
+    /// reloaded at once with [`Connection::reload_views`]. Returns `true` if
+    /// any file changed, or `false` if already up to date.
     pub fn refresh(&self) -> Result<bool> {
-        let stale = self.conn.cache.borrow_mut().is_stale()?;
-        if stale {
-            self.conn.cache.borrow().clear()?;
-            self.conn.reset_views();
-            eprintln!("MTGJSON data was stale; cache cleared and views reset");
+        let updated = self.connection()?.cache().refresh()?;
+        if updated.is_empty() {
+            return Ok(false);
+        }
+        self.connection()?.reload_views()?;
+        info!(
+            "MTGJSON data was stale; {} cached file(s) updated and views reloaded",
+            updated.len()
+        );
+        Ok(true)
+    }
+
+    /// Undo the most recent [`refresh`](Self::refresh) that changed files,
+    /// restoring the previous version's files and reloading all views.
+    ///
+    /// Up to [`MtgjsonSdkBuilder::keep_versions`] refreshes can be undone
+    /// this way. Returns the restored version, or `None` if there is nothing
+    /// to roll back to.
+    pub fn rollback(&self) -> Result<Option<String>> {
+        let version = self.connection()?.cache().rollback()?;
+        if version.is_some() {
+            self.connection()?.reload_views()?;
         }
-        Ok(stale)
+        Ok(version)
     }
 
     /// Consume the SDK and release all resources.
@@ -261,14 +560,18 @@ impl MtgjsonSdk {
         drop(self);
     }
 
-    /// Return a reference to the underlying [`Connection`] for advanced usage.
-    pub fn connection(&self) -> &Connection {
-        &self.conn
-    }
-
-    /// Return a mutable reference to the underlying [`Connection`].
-    pub fn connection_mut(&mut self) -> &mut Connection {
-        &mut self.conn
+    /// Return the calling thread's [`Connection`] for advanced usage.
+    ///
+    /// Each thread gets its own connection to the shared database, so the
+    /// returned reference differs between threads. Fails if DuckDB cannot
+    /// open a connection for a thread's first use; the next call retries.
+    pub fn connection(&self) -> Result<&Connection> {
+        self.pool.get_or_try(|| {
+            self.origin
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .try_clone()
+        })
     }
 }
 
@@ -278,8 +581,10 @@ impl MtgjsonSdk {
 
 impl fmt::Display for MtgjsonSdk {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let views = self.conn.views();
-        let cache = self.conn.cache.borrow();
+        // The origin connection avoids cloning one for the formatting thread
+        let origin = self.origin.lock().unwrap_or_else(PoisonError::into_inner);
+        let views = origin.views();
+        let cache = origin.cache();
         write!(
             f,
             "MtgjsonSdk(cache_dir={}, views=[{}], offline={})",
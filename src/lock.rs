@@ -0,0 +1,62 @@
+//! Advisory file locks coordinating processes that share a cache directory.
+//!
+//! Lock files live under `locks/` in the cache directory and are never
+//! deleted. Locks are released when the returned [`FileLock`] is dropped.
+
+use crate::error::{MtgjsonError, Result};
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Interval between attempts while waiting for a lock.
+const POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+/// Whether a lock may be held by several holders at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LockMode {
+    Shared,
+    Exclusive,
+}
+
+/// A held advisory lock on a lock file.
+#[derive(Debug)]
+pub(crate) struct FileLock {
+    // Closing the file releases the lock
+    _file: File,
+}
+
+/// Lock `path`, creating it and its parent directories if needed, waiting
+/// up to `timeout` for other holders to release it.
+///
+/// Fails with [`MtgjsonError::LockTimeout`] if the lock is still held after
+/// `timeout`.
+pub(crate) fn acquire(path: &Path, mode: LockMode, timeout: Duration) -> Result<FileLock> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path)?;
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        let attempt = match mode {
+            LockMode::Shared => file.try_lock_shared(),
+            LockMode::Exclusive => file.try_lock(),
+        };
+        match attempt {
+            Ok(()) => return Ok(FileLock { _file: file }),
+            Err(TryLockError::Error(e)) => return Err(e.into()),
+            Err(TryLockError::WouldBlock) if Instant::now() >= deadline => {
+                return Err(MtgjsonError::LockTimeout(format!(
+                    "{} still held after {:?}",
+                    path.display(),
+                    timeout
+                )));
+            }
+            Err(TryLockError::WouldBlock) => std::thread::sleep(POLL_INTERVAL),
+        }
+    }
+}
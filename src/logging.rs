@@ -0,0 +1,64 @@
+//! Diagnostics as `tracing` events and spans.
+//!
+//! With the `tracing` feature, `debug!`, `info!`, `warn!` and `span!`
+//! forward to the `tracing` macros, so applications decide what is
+//! shown through their subscriber. Without it they compile to nothing and
+//! the SDK prints nothing.
+//!
+//! Events take optional `key = value` fields, recorded with their `Display`
+//! implementation, followed by a format string and its arguments:
+//!
+//! ```ignore
+//! warn!(url = url, "Download failed: {}", e);
+//! ```
+
+#[cfg(feature = "tracing")]
+macro_rules! event {
+    ($level:ident, $($key:ident = $value:expr,)* $fmt:literal $(, $arg:expr)* $(,)?) => {
+        ::tracing::$level!($($key = %$value,)* $fmt $(, $arg)*)
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! event {
+    ($level:ident, $($key:ident = $value:expr,)* $fmt:literal $(, $arg:expr)* $(,)?) => {{
+        $(let _ = &$value;)*
+        let _ = format_args!($fmt $(, $arg)*);
+    }};
+}
+
+/// Details useful when debugging the SDK itself.
+macro_rules! debug {
+    ($($t:tt)*) => { event!(debug, $($t)*) };
+}
+
+/// Normal progress: downloads, refreshes, evictions.
+macro_rules! info {
+    ($($t:tt)*) => { event!(info, $($t)*) };
+}
+
+/// Failures the SDK recovered from: retries, fallbacks, corrupt files.
+macro_rules! warn {
+    ($($t:tt)*) => { event!(warn, $($t)*) };
+}
+
+/// Enter an info-level span until the returned guard is dropped.
+#[cfg(feature = "tracing")]
+macro_rules! span {
+    ($name:literal $(, $key:ident = $value:expr)* $(,)?) => {
+        ::tracing::info_span!($name $(, $key = %$value)*).entered()
+    };
+}
+
+/// Enter an info-level span until the returned guard is dropped.
+#[cfg(not(feature = "tracing"))]
+macro_rules! span {
+    ($name:literal $(, $key:ident = $value:expr)* $(,)?) => {{
+        $(let _ = &$value;)*
+        $crate::logging::NoSpan
+    }};
+}
+
+/// Stands in for a span guard without the `tracing` feature.
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct NoSpan;
@@ -33,6 +33,7 @@ pub struct CardSet {
     pub toughness: Option<String>,
     pub loyalty: Option<String>,
     pub keywords: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub identifiers: Option<serde_json::Value>,
     pub is_funny: Option<bool>,
     pub edhrec_saltiness: Option<f64>,
@@ -47,15 +48,21 @@ pub struct CardSet {
     pub hand: Option<String>,
     pub life: Option<String>,
     pub edhrec_rank: Option<i64>,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub foreign_data: Option<serde_json::Value>,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub legalities: Option<serde_json::Value>,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub leadership_skills: Option<serde_json::Value>,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub rulings: Option<serde_json::Value>,
     pub has_alternative_deck_limit: Option<bool>,
     pub is_reserved: Option<bool>,
     pub is_game_changer: Option<bool>,
     pub printings: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub purchase_urls: Option<serde_json::Value>,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub related_cards: Option<serde_json::Value>,
 
     // -- CardPrintingBase fields --
@@ -85,6 +92,7 @@ pub struct CardSet {
     #[serde(default)]
     pub finishes: Vec<String>,
     pub promo_types: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "super::de::int_list")]
     pub attraction_lights: Option<Vec<i64>>,
     pub is_full_art: Option<bool>,
     pub is_online_only: Option<bool>,
@@ -95,6 +103,7 @@ pub struct CardSet {
     pub other_face_ids: Option<Vec<String>>,
     pub card_parts: Option<Vec<String>>,
     pub language: Option<String>,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub source_products: Option<serde_json::Value>,
 
     // -- CardPrintingFull fields --
@@ -144,6 +153,7 @@ pub struct CardAtomic {
     pub toughness: Option<String>,
     pub loyalty: Option<String>,
     pub keywords: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub identifiers: Option<serde_json::Value>,
     pub is_funny: Option<bool>,
     pub edhrec_saltiness: Option<f64>,
@@ -158,15 +168,21 @@ pub struct CardAtomic {
     pub hand: Option<String>,
     pub life: Option<String>,
     pub edhrec_rank: Option<i64>,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub foreign_data: Option<serde_json::Value>,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub legalities: Option<serde_json::Value>,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub leadership_skills: Option<serde_json::Value>,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub rulings: Option<serde_json::Value>,
     pub has_alternative_deck_limit: Option<bool>,
     pub is_reserved: Option<bool>,
     pub is_game_changer: Option<bool>,
     pub printings: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub purchase_urls: Option<serde_json::Value>,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub related_cards: Option<serde_json::Value>,
 
     // -- Atomic-specific --
@@ -206,6 +222,7 @@ pub struct CardToken {
     pub toughness: Option<String>,
     pub loyalty: Option<String>,
     pub keywords: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub identifiers: Option<serde_json::Value>,
     pub is_funny: Option<bool>,
     pub edhrec_saltiness: Option<f64>,
@@ -238,6 +255,7 @@ pub struct CardToken {
     #[serde(default)]
     pub finishes: Vec<String>,
     pub promo_types: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "super::de::int_list")]
     pub attraction_lights: Option<Vec<i64>>,
     pub is_full_art: Option<bool>,
     pub is_online_only: Option<bool>,
@@ -248,12 +266,15 @@ pub struct CardToken {
     pub other_face_ids: Option<Vec<String>>,
     pub card_parts: Option<Vec<String>>,
     pub language: Option<String>,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub source_products: Option<serde_json::Value>,
 
     // -- Token-specific fields --
     pub orientation: Option<String>,
     pub reverse_related: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub related_cards: Option<serde_json::Value>,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub token_products: Option<serde_json::Value>,
 }
 
@@ -290,6 +311,7 @@ pub struct CardDeck {
     pub toughness: Option<String>,
     pub loyalty: Option<String>,
     pub keywords: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub identifiers: Option<serde_json::Value>,
     pub is_funny: Option<bool>,
     pub edhrec_saltiness: Option<f64>,
@@ -304,15 +326,21 @@ pub struct CardDeck {
     pub hand: Option<String>,
     pub life: Option<String>,
     pub edhrec_rank: Option<i64>,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub foreign_data: Option<serde_json::Value>,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub legalities: Option<serde_json::Value>,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub leadership_skills: Option<serde_json::Value>,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub rulings: Option<serde_json::Value>,
     pub has_alternative_deck_limit: Option<bool>,
     pub is_reserved: Option<bool>,
     pub is_game_changer: Option<bool>,
     pub printings: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub purchase_urls: Option<serde_json::Value>,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub related_cards: Option<serde_json::Value>,
 
     // -- CardPrintingBase fields --
@@ -342,6 +370,7 @@ pub struct CardDeck {
     #[serde(default)]
     pub finishes: Vec<String>,
     pub promo_types: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "super::de::int_list")]
     pub attraction_lights: Option<Vec<i64>>,
     pub is_full_art: Option<bool>,
     pub is_online_only: Option<bool>,
@@ -352,6 +381,7 @@ pub struct CardDeck {
     pub other_face_ids: Option<Vec<String>>,
     pub card_parts: Option<Vec<String>>,
     pub language: Option<String>,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub source_products: Option<serde_json::Value>,
 
     // -- CardPrintingFull fields --
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+
+// ---------------------------------------------------------------------------
+// CollectionEntry — Owned copies of one card printing
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct CollectionEntry {
+    pub uuid: String,
+    pub finish: String,
+    pub condition: String,
+    pub language: String,
+    pub quantity: i64,
+    pub acquired_price: Option<f64>,
+    pub acquired_date: Option<String>,
+}
+
+impl CollectionEntry {
+    /// Entry for `quantity` near-mint, non-foil English copies of `uuid`.
+    pub fn new(uuid: &str, quantity: i64) -> Self {
+        Self {
+            uuid: uuid.to_string(),
+            finish: "normal".to_string(),
+            condition: "NM".to_string(),
+            language: "English".to_string(),
+            quantity,
+            acquired_price: None,
+            acquired_date: None,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// CollectionValue — Collection valued against one price provider
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct CollectionValue {
+    pub provider: String,
+    pub currency: Option<String>,
+    pub total_value: f64,
+    pub cost_basis: f64,
+    pub total_quantity: i64,
+    pub priced_quantity: i64,
+}
+
+// ---------------------------------------------------------------------------
+// SetCompletion — Owned vs. total distinct printings in a set
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct SetCompletion {
+    pub set_code: String,
+    pub owned: i64,
+    pub total: i64,
+    pub percentage: f64,
+}
@@ -0,0 +1,63 @@
+//! Serde helpers for deserializing models from DuckDB view rows.
+//!
+//! The connection casts struct-like parquet columns to DuckDB's `JSON` type,
+//! which reaches Rust as a JSON-encoded string. List columns that were stored
+//! as comma-separated text arrive as arrays of strings. These helpers accept
+//! both the view shapes and the native JSON shapes used by the CDN files.
+
+use serde::de::{DeserializeOwned, Error};
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+/// Deserialize an optional nested value that may arrive as a JSON string.
+///
+/// Strings that parse as JSON are decoded; anything else is deserialized
+/// directly. `null` maps to `None`.
+pub(crate) fn json_column<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let value = Option::<Value>::deserialize(deserializer)?;
+    match value {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(s)) => match serde_json::from_str::<Value>(&s) {
+            Ok(Value::Null) => Ok(None),
+            Ok(parsed @ (Value::Object(_) | Value::Array(_))) => {
+                serde_json::from_value(parsed).map(Some).map_err(D::Error::custom)
+            }
+            _ => serde_json::from_value(Value::String(s))
+                .map(Some)
+                .map_err(D::Error::custom),
+        },
+        Some(v) => serde_json::from_value(v).map(Some).map_err(D::Error::custom),
+    }
+}
+
+/// Deserialize an optional list of integers that may arrive as strings.
+///
+/// CSV-to-array rewriting splits text columns into `VARCHAR[]`, so numeric
+/// lists such as `attractionLights` come back as `["1", "3"]`.
+pub(crate) fn int_list<'de, D>(deserializer: D) -> Result<Option<Vec<i64>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Option::<Vec<Value>>::deserialize(deserializer)?;
+    let Some(items) = value else {
+        return Ok(None);
+    };
+    items
+        .into_iter()
+        .map(|item| match item {
+            Value::Number(n) => n
+                .as_i64()
+                .ok_or_else(|| D::Error::custom(format!("expected integer, got {}", n))),
+            Value::String(s) => s
+                .trim()
+                .parse::<i64>()
+                .map_err(|e| D::Error::custom(format!("invalid integer '{}': {}", s, e))),
+            other => Err(D::Error::custom(format!("expected integer, got {}", other))),
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(Some)
+}
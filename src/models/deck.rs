@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use super::card::CardDeck;
+
 // ---------------------------------------------------------------------------
 // DeckList — Summary info for a deck
 // ---------------------------------------------------------------------------
@@ -14,3 +16,31 @@ pub struct DeckList {
     pub type_field: String,
     pub release_date: Option<String>,
 }
+
+// ---------------------------------------------------------------------------
+// Deck — Full deck contents from `decks/{fileName}.json`
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Deck {
+    pub code: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_field: String,
+    pub release_date: Option<String>,
+    #[serde(default)]
+    pub commander: Vec<CardDeck>,
+    #[serde(default)]
+    pub display_commander: Vec<CardDeck>,
+    #[serde(default)]
+    pub main_board: Vec<CardDeck>,
+    #[serde(default)]
+    pub side_board: Vec<CardDeck>,
+    #[serde(default)]
+    pub tokens: Vec<CardDeck>,
+    pub planes: Option<Vec<CardDeck>>,
+    pub schemes: Option<Vec<CardDeck>>,
+    pub sealed_product_uuids: Option<Vec<String>>,
+    pub source_set_codes: Option<Vec<String>>,
+}
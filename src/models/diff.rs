@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+// ---------------------------------------------------------------------------
+// ChangeKind — Category of a difference between two MTGJSON versions
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    /// A printing (card uuid) only in the new version.
+    CardAdded,
+    /// A printing (card uuid) only in the old version.
+    CardRemoved,
+    /// A set code only in the new version.
+    SetAdded,
+    /// A set code only in the old version.
+    SetRemoved,
+    /// Oracle text of a printing changed (errata).
+    TextChanged,
+    /// A printing's status in one format changed, appeared or disappeared.
+    LegalityChanged,
+    /// An identifier that a printing did not have before.
+    IdentifierAdded,
+}
+
+// ---------------------------------------------------------------------------
+// DiffEntry — One change between two MTGJSON versions
+// ---------------------------------------------------------------------------
+
+/// One change between two MTGJSON versions, keyed by `key` and `field`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct DiffEntry {
+    pub kind: ChangeKind,
+    /// Card uuid, or set code for set changes.
+    pub key: String,
+    /// Changed field: `"text"`, the format name for legalities, or the
+    /// identifier name. `None` for added and removed cards and sets.
+    pub field: Option<String>,
+    /// Value in the old version; the name of a removed card or set.
+    pub old_value: Option<String>,
+    /// Value in the new version; the name of an added card or set.
+    pub new_value: Option<String>,
+}
@@ -1,12 +1,17 @@
 pub mod card;
+pub mod collection;
+pub(crate) mod de;
 pub mod deck;
+pub mod diff;
 pub mod price;
 pub mod sealed;
 pub mod set;
 pub mod sub;
 
 pub use card::*;
+pub use collection::*;
 pub use deck::*;
+pub use diff::*;
 pub use price::*;
 pub use sealed::*;
 pub use set::*;
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use super::card::CardSet;
+
 // ---------------------------------------------------------------------------
 // PriceRow — Single price data point (query result)
 // ---------------------------------------------------------------------------
@@ -11,6 +13,7 @@ pub struct PriceRow {
     pub source: String,
     pub provider: String,
     pub currency: String,
+    #[serde(alias = "price_type")]
     pub category: String,
     pub finish: String,
     pub date: String,
@@ -46,3 +49,19 @@ pub struct FinancialSummary {
     pub max_value: f64,
     pub date: String,
 }
+
+// ---------------------------------------------------------------------------
+// PricedCard — Card printing joined with a single price point
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PricedCard {
+    #[serde(flatten)]
+    pub card: CardSet,
+    pub price: f64,
+    pub source: String,
+    pub provider: String,
+    pub finish: String,
+    pub date: String,
+}
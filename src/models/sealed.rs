@@ -18,7 +18,10 @@ pub struct SealedProduct {
     pub release_date: Option<String>,
     pub card_count: Option<i64>,
     pub product_size: Option<i64>,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub contents: Option<SealedProductContents>,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub identifiers: Option<serde_json::Value>,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub purchase_urls: Option<serde_json::Value>,
 }
@@ -18,6 +18,7 @@ pub struct SetList {
     pub base_set_size: i64,
     pub total_set_size: i64,
     pub keyrune_code: String,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub translations: Option<serde_json::Value>,
     pub block: Option<String>,
     pub parent_code: Option<String>,
@@ -35,7 +36,9 @@ pub struct SetList {
     pub is_foreign_only: Option<bool>,
     pub is_partial_preview: Option<bool>,
     pub languages: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub decks: Option<Vec<serde_json::Value>>,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub sealed_product: Option<Vec<serde_json::Value>>,
 }
 
@@ -54,6 +57,7 @@ pub struct MtgSet {
     pub base_set_size: i64,
     pub total_set_size: i64,
     pub keyrune_code: String,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub translations: Option<serde_json::Value>,
     pub block: Option<String>,
     pub parent_code: Option<String>,
@@ -71,7 +75,9 @@ pub struct MtgSet {
     pub is_foreign_only: Option<bool>,
     pub is_partial_preview: Option<bool>,
     pub languages: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub decks: Option<Vec<serde_json::Value>>,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub sealed_product: Option<Vec<serde_json::Value>>,
 
     // -- Full set specific fields --
@@ -79,5 +85,6 @@ pub struct MtgSet {
     pub cards: Vec<CardSet>,
     #[serde(default)]
     pub tokens: Vec<CardToken>,
+    #[serde(default, deserialize_with = "super::de::json_column")]
     pub booster: Option<HashMap<String, serde_json::Value>>,
 }
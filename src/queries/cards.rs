@@ -2,10 +2,19 @@
 
 use std::collections::HashMap;
 
+#[cfg(feature = "arrow")]
+use duckdb::arrow::record_batch::RecordBatch;
+use serde::de::DeserializeOwned;
 use serde_json::Value;
 
 use crate::error::Result;
+use crate::models::{CardAtomic, CardSet};
 use crate::sql_builder::SqlBuilder;
+use crate::sql_expr::SqlExpr;
+use crate::sql_value::SqlValue;
+use crate::stream::TypedRowStream;
+
+use super::search_syntax;
 
 // ---------------------------------------------------------------------------
 // SearchCardsParams
@@ -49,49 +58,84 @@ pub struct SearchCardsParams {
 
 /// Query interface for MTG cards backed by the `cards` parquet view.
 pub struct CardQuery<'a> {
-    conn: &'a crate::connection::Connection,
+    conn: crate::connection::ConnectionRef<'a>,
 }
 
 impl<'a> CardQuery<'a> {
     /// Create a new `CardQuery` bound to the given connection.
     pub fn new(conn: &'a crate::connection::Connection) -> Self {
-        Self { conn }
+        Self {
+            conn: crate::connection::ConnectionRef::Direct(conn),
+        }
+    }
+
+    /// Create a new `CardQuery` on the calling thread's SDK connection.
+    pub(crate) fn from_sdk(sdk: &'a crate::MtgjsonSdk) -> Self {
+        Self {
+            conn: crate::connection::ConnectionRef::Sdk(sdk),
+        }
+    }
+
+    /// Return a typed view of this query interface.
+    ///
+    /// The typed interface runs the same queries but deserializes each row
+    /// into [`CardSet`] (or [`CardAtomic`]) via
+    /// [`Connection::execute_into`](crate::connection::Connection::execute_into).
+    pub fn typed(&self) -> TypedCardQuery<'a> {
+        TypedCardQuery {
+            inner: CardQuery { conn: self.conn },
+        }
     }
 
     // -- Single card lookup ------------------------------------------------
 
     /// Retrieve a single card by its UUID.
     pub fn get_by_uuid(&self, uuid: &str) -> Result<Option<Value>> {
-        self.conn.ensure_views(&["cards"])?;
+        self.fetch_by_uuid(uuid)
+    }
+
+    fn fetch_by_uuid<T: DeserializeOwned>(&self, uuid: &str) -> Result<Option<T>> {
+        self.conn.get()?.ensure_views(&["cards"])?;
 
         let (sql, params) = SqlBuilder::new("cards")
             .where_eq("uuid", uuid)
             .limit(1)
             .build();
 
-        let rows = self.conn.execute(&sql, &params)?;
-        Ok(rows.into_iter().next().map(|r| serde_json::to_value(r).unwrap_or(Value::Null)))
+        let rows = self.conn.get()?.execute_into(&sql, &params)?;
+        Ok(rows.into_iter().next())
     }
 
     // -- Batch lookup ------------------------------------------------------
 
     /// Retrieve multiple cards by their UUIDs (preserves order where possible).
     pub fn get_by_uuids(&self, uuids: &[&str]) -> Result<Vec<Value>> {
-        self.conn.ensure_views(&["cards"])?;
+        self.fetch_by_uuids(uuids)
+    }
+
+    fn fetch_by_uuids<T: DeserializeOwned>(&self, uuids: &[&str]) -> Result<Vec<T>> {
+        self.conn.get()?.ensure_views(&["cards"])?;
 
         let (sql, params) = SqlBuilder::new("cards")
             .where_in("uuid", uuids)
             .build();
 
-        let rows = self.conn.execute(&sql, &params)?;
-        Ok(rows_to_values(rows))
+        self.conn.get()?.execute_into(&sql, &params)
     }
 
     // -- Name lookup -------------------------------------------------------
 
     /// Get all printings of a card by exact name, optionally filtered by set code.
     pub fn get_by_name(&self, name: &str, set_code: Option<&str>) -> Result<Vec<Value>> {
-        self.conn.ensure_views(&["cards"])?;
+        self.fetch_by_name(name, set_code)
+    }
+
+    fn fetch_by_name<T: DeserializeOwned>(
+        &self,
+        name: &str,
+        set_code: Option<&str>,
+    ) -> Result<Vec<T>> {
+        self.conn.get()?.ensure_views(&["cards"])?;
 
         let mut qb = SqlBuilder::new("cards");
         qb.where_eq("name", name);
@@ -101,8 +145,7 @@ impl<'a> CardQuery<'a> {
         }
 
         let (sql, params) = qb.build();
-        let rows = self.conn.execute(&sql, &params)?;
-        Ok(rows_to_values(rows))
+        self.conn.get()?.execute_into(&sql, &params)
     }
 
     /// Alias for [`get_by_name`](Self::get_by_name) -- returns all printings of the card.
@@ -117,7 +160,11 @@ impl<'a> CardQuery<'a> {
     /// De-duplicates by `(name, faceName)`. If no results are found for an exact name
     /// match, falls back to searching by `faceName`.
     pub fn get_atomic(&self, name: &str) -> Result<Vec<Value>> {
-        self.conn.ensure_views(&["cards"])?;
+        self.fetch_atomic(name)
+    }
+
+    fn fetch_atomic<T: DeserializeOwned>(&self, name: &str) -> Result<Vec<T>> {
+        self.conn.get()?.ensure_views(&["cards"])?;
 
         // First try: match by name, deduplicate by name + faceName
         let (sql, params) = SqlBuilder::new("cards")
@@ -125,9 +172,9 @@ impl<'a> CardQuery<'a> {
             .where_eq("name", name)
             .build();
 
-        let rows = self.conn.execute(&sql, &params)?;
+        let rows = self.conn.get()?.execute_into(&sql, &params)?;
         if !rows.is_empty() {
-            return Ok(rows_to_values(rows));
+            return Ok(rows);
         }
 
         // Fallback: search by faceName
@@ -136,41 +183,46 @@ impl<'a> CardQuery<'a> {
             .where_eq("faceName", name)
             .build();
 
-        let rows2 = self.conn.execute(&sql2, &params2)?;
-        Ok(rows_to_values(rows2))
+        self.conn.get()?.execute_into(&sql2, &params2)
     }
 
     // -- Cross-table lookups -----------------------------------------------
 
     /// Find cards by their Scryfall ID (joins `card_identifiers`).
     pub fn find_by_scryfall_id(&self, scryfall_id: &str) -> Result<Vec<Value>> {
-        self.conn.ensure_views(&["cards", "card_identifiers"])?;
+        self.fetch_by_scryfall_id(scryfall_id)
+    }
+
+    fn fetch_by_scryfall_id<T: DeserializeOwned>(&self, scryfall_id: &str) -> Result<Vec<T>> {
+        self.conn.get()?.ensure_views(&["cards", "card_identifiers"])?;
 
         let (sql, params) = SqlBuilder::new("cards c")
             .join("JOIN card_identifiers ci ON c.uuid = ci.uuid")
             .where_eq("ci.scryfallId", scryfall_id)
             .build();
 
-        let rows = self.conn.execute(&sql, &params)?;
-        Ok(rows_to_values(rows))
+        self.conn.get()?.execute_into(&sql, &params)
     }
 
     // -- Random sampling ---------------------------------------------------
 
     /// Return `count` randomly-sampled cards.
     pub fn random(&self, count: usize) -> Result<Vec<Value>> {
-        self.conn.ensure_views(&["cards"])?;
+        self.fetch_random(count)
+    }
+
+    fn fetch_random<T: DeserializeOwned>(&self, count: usize) -> Result<Vec<T>> {
+        self.conn.get()?.ensure_views(&["cards"])?;
 
         let sql = format!("SELECT * FROM cards USING SAMPLE {}", count);
-        let rows = self.conn.execute(&sql, &[])?;
-        Ok(rows_to_values(rows))
+        self.conn.get()?.execute_into(&sql, &[])
     }
 
     // -- Count -------------------------------------------------------------
 
     /// Count cards, optionally filtered by the supplied column/value pairs.
     pub fn count(&self, filters: &HashMap<String, String>) -> Result<i64> {
-        self.conn.ensure_views(&["cards"])?;
+        self.conn.get()?.ensure_views(&["cards"])?;
 
         let mut qb = SqlBuilder::new("cards");
         qb.select(&["COUNT(*) AS cnt"]);
@@ -178,9 +230,10 @@ impl<'a> CardQuery<'a> {
         for (col, val) in filters {
             qb.where_eq(col, val);
         }
+        self.conn.get()?.check_columns(&["cards"], qb.columns())?;
 
-        let (sql, params) = qb.build();
-        let rows = self.conn.execute(&sql, &params)?;
+        let (sql, params) = qb.try_build()?;
+        let rows = self.conn.get()?.execute(&sql, &params)?;
 
         let cnt = rows
             .first()
@@ -198,6 +251,22 @@ impl<'a> CardQuery<'a> {
     /// Translates each field of [`SearchCardsParams`] into appropriate SQL conditions
     /// (LIKE, exact match, fuzzy match, JOIN, list_contains, regexp, etc.).
     pub fn search(&self, params: &SearchCardsParams) -> Result<Vec<Value>> {
+        self.fetch_search(params)
+    }
+
+    /// Arrow counterpart of [`search`](Self::search).
+    #[cfg(feature = "arrow")]
+    pub fn search_arrow(&self, params: &SearchCardsParams) -> Result<Vec<RecordBatch>> {
+        let (sql, sql_params) = self.search_sql(params)?;
+        self.conn.get()?.execute_arrow(&sql, &sql_params)
+    }
+
+    fn fetch_search<T: DeserializeOwned>(&self, params: &SearchCardsParams) -> Result<Vec<T>> {
+        let (sql, sql_params) = self.search_sql(params)?;
+        self.conn.get()?.execute_into(&sql, &sql_params)
+    }
+
+    fn search_sql(&self, params: &SearchCardsParams) -> Result<(String, Vec<SqlValue>)> {
         // Determine which views we need
         let mut views: Vec<&str> = vec!["cards"];
         if params.legal_in.is_some() {
@@ -209,7 +278,7 @@ impl<'a> CardQuery<'a> {
         if params.set_type.is_some() {
             views.push("sets");
         }
-        self.conn.ensure_views(&views)?;
+        self.conn.get()?.ensure_views(&views)?;
 
         let mut qb = SqlBuilder::new("cards");
 
@@ -225,10 +294,13 @@ impl<'a> CardQuery<'a> {
         // -- fuzzy_name: jaro_winkler_similarity >= 0.8 ---------------------
         if let Some(ref fuzzy) = params.fuzzy_name {
             qb.where_fuzzy("cards.name", fuzzy, 0.8);
-            qb.order_by(&[&format!(
-                "jaro_winkler_similarity(cards.name, '{}') DESC",
-                fuzzy.replace('\'', "''")
-            )]);
+            qb.order_by_expr(
+                SqlExpr::func(
+                    "jaro_winkler_similarity",
+                    [SqlExpr::col("cards.name"), SqlExpr::val(fuzzy)],
+                ),
+                true,
+            );
         }
 
         // -- localized_name: JOIN card_foreign_data -------------------------
@@ -247,7 +319,7 @@ impl<'a> CardQuery<'a> {
             for color in colors {
                 qb.where_clause(
                     "list_contains(cards.colors, ?)",
-                    &[color.as_str()],
+                    &[color.into()],
                 );
             }
         }
@@ -257,7 +329,7 @@ impl<'a> CardQuery<'a> {
             for color in ci {
                 qb.where_clause(
                     "list_contains(cards.colorIdentity, ?)",
-                    &[color.as_str()],
+                    &[color.into()],
                 );
             }
         }
@@ -281,17 +353,17 @@ impl<'a> CardQuery<'a> {
 
         // -- mana_value (exact) --------------------------------------------
         if let Some(mv) = params.mana_value {
-            qb.where_eq("cards.manaValue", &mv.to_string());
+            qb.where_eq("cards.manaValue", mv);
         }
 
         // -- mana_value_lte -------------------------------------------------
         if let Some(mv) = params.mana_value_lte {
-            qb.where_lte("cards.manaValue", &mv.to_string());
+            qb.where_lte("cards.manaValue", mv);
         }
 
         // -- mana_value_gte -------------------------------------------------
         if let Some(mv) = params.mana_value_gte {
-            qb.where_gte("cards.manaValue", &mv.to_string());
+            qb.where_gte("cards.manaValue", mv);
         }
 
         // -- text: LIKE %text% ---------------------------------------------
@@ -323,20 +395,20 @@ impl<'a> CardQuery<'a> {
         if let Some(ref kw) = params.keyword {
             qb.where_clause(
                 "list_contains(cards.keywords, ?)",
-                &[kw.as_str()],
+                &[kw.into()],
             );
         }
 
         // -- is_promo -------------------------------------------------------
         if let Some(promo) = params.is_promo {
-            qb.where_eq("cards.isPromo", if promo { "true" } else { "false" });
+            qb.where_eq("cards.isPromo", promo);
         }
 
         // -- availability: list_contains ------------------------------------
         if let Some(ref avail) = params.availability {
             qb.where_clause(
                 "list_contains(cards.availability, ?)",
-                &[avail.as_str()],
+                &[avail.into()],
             );
         }
 
@@ -362,19 +434,161 @@ impl<'a> CardQuery<'a> {
         qb.limit(limit);
         qb.offset(offset);
 
-        let (sql, sql_params) = qb.build();
-        let rows = self.conn.execute(&sql, &sql_params)?;
-        Ok(rows_to_values(rows))
+        Ok(qb.build())
+    }
+
+    // -- Search syntax -----------------------------------------------------
+
+    /// Search for cards using a Scryfall-style query string.
+    ///
+    /// Supports `key:value` terms (e.g. `t:creature c>=rg mv<=3 o:"draw a card"
+    /// f:modern r:mythic`), bare name words, `and`/`or`, negation with `-` or
+    /// `not`, and parentheses. See [`search_syntax`](super::search_syntax) for
+    /// the full list of keys.
+    ///
+    /// Returns [`MtgjsonError::InvalidArgument`](crate::MtgjsonError::InvalidArgument)
+    /// with the offending position if the query cannot be parsed.
+    pub fn search_query(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<Value>> {
+        self.fetch_search_query(query, limit, offset)
+    }
+
+    /// Arrow counterpart of [`search_query`](Self::search_query).
+    #[cfg(feature = "arrow")]
+    pub fn search_query_arrow(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<RecordBatch>> {
+        let (sql, params) = self.search_query_sql(query, limit, offset)?;
+        self.conn.get()?.execute_arrow(&sql, &params)
+    }
+
+    fn fetch_search_query<T: DeserializeOwned>(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<T>> {
+        let (sql, params) = self.search_query_sql(query, limit, offset)?;
+        self.conn.get()?.execute_into(&sql, &params)
+    }
+
+    fn search_query_sql(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<(String, Vec<SqlValue>)> {
+        let parsed = search_syntax::parse(query)?;
+        self.conn.get()?.ensure_views(&parsed.views())?;
+
+        let mut qb = SqlBuilder::new("cards");
+        parsed.apply(&mut qb);
+        qb.order_by(&["cards.name ASC", "cards.setCode ASC", "cards.number ASC"]);
+        qb.limit(limit.unwrap_or(100));
+        qb.offset(offset.unwrap_or(0));
+
+        Ok(qb.build())
+    }
+
+    // -- Streaming scan ----------------------------------------------------
+
+    /// Lazily iterate over every card, optionally restricted to one set.
+    ///
+    /// Rows are streamed from DuckDB instead of being collected, so this is
+    /// suitable for exporting the whole `cards` view. Finish iterating before
+    /// running other queries on the same connection; see
+    /// [`Connection::stream`](crate::connection::Connection::stream).
+    pub fn scan(&self, set_code: Option<&str>) -> Result<TypedRowStream<'a, Value>> {
+        self.scan_into(set_code)
+    }
+
+    fn scan_into<T: DeserializeOwned>(
+        &self,
+        set_code: Option<&str>,
+    ) -> Result<TypedRowStream<'a, T>> {
+        self.conn.get()?.ensure_views(&["cards"])?;
+
+        let mut qb = SqlBuilder::new("cards");
+        if let Some(sc) = set_code {
+            qb.where_eq("setCode", sc);
+        }
+
+        let (sql, params) = qb.build();
+        self.conn.get()?.execute_iter(&sql, &params)
     }
 }
 
 // ---------------------------------------------------------------------------
-// Helpers
+// TypedCardQuery
 // ---------------------------------------------------------------------------
 
-/// Convert a vector of row HashMaps into `serde_json::Value` objects.
-fn rows_to_values(rows: Vec<HashMap<String, Value>>) -> Vec<Value> {
-    rows.into_iter()
-        .map(|r| serde_json::to_value(r).unwrap_or(Value::Null))
-        .collect()
+/// Typed counterpart of [`CardQuery`] returning [`CardSet`] models.
+///
+/// Obtained via [`CardQuery::typed`].
+pub struct TypedCardQuery<'a> {
+    inner: CardQuery<'a>,
+}
+
+impl<'a> TypedCardQuery<'a> {
+    /// Retrieve a single card by its UUID.
+    pub fn get_by_uuid(&self, uuid: &str) -> Result<Option<CardSet>> {
+        self.inner.fetch_by_uuid(uuid)
+    }
+
+    /// Retrieve multiple cards by their UUIDs.
+    pub fn get_by_uuids(&self, uuids: &[&str]) -> Result<Vec<CardSet>> {
+        self.inner.fetch_by_uuids(uuids)
+    }
+
+    /// Get all printings of a card by exact name, optionally filtered by set code.
+    pub fn get_by_name(&self, name: &str, set_code: Option<&str>) -> Result<Vec<CardSet>> {
+        self.inner.fetch_by_name(name, set_code)
+    }
+
+    /// Alias for [`get_by_name`](Self::get_by_name) -- returns all printings of the card.
+    pub fn get_printings(&self, name: &str) -> Result<Vec<CardSet>> {
+        self.inner.fetch_by_name(name, None)
+    }
+
+    /// Get a de-duplicated oracle-level card by name.
+    pub fn get_atomic(&self, name: &str) -> Result<Vec<CardAtomic>> {
+        self.inner.fetch_atomic(name)
+    }
+
+    /// Find cards by their Scryfall ID (joins `card_identifiers`).
+    pub fn find_by_scryfall_id(&self, scryfall_id: &str) -> Result<Vec<CardSet>> {
+        self.inner.fetch_by_scryfall_id(scryfall_id)
+    }
+
+    /// Return `count` randomly-sampled cards.
+    pub fn random(&self, count: usize) -> Result<Vec<CardSet>> {
+        self.inner.fetch_random(count)
+    }
+
+    /// Search for cards using a rich set of optional filters.
+    pub fn search(&self, params: &SearchCardsParams) -> Result<Vec<CardSet>> {
+        self.inner.fetch_search(params)
+    }
+
+    /// Typed variant of [`CardQuery::search_query`].
+    pub fn search_query(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<CardSet>> {
+        self.inner.fetch_search_query(query, limit, offset)
+    }
+
+    /// Lazily iterate over every card, optionally restricted to one set.
+    pub fn scan(&self, set_code: Option<&str>) -> Result<TypedRowStream<'a, CardSet>> {
+        self.inner.scan_into(set_code)
+    }
 }
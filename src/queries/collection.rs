@@ -0,0 +1,303 @@
+//! Collection tracking backed by a persistent DuckDB database.
+//!
+//! Owned cards are stored in the `owned_cards` table of `collection.duckdb` in
+//! the cache directory (see [`config::COLLECTION_FILE`](crate::config::COLLECTION_FILE)).
+//! The file is attached to the SDK's in-memory connection as `collection`, so
+//! valuation and completion queries join it directly against the `cards` and
+//! `all_prices_today` views.
+
+use crate::error::{MtgjsonError, Result};
+use crate::models::{CardSet, CollectionEntry, CollectionValue, SetCompletion};
+use crate::sql_value::SqlValue;
+
+/// Upsert one entry; an existing row for the same key has its quantity
+/// increased and keeps its acquisition details unless new ones are given.
+const ADD_SQL: &str = r#"
+    INSERT INTO collection.owned_cards
+    VALUES (?, ?, ?, ?, ?, ?, CAST(? AS DATE))
+    ON CONFLICT DO UPDATE SET
+        quantity = quantity + EXCLUDED.quantity,
+        acquired_price = COALESCE(EXCLUDED.acquired_price, acquired_price),
+        acquired_date = COALESCE(EXCLUDED.acquired_date, acquired_date)
+"#;
+
+const KEY_WHERE: &str = "uuid = ? AND finish = ? AND condition = ? AND language = ?";
+
+/// Collection value against the latest retail price of each printing and
+/// finish from one provider.
+const VALUE_SQL: &str = r#"
+    WITH latest AS (
+        SELECT uuid, finish, currency, price
+        FROM all_prices_today
+        WHERE provider = ? AND price_type = 'retail'
+        QUALIFY row_number() OVER (PARTITION BY uuid, finish ORDER BY date DESC) = 1
+    )
+    SELECT
+        MAX(l.currency) AS currency,
+        COALESCE(SUM(o.quantity * l.price), 0)::DOUBLE AS total_value,
+        COALESCE(SUM(o.quantity * o.acquired_price), 0)::DOUBLE AS cost_basis,
+        COALESCE(SUM(o.quantity), 0)::BIGINT AS total_quantity,
+        COALESCE(SUM(o.quantity) FILTER (WHERE l.price IS NOT NULL), 0)::BIGINT
+            AS priced_quantity
+    FROM collection.owned_cards o
+    LEFT JOIN latest l ON o.uuid = l.uuid AND o.finish = l.finish
+"#;
+
+const SET_COMPLETION_SQL: &str = r#"
+    SELECT
+        COUNT(DISTINCT o.uuid) AS owned,
+        COUNT(DISTINCT c.uuid) AS total
+    FROM cards c
+    LEFT JOIN collection.owned_cards o ON c.uuid = o.uuid
+    WHERE c.setCode = ?
+"#;
+
+const MISSING_CARDS_SQL: &str = r#"
+    SELECT c.*
+    FROM cards c
+    WHERE c.setCode = ?
+      AND c.uuid NOT IN (SELECT uuid FROM collection.owned_cards)
+    ORDER BY TRY_CAST(c.number AS INTEGER) NULLS LAST, c.number
+"#;
+
+// ---------------------------------------------------------------------------
+// CollectionQuery
+// ---------------------------------------------------------------------------
+
+/// Interface for the user's card collection.
+///
+/// Entries are keyed by `(uuid, finish, condition, language)`. Unlike the
+/// other query interfaces this one writes: changes are persisted to disk
+/// immediately and survive [`CacheManager::clear`](crate::cache::CacheManager::clear).
+pub struct CollectionQuery<'a> {
+    conn: crate::connection::ConnectionRef<'a>,
+}
+
+impl<'a> CollectionQuery<'a> {
+    /// Create a new `CollectionQuery` bound to the given connection.
+    pub fn new(conn: &'a crate::connection::Connection) -> Self {
+        Self {
+            conn: crate::connection::ConnectionRef::Direct(conn),
+        }
+    }
+
+    /// Create a new `CollectionQuery` on the calling thread's SDK connection.
+    pub(crate) fn from_sdk(sdk: &'a crate::MtgjsonSdk) -> Self {
+        Self {
+            conn: crate::connection::ConnectionRef::Sdk(sdk),
+        }
+    }
+
+    // -- Editing -----------------------------------------------------------
+
+    /// Add `entry.quantity` copies to the collection.
+    ///
+    /// If the same card, finish, condition and language is already owned the
+    /// quantities are summed; `acquired_price` and `acquired_date` are
+    /// replaced only when the new entry sets them.
+    pub fn add(&self, entry: &CollectionEntry) -> Result<()> {
+        if entry.quantity <= 0 {
+            return Err(MtgjsonError::InvalidArgument(format!(
+                "quantity must be positive, got {}",
+                entry.quantity
+            )));
+        }
+        self.conn.get()?.ensure_collection()?;
+
+        let params: [SqlValue; 7] = [
+            entry.uuid.as_str().into(),
+            entry.finish.as_str().into(),
+            entry.condition.as_str().into(),
+            entry.language.as_str().into(),
+            entry.quantity.into(),
+            entry.acquired_price.into(),
+            entry.acquired_date.as_deref().into(),
+        ];
+        self.conn
+            .get()?
+            .raw()
+            .execute(ADD_SQL, duckdb::params_from_iter(params.iter()))?;
+        Ok(())
+    }
+
+    /// Remove `entry.quantity` copies matching the entry's key.
+    ///
+    /// Returns the quantity left afterwards; the row is deleted when it
+    /// reaches zero. Returns [`MtgjsonError::NotFound`] if no matching copies
+    /// are owned, and [`MtgjsonError::InvalidArgument`] if fewer copies are
+    /// owned than `entry.quantity`, leaving the collection unchanged.
+    ///
+    /// The read and the write run in one transaction, so concurrent removes
+    /// through different connections cannot lose a decrement.
+    pub fn remove(&self, entry: &CollectionEntry) -> Result<i64> {
+        if entry.quantity <= 0 {
+            return Err(MtgjsonError::InvalidArgument(format!(
+                "quantity must be positive, got {}",
+                entry.quantity
+            )));
+        }
+        self.conn.get()?.ensure_collection()?;
+
+        let key: Vec<SqlValue> = vec![
+            entry.uuid.as_str().into(),
+            entry.finish.as_str().into(),
+            entry.condition.as_str().into(),
+            entry.language.as_str().into(),
+        ];
+        let conn = self.conn.get()?;
+        conn.raw().execute_batch("BEGIN TRANSACTION")?;
+        let removed = self.remove_in_transaction(entry, key).and_then(|remaining| {
+            conn.raw().execute_batch("COMMIT")?;
+            Ok(remaining)
+        });
+        if removed.is_err() {
+            let _ = conn.raw().execute_batch("ROLLBACK");
+        }
+        removed
+    }
+
+    fn remove_in_transaction(&self, entry: &CollectionEntry, key: Vec<SqlValue>) -> Result<i64> {
+        let quantity = SqlValue::Int(entry.quantity);
+        let mut params = vec![quantity.clone()];
+        params.extend(key.iter().cloned());
+        params.push(quantity);
+
+        // Quantities must stay positive, so a partial remove decrements in
+        // place and removing every copy deletes the row
+        let decremented = self.conn.get()?.execute_scalar(
+            &format!(
+                "UPDATE collection.owned_cards SET quantity = quantity - ? \
+                 WHERE {} AND quantity > ? RETURNING quantity",
+                KEY_WHERE
+            ),
+            &params,
+        )?;
+        if let Some(remaining) = decremented.and_then(|v| v.as_i64()) {
+            return Ok(remaining);
+        }
+
+        let deleted = self.conn.get()?.execute_scalar(
+            &format!(
+                "DELETE FROM collection.owned_cards WHERE {} AND quantity = ? \
+                 RETURNING quantity",
+                KEY_WHERE
+            ),
+            &params[1..],
+        )?;
+        if deleted.is_some() {
+            return Ok(0);
+        }
+
+        let owned = self
+            .conn
+            .get()?
+            .execute_scalar(
+                &format!("SELECT quantity FROM collection.owned_cards WHERE {}", KEY_WHERE),
+                &key,
+            )?
+            .and_then(|v| v.as_i64());
+        Err(match owned {
+            Some(owned) => MtgjsonError::InvalidArgument(format!(
+                "cannot remove {} copies of {} ({}, {}, {}): only {} owned",
+                entry.quantity, entry.uuid, entry.finish, entry.condition, entry.language, owned
+            )),
+            None => MtgjsonError::NotFound(format!(
+                "{} ({}, {}, {}) is not in the collection",
+                entry.uuid, entry.finish, entry.condition, entry.language
+            )),
+        })
+    }
+
+    // -- Listing -----------------------------------------------------------
+
+    /// List owned entries, optionally restricted to one set.
+    ///
+    /// Filtering by set joins against the `cards` view.
+    pub fn list(&self, set_code: Option<&str>) -> Result<Vec<CollectionEntry>> {
+        self.conn.get()?.ensure_collection()?;
+
+        match set_code {
+            Some(sc) => {
+                self.conn.get()?.ensure_views(&["cards"])?;
+                self.conn.get()?.execute_into(
+                    r#"
+                    SELECT o.*
+                    FROM collection.owned_cards o
+                    JOIN cards c ON o.uuid = c.uuid
+                    WHERE c.setCode = ?
+                    ORDER BY o.uuid, o.finish, o.condition, o.language
+                    "#,
+                    &[sc.to_uppercase().into()],
+                )
+            }
+            None => self.conn.get()?.execute_into(
+                "SELECT * FROM collection.owned_cards \
+                 ORDER BY uuid, finish, condition, language",
+                &[],
+            ),
+        }
+    }
+
+    // -- Analytics ---------------------------------------------------------
+
+    /// Value the collection at today's retail prices from `provider`
+    /// (e.g. `"tcgplayer"`, `"cardmarket"`).
+    ///
+    /// Each entry is priced by its own finish. Entries with no price from the
+    /// provider count towards `total_quantity` but not `priced_quantity`.
+    pub fn value(&self, provider: &str) -> Result<CollectionValue> {
+        self.conn.get()?.ensure_collection()?;
+        self.conn.get()?.ensure_views(&["all_prices_today"])?;
+
+        let row = self
+            .conn
+            .get()?
+            .execute(VALUE_SQL, &[provider.into()])?
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+
+        let mut value = serde_json::Value::Object(row.into_iter().collect());
+        value["provider"] = provider.into();
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Fraction of a set's distinct printings owned in any finish or condition.
+    pub fn set_completion(&self, set_code: &str) -> Result<SetCompletion> {
+        self.conn.get()?.ensure_collection()?;
+        self.conn.get()?.ensure_views(&["cards"])?;
+
+        let upper = set_code.to_uppercase();
+        let row = self
+            .conn
+            .get()?
+            .execute(SET_COMPLETION_SQL, &[upper.as_str().into()])?
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+        let count = |key: &str| row.get(key).and_then(|v| v.as_i64()).unwrap_or(0);
+        let (owned, total) = (count("owned"), count("total"));
+
+        Ok(SetCompletion {
+            set_code: upper,
+            owned,
+            total,
+            percentage: if total > 0 {
+                owned as f64 * 100.0 / total as f64
+            } else {
+                0.0
+            },
+        })
+    }
+
+    /// Printings in a set that are not in the collection, in collector
+    /// number order.
+    pub fn missing_cards(&self, set_code: &str) -> Result<Vec<CardSet>> {
+        self.conn.get()?.ensure_collection()?;
+        self.conn.get()?.ensure_views(&["cards"])?;
+
+        self.conn
+            .get()?
+            .execute_into(MISSING_CARDS_SQL, &[set_code.to_uppercase().into()])
+    }
+}
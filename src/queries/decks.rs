@@ -2,12 +2,14 @@
 //!
 //! Unlike parquet-backed queries, deck data is stored as a JSON array in memory.
 //! The `DeckQuery` loads the deck list from the cache on first access and performs
-//! in-memory filtering.
+//! in-memory filtering. Full deck contents are fetched per deck from
+//! `decks/{fileName}.json` through the same cache.
 
 use serde_json::Value;
 
-use crate::connection::Connection;
+use crate::connection::{Connection, ConnectionRef};
 use crate::error::Result;
+use crate::models::Deck;
 
 // ---------------------------------------------------------------------------
 // DeckQuery
@@ -15,20 +17,29 @@ use crate::error::Result;
 
 /// Query interface for MTG decks backed by the cached `DeckList.json` data.
 pub struct DeckQuery<'a> {
-    conn: &'a Connection,
+    conn: ConnectionRef<'a>,
 }
 
 impl<'a> DeckQuery<'a> {
     /// Create a new `DeckQuery` bound to the given connection.
     pub fn new(conn: &'a Connection) -> Self {
-        Self { conn }
+        Self {
+            conn: ConnectionRef::Direct(conn),
+        }
+    }
+
+    /// Create a new `DeckQuery` on the calling thread's SDK connection.
+    pub(crate) fn from_sdk(sdk: &'a crate::MtgjsonSdk) -> Self {
+        Self {
+            conn: ConnectionRef::Sdk(sdk),
+        }
     }
 
     /// Load the deck list from the cache.
     ///
     /// Returns a `Vec<Value>` representing the array of deck objects.
     fn load_decks(&self) -> Result<Vec<Value>> {
-        let data = self.conn.cache.borrow_mut().load_json("deck_list")?;
+        let data = self.conn.get()?.cache().load_json("deck_list")?;
         match data {
             Value::Object(map) => {
                 // DeckList.json has { "data": [...] } structure
@@ -124,4 +135,26 @@ impl<'a> DeckQuery<'a> {
         let filtered = self.list(set_code, deck_type)?;
         Ok(filtered.len())
     }
+
+    /// Get the full contents of a deck by its `fileName` from the deck list.
+    ///
+    /// The deck file is downloaded on first access and cached alongside the
+    /// other data files, so it follows the same version and offline rules.
+    /// Returns `None` if no deck in `DeckList.json` has this `fileName`.
+    pub fn get(&self, file_name: &str) -> Result<Option<Deck>> {
+        let known = self.load_decks()?.iter().any(|d| {
+            d.get("fileName").and_then(|v| v.as_str()) == Some(file_name)
+        });
+        if !known {
+            return Ok(None);
+        }
+
+        let data = self.conn.get()?.cache().load_deck(file_name)?;
+        // Deck files have { "meta": {...}, "data": {...} } structure
+        let deck = match data {
+            Value::Object(mut map) if map.contains_key("data") => map.remove("data").unwrap(),
+            other => other,
+        };
+        Ok(Some(serde_json::from_value(deck)?))
+    }
 }
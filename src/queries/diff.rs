@@ -0,0 +1,196 @@
+//! Changelog between two cached MTGJSON versions.
+//!
+//! Both versions are attached to the connection with
+//! [`Connection::attach_version`](crate::connection::Connection::attach_version)
+//! under schemas unique to the call, and compared view by view:
+//! `cards` for added and removed printings and oracle text errata, `sets` for
+//! added and removed sets, `card_legalities` for legality changes and
+//! `card_identifiers` for new identifiers.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::connection::Connection;
+use crate::error::Result;
+use crate::models::DiffEntry;
+
+/// Numbers the schemas each [`DiffQuery::changes`] call attaches. The
+/// catalog is shared by every cloned connection, so concurrent calls must
+/// not reuse a schema name.
+static NEXT_DIFF: AtomicUsize = AtomicUsize::new(0);
+
+/// Views compared by [`DiffQuery::changes`]; each must be cached for both
+/// versions.
+const VIEWS: &[&str] = &["cards", "sets", "card_legalities", "card_identifiers"];
+
+/// Every change except new identifiers, which need the identifier columns
+/// of each version and are appended by [`DiffQuery::changes`]. `{old}` and
+/// `{new}` stand for the schemas the versions are attached as.
+const CHANGES_SQL: &str = r#"
+    SELECT 'card_added' AS kind, n.uuid AS key, NULL::VARCHAR AS field,
+           NULL::VARCHAR AS old_value, n.name AS new_value
+    FROM {new}.cards n
+    WHERE n.uuid NOT IN (SELECT uuid FROM {old}.cards)
+    UNION ALL
+    SELECT 'card_removed', o.uuid, NULL, o.name, NULL
+    FROM {old}.cards o
+    WHERE o.uuid NOT IN (SELECT uuid FROM {new}.cards)
+    UNION ALL
+    SELECT 'set_added', n.code, NULL, NULL, n.name
+    FROM {new}.sets n
+    WHERE n.code NOT IN (SELECT code FROM {old}.sets)
+    UNION ALL
+    SELECT 'set_removed', o.code, NULL, o.name, NULL
+    FROM {old}.sets o
+    WHERE o.code NOT IN (SELECT code FROM {new}.sets)
+    UNION ALL
+    SELECT 'text_changed', n.uuid, 'text', o.text, n.text
+    FROM {old}.cards o
+    JOIN {new}.cards n ON o.uuid = n.uuid
+    WHERE o.text IS DISTINCT FROM n.text
+    UNION ALL
+    SELECT 'legality_changed', COALESCE(n.uuid, o.uuid), COALESCE(n.format, o.format),
+           o.status, n.status
+    FROM {old}.card_legalities o
+    FULL OUTER JOIN {new}.card_legalities n
+        ON o.uuid = n.uuid AND o.format = n.format
+    WHERE o.status IS DISTINCT FROM n.status
+      AND COALESCE(n.uuid, o.uuid) IN (SELECT uuid FROM {old}.cards)
+      AND COALESCE(n.uuid, o.uuid) IN (SELECT uuid FROM {new}.cards)
+"#;
+
+// ---------------------------------------------------------------------------
+// DiffQuery
+// ---------------------------------------------------------------------------
+
+/// Compares two cached MTGJSON versions.
+///
+/// Versions are located like [`CacheManager::version_file`](crate::cache::CacheManager::version_file):
+/// the current cache or one kept under `versions/` by an earlier refresh.
+pub struct DiffQuery<'a> {
+    conn: crate::connection::ConnectionRef<'a>,
+}
+
+impl<'a> DiffQuery<'a> {
+    /// Create a new `DiffQuery` bound to the given connection.
+    pub fn new(conn: &'a crate::connection::Connection) -> Self {
+        Self {
+            conn: crate::connection::ConnectionRef::Direct(conn),
+        }
+    }
+
+    /// Create a new `DiffQuery` on the calling thread's SDK connection.
+    pub(crate) fn from_sdk(sdk: &'a crate::MtgjsonSdk) -> Self {
+        Self {
+            conn: crate::connection::ConnectionRef::Sdk(sdk),
+        }
+    }
+
+    /// List the changes from version `old` to version `new`, ordered by key
+    /// and field.
+    ///
+    /// Legality and identifier changes are only reported for printings in
+    /// both versions; added and removed printings are reported once as a
+    /// whole. Fails with [`MtgjsonError::NotFound`](crate::MtgjsonError::NotFound)
+    /// if a compared view is not cached for either version.
+    ///
+    /// Each call attaches the versions under its own schemas and drops them
+    /// before returning, so calls from several threads do not interfere.
+    pub fn changes(&self, old: &str, new: &str) -> Result<Vec<DiffEntry>> {
+        let conn = self.conn.get()?;
+        let id = NEXT_DIFF.fetch_add(1, Ordering::Relaxed);
+        let schemas = [format!("diff_old_{}", id), format!("diff_new_{}", id)];
+        let changes = self.changes_in(conn, &schemas[0], &schemas[1], old, new);
+        for schema in &schemas {
+            conn.detach_version(schema)?;
+        }
+        changes
+    }
+
+    /// Attach `old` and `new` as the schemas `old_schema` and `new_schema`
+    /// and compare them.
+    fn changes_in(
+        &self,
+        conn: &Connection,
+        old_schema: &str,
+        new_schema: &str,
+        old: &str,
+        new: &str,
+    ) -> Result<Vec<DiffEntry>> {
+        conn.attach_version(old_schema, old, VIEWS)?;
+        conn.attach_version(new_schema, new, VIEWS)?;
+
+        let changes_sql = CHANGES_SQL
+            .replace("{old}", old_schema)
+            .replace("{new}", new_schema);
+        let sql = format!(
+            "SELECT * FROM ({} UNION ALL {}) ORDER BY key, field NULLS FIRST, kind",
+            changes_sql,
+            self.identifiers_added_sql(old_schema, new_schema)?
+        );
+        conn.execute_into(&sql, &[])
+    }
+
+    /// Like [`changes`](Self::changes), serialized as newline-delimited
+    /// JSON, one [`DiffEntry`] per line.
+    pub fn changes_ndjson(&self, old: &str, new: &str) -> Result<String> {
+        let mut out = String::new();
+        for entry in self.changes(old, new)? {
+            out.push_str(&serde_json::to_string(&entry)?);
+            out.push('\n');
+        }
+        Ok(out)
+    }
+
+    /// Identifiers set in the new version for printings that had no value
+    /// for them in the old one, including identifier columns new to the
+    /// schema.
+    fn identifiers_added_sql(&self, old_schema: &str, new_schema: &str) -> Result<String> {
+        Ok(format!(
+            "SELECT 'identifier_added', n.uuid, n.field, NULL, n.value \
+             FROM ({}) n \
+             WHERE n.uuid IN (SELECT uuid FROM {}.cards) \
+               AND NOT EXISTS (SELECT 1 FROM ({}) o \
+                               WHERE o.uuid = n.uuid AND o.field = n.field)",
+            self.unpivot_identifiers(new_schema)?,
+            old_schema,
+            self.unpivot_identifiers(old_schema)?
+        ))
+    }
+
+    /// `(uuid, field, value)` rows for every non-null identifier of one
+    /// attached version.
+    fn unpivot_identifiers(&self, schema: &str) -> Result<String> {
+        let rows = self.conn.get()?.execute(
+            &format!(
+                "SELECT column_name FROM (DESCRIBE {}.card_identifiers)",
+                schema
+            ),
+            &[],
+        )?;
+        let columns: Vec<String> = rows
+            .iter()
+            .filter_map(|r| r.get("column_name").and_then(|v| v.as_str()))
+            .filter(|c| *c != "uuid")
+            .map(|c| format!("\"{}\"", c.replace('"', "\"\"")))
+            .collect();
+
+        if columns.is_empty() {
+            return Ok(
+                "SELECT NULL::VARCHAR AS uuid, NULL::VARCHAR AS field, \
+                 NULL::VARCHAR AS value WHERE false"
+                    .to_string(),
+            );
+        }
+        let casts: Vec<String> = columns
+            .iter()
+            .map(|c| format!("CAST({} AS VARCHAR) AS {}", c, c))
+            .collect();
+        Ok(format!(
+            "UNPIVOT (SELECT uuid, {} FROM {}.card_identifiers) \
+             ON {} INTO NAME field VALUE value",
+            casts.join(", "),
+            schema,
+            columns.join(", ")
+        ))
+    }
+}
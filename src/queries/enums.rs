@@ -5,7 +5,7 @@
 
 use serde_json::Value;
 
-use crate::connection::Connection;
+use crate::connection::{Connection, ConnectionRef};
 use crate::error::Result;
 
 // ---------------------------------------------------------------------------
@@ -14,13 +14,22 @@ use crate::error::Result;
 
 /// Query interface for MTGJSON enum/keyword data backed by cached JSON files.
 pub struct EnumQuery<'a> {
-    conn: &'a Connection,
+    conn: ConnectionRef<'a>,
 }
 
 impl<'a> EnumQuery<'a> {
     /// Create a new `EnumQuery` bound to the given connection.
     pub fn new(conn: &'a Connection) -> Self {
-        Self { conn }
+        Self {
+            conn: ConnectionRef::Direct(conn),
+        }
+    }
+
+    /// Create a new `EnumQuery` on the calling thread's SDK connection.
+    pub(crate) fn from_sdk(sdk: &'a crate::MtgjsonSdk) -> Self {
+        Self {
+            conn: ConnectionRef::Sdk(sdk),
+        }
     }
 
     /// Get all keyword categories.
@@ -29,7 +38,7 @@ impl<'a> EnumQuery<'a> {
     /// has keys like `"abilityWords"`, `"keywordAbilities"`, `"keywordActions"`, each
     /// mapping to an array of strings.
     pub fn keywords(&self) -> Result<Value> {
-        let data = self.conn.cache.borrow_mut().load_json("keywords")?;
+        let data = self.conn.get()?.cache().load_json("keywords")?;
         Ok(extract_data(data))
     }
 
@@ -39,7 +48,7 @@ impl<'a> EnumQuery<'a> {
     /// has keys for each card type (e.g., `"creature"`, `"instant"`, `"land"`), each
     /// containing `subTypes` and `superTypes` arrays.
     pub fn card_types(&self) -> Result<Value> {
-        let data = self.conn.cache.borrow_mut().load_json("card_types")?;
+        let data = self.conn.get()?.cache().load_json("card_types")?;
         Ok(extract_data(data))
     }
 
@@ -48,7 +57,7 @@ impl<'a> EnumQuery<'a> {
     /// Loads `EnumValues.json` and returns its `data` payload. Contains all valid
     /// enum values used across the MTGJSON data model.
     pub fn enum_values(&self) -> Result<Value> {
-        let data = self.conn.cache.borrow_mut().load_json("enum_values")?;
+        let data = self.conn.get()?.cache().load_json("enum_values")?;
         Ok(extract_data(data))
     }
 }
@@ -9,6 +9,7 @@ use serde_json::Value;
 
 use crate::error::{MtgjsonError, Result};
 use crate::sql_builder::SqlBuilder;
+use crate::sql_expr::SqlExpr;
 
 // ---------------------------------------------------------------------------
 // Known identifier columns
@@ -44,37 +45,56 @@ pub const KNOWN_ID_COLUMNS: &[&str] = &[
 
 /// Query interface for looking up cards by external identifiers.
 pub struct IdentifierQuery<'a> {
-    conn: &'a crate::connection::Connection,
+    conn: crate::connection::ConnectionRef<'a>,
 }
 
 impl<'a> IdentifierQuery<'a> {
     /// Create a new `IdentifierQuery` bound to the given connection.
     pub fn new(conn: &'a crate::connection::Connection) -> Self {
-        Self { conn }
+        Self {
+            conn: crate::connection::ConnectionRef::Direct(conn),
+        }
+    }
+
+    /// Create a new `IdentifierQuery` on the calling thread's SDK connection.
+    pub(crate) fn from_sdk(sdk: &'a crate::MtgjsonSdk) -> Self {
+        Self {
+            conn: crate::connection::ConnectionRef::Sdk(sdk),
+        }
     }
 
     /// Generic find: look up cards whose `column` in `card_identifiers` matches `value`.
     ///
     /// Returns full card rows (joined from the `cards` view).
     ///
-    /// Returns `Err(InvalidArgument)` if `column` is not in [`KNOWN_ID_COLUMNS`].
+    /// Returns `Err(InvalidArgument)` if `column` is not an identifier column
+    /// of the `card_identifiers` view, as introspected from its schema (see
+    /// [`KNOWN_ID_COLUMNS`] for the usual set).
     pub fn find_by(&self, column: &str, value: &str) -> Result<Vec<Value>> {
-        if !KNOWN_ID_COLUMNS.contains(&column) {
+        self.conn.get()?.ensure_views(&["cards", "card_identifiers"])?;
+
+        let valid: Vec<String> = self
+            .conn
+            .get()?
+            .view_columns("card_identifiers")?
+            .into_iter()
+            .filter(|c| !c.eq_ignore_ascii_case("uuid"))
+            .collect();
+        // DuckDB resolves column names case-insensitively, so match them the
+        // same way and query the column under its schema spelling
+        let Some(canonical) = valid.iter().find(|c| c.eq_ignore_ascii_case(column)) else {
             return Err(MtgjsonError::InvalidArgument(format!(
-                "Unknown identifier column: '{}'. Valid columns: {:?}",
-                column, KNOWN_ID_COLUMNS
+                "Unknown identifier column: {:?}. Valid columns: {:?}",
+                column, valid
             )));
-        }
-
-        self.conn.ensure_views(&["cards", "card_identifiers"])?;
+        };
 
-        let condition = format!("ci.{} = ?", column);
         let (sql, params) = SqlBuilder::new("cards c")
             .join("JOIN card_identifiers ci ON c.uuid = ci.uuid")
-            .where_clause(&condition, &[value])
+            .where_expr(SqlExpr::col(&format!("ci.{}", canonical)).eq(value))
             .build();
 
-        let rows = self.conn.execute(&sql, &params)?;
+        let rows = self.conn.get()?.execute(&sql, &params)?;
         Ok(rows_to_values(rows))
     }
 
@@ -82,14 +102,14 @@ impl<'a> IdentifierQuery<'a> {
     ///
     /// Returns the full `card_identifiers` row as a JSON object.
     pub fn get_identifiers(&self, uuid: &str) -> Result<Option<Value>> {
-        self.conn.ensure_views(&["card_identifiers"])?;
+        self.conn.get()?.ensure_views(&["card_identifiers"])?;
 
         let (sql, params) = SqlBuilder::new("card_identifiers")
             .where_eq("uuid", uuid)
             .limit(1)
             .build();
 
-        let rows = self.conn.execute(&sql, &params)?;
+        let rows = self.conn.get()?.execute(&sql, &params)?;
         Ok(rows
             .into_iter()
             .next()
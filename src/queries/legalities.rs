@@ -16,26 +16,35 @@ use crate::sql_builder::SqlBuilder;
 
 /// Query interface for MTG card legalities across all formats.
 pub struct LegalityQuery<'a> {
-    conn: &'a crate::connection::Connection,
+    conn: crate::connection::ConnectionRef<'a>,
 }
 
 impl<'a> LegalityQuery<'a> {
     /// Create a new `LegalityQuery` bound to the given connection.
     pub fn new(conn: &'a crate::connection::Connection) -> Self {
-        Self { conn }
+        Self {
+            conn: crate::connection::ConnectionRef::Direct(conn),
+        }
+    }
+
+    /// Create a new `LegalityQuery` on the calling thread's SDK connection.
+    pub(crate) fn from_sdk(sdk: &'a crate::MtgjsonSdk) -> Self {
+        Self {
+            conn: crate::connection::ConnectionRef::Sdk(sdk),
+        }
     }
 
     /// Get all format/status pairs for a given card UUID.
     ///
     /// Returns a list of objects each containing `format` and `status` keys.
     pub fn formats_for_card(&self, uuid: &str) -> Result<Vec<Value>> {
-        self.conn.ensure_views(&["card_legalities"])?;
+        self.conn.get()?.ensure_views(&["card_legalities"])?;
 
         let (sql, params) = SqlBuilder::new("card_legalities")
             .where_eq("uuid", uuid)
             .build();
 
-        let rows = self.conn.execute(&sql, &params)?;
+        let rows = self.conn.get()?.execute(&sql, &params)?;
         Ok(rows_to_values(rows))
     }
 
@@ -43,7 +52,7 @@ impl<'a> LegalityQuery<'a> {
     ///
     /// Returns `true` if the card's status for that format is `"Legal"`.
     pub fn is_legal(&self, uuid: &str, format: &str) -> Result<bool> {
-        self.conn.ensure_views(&["card_legalities"])?;
+        self.conn.get()?.ensure_views(&["card_legalities"])?;
 
         let (sql, params) = SqlBuilder::new("card_legalities")
             .select(&["COUNT(*) AS cnt"])
@@ -52,7 +61,7 @@ impl<'a> LegalityQuery<'a> {
             .where_eq("status", "Legal")
             .build();
 
-        let rows = self.conn.execute(&sql, &params)?;
+        let rows = self.conn.get()?.execute(&sql, &params)?;
         let cnt = rows
             .first()
             .and_then(|r| r.get("cnt"))
@@ -66,7 +75,7 @@ impl<'a> LegalityQuery<'a> {
     ///
     /// Joins `card_legalities` with `cards` to return full card data.
     pub fn legal_in(&self, format: &str) -> Result<Vec<Value>> {
-        self.conn.ensure_views(&["cards", "card_legalities"])?;
+        self.conn.get()?.ensure_views(&["cards", "card_legalities"])?;
 
         let (sql, params) = SqlBuilder::new("cards c")
             .join("JOIN card_legalities cl ON c.uuid = cl.uuid")
@@ -74,13 +83,13 @@ impl<'a> LegalityQuery<'a> {
             .where_eq("cl.status", "Legal")
             .build();
 
-        let rows = self.conn.execute(&sql, &params)?;
+        let rows = self.conn.get()?.execute(&sql, &params)?;
         Ok(rows_to_values(rows))
     }
 
     /// Get all cards that are banned in the given format.
     pub fn banned_in(&self, format: &str) -> Result<Vec<Value>> {
-        self.conn.ensure_views(&["cards", "card_legalities"])?;
+        self.conn.get()?.ensure_views(&["cards", "card_legalities"])?;
 
         let (sql, params) = SqlBuilder::new("cards c")
             .join("JOIN card_legalities cl ON c.uuid = cl.uuid")
@@ -88,13 +97,13 @@ impl<'a> LegalityQuery<'a> {
             .where_eq("cl.status", "Banned")
             .build();
 
-        let rows = self.conn.execute(&sql, &params)?;
+        let rows = self.conn.get()?.execute(&sql, &params)?;
         Ok(rows_to_values(rows))
     }
 
     /// Get all cards that are restricted in the given format.
     pub fn restricted_in(&self, format: &str) -> Result<Vec<Value>> {
-        self.conn.ensure_views(&["cards", "card_legalities"])?;
+        self.conn.get()?.ensure_views(&["cards", "card_legalities"])?;
 
         let (sql, params) = SqlBuilder::new("cards c")
             .join("JOIN card_legalities cl ON c.uuid = cl.uuid")
@@ -102,13 +111,13 @@ impl<'a> LegalityQuery<'a> {
             .where_eq("cl.status", "Restricted")
             .build();
 
-        let rows = self.conn.execute(&sql, &params)?;
+        let rows = self.conn.get()?.execute(&sql, &params)?;
         Ok(rows_to_values(rows))
     }
 
     /// Get all cards that are suspended in the given format.
     pub fn suspended_in(&self, format: &str) -> Result<Vec<Value>> {
-        self.conn.ensure_views(&["cards", "card_legalities"])?;
+        self.conn.get()?.ensure_views(&["cards", "card_legalities"])?;
 
         let (sql, params) = SqlBuilder::new("cards c")
             .join("JOIN card_legalities cl ON c.uuid = cl.uuid")
@@ -116,13 +125,13 @@ impl<'a> LegalityQuery<'a> {
             .where_eq("cl.status", "Suspended")
             .build();
 
-        let rows = self.conn.execute(&sql, &params)?;
+        let rows = self.conn.get()?.execute(&sql, &params)?;
         Ok(rows_to_values(rows))
     }
 
     /// Get all cards that are not legal in the given format.
     pub fn not_legal_in(&self, format: &str) -> Result<Vec<Value>> {
-        self.conn.ensure_views(&["cards", "card_legalities"])?;
+        self.conn.get()?.ensure_views(&["cards", "card_legalities"])?;
 
         let (sql, params) = SqlBuilder::new("cards c")
             .join("JOIN card_legalities cl ON c.uuid = cl.uuid")
@@ -130,7 +139,7 @@ impl<'a> LegalityQuery<'a> {
             .where_eq("cl.status", "Not Legal")
             .build();
 
-        let rows = self.conn.execute(&sql, &params)?;
+        let rows = self.conn.get()?.execute(&sql, &params)?;
         Ok(rows_to_values(rows))
     }
 }
@@ -2,26 +2,34 @@
 //!
 //! Each module provides a query struct that borrows from a [`Connection`](crate::connection::Connection)
 //! (or [`CacheManager`](crate::cache::CacheManager) for JSON-only queries) and exposes
-//! methods returning `Result<T>` with `serde_json::Value` payloads.
+//! methods returning `Result<T>` with `serde_json::Value` payloads. Query structs
+//! backed by a model also offer a `typed()` view returning the structs from
+//! [`models`](crate::models) instead.
 
 pub mod cards;
+pub mod collection;
 pub mod decks;
+pub mod diff;
 pub mod enums;
 pub mod identifiers;
 pub mod legalities;
 pub mod prices;
 pub mod sealed;
+pub mod search_syntax;
 pub mod sets;
 pub mod skus;
 pub mod tokens;
 
-pub use cards::{CardQuery, SearchCardsParams};
+pub use cards::{CardQuery, SearchCardsParams, TypedCardQuery};
+pub use collection::CollectionQuery;
 pub use decks::DeckQuery;
+pub use diff::DiffQuery;
 pub use enums::EnumQuery;
 pub use identifiers::IdentifierQuery;
 pub use legalities::LegalityQuery;
-pub use prices::PriceQuery;
-pub use sealed::SealedQuery;
-pub use sets::{SearchSetsParams, SetQuery};
+pub use prices::{PriceQuery, TypedPriceQuery};
+pub use sealed::{SealedQuery, TypedSealedQuery};
+pub use search_syntax::SearchQuery;
+pub use sets::{SearchSetsParams, SetQuery, TypedSetQuery};
 pub use skus::SkuQuery;
-pub use tokens::{SearchTokensParams, TokenQuery};
+pub use tokens::{SearchTokensParams, TokenQuery, TypedTokenQuery};
@@ -2,10 +2,43 @@
 
 use std::collections::HashMap;
 
+#[cfg(feature = "arrow")]
+use duckdb::arrow::record_batch::RecordBatch;
+use serde::de::DeserializeOwned;
 use serde_json::Value;
 
 use crate::error::Result;
+use crate::models::{PriceRow, PriceTrend, PricedCard};
 use crate::sql_builder::SqlBuilder;
+use crate::sql_value::SqlValue;
+use crate::stream::TypedRowStream;
+
+/// Nested price map: source -> provider -> currency -> price_type -> finish -> {date: price}.
+type NestedPrices = HashMap<
+    String,
+    HashMap<String, HashMap<String, HashMap<String, HashMap<String, HashMap<String, f64>>>>>,
+>;
+
+/// Most recent prices for a single card UUID.
+const TODAY_SQL: &str = r#"
+    SELECT *
+    FROM all_prices_today
+    WHERE uuid = ?
+      AND date = (SELECT MAX(date) FROM all_prices_today WHERE uuid = ?)
+"#;
+
+/// Aggregate price statistics for a single card UUID.
+const PRICE_TREND_SQL: &str = r#"
+    SELECT
+        MIN(price) AS min_price,
+        MAX(price) AS max_price,
+        AVG(price) AS avg_price,
+        MIN(date) AS first_date,
+        MAX(date) AS last_date,
+        COUNT(*) AS data_points
+    FROM all_prices
+    WHERE uuid = ?
+"#;
 
 // ---------------------------------------------------------------------------
 // PriceQuery
@@ -13,31 +46,50 @@ use crate::sql_builder::SqlBuilder;
 
 /// Query interface for MTG card prices backed by the `all_prices_today` DuckDB view.
 pub struct PriceQuery<'a> {
-    conn: &'a crate::connection::Connection,
+    conn: crate::connection::ConnectionRef<'a>,
 }
 
 impl<'a> PriceQuery<'a> {
     /// Create a new `PriceQuery` bound to the given connection.
     pub fn new(conn: &'a crate::connection::Connection) -> Self {
-        Self { conn }
+        Self {
+            conn: crate::connection::ConnectionRef::Direct(conn),
+        }
+    }
+
+    /// Create a new `PriceQuery` on the calling thread's SDK connection.
+    pub(crate) fn from_sdk(sdk: &'a crate::MtgjsonSdk) -> Self {
+        Self {
+            conn: crate::connection::ConnectionRef::Sdk(sdk),
+        }
+    }
+
+    /// Return a typed view of this query interface.
+    ///
+    /// The typed interface runs the same queries but deserializes each row
+    /// into [`PriceRow`], [`PriceTrend`] or [`PricedCard`] via
+    /// [`Connection::execute_into`](crate::connection::Connection::execute_into).
+    pub fn typed(&self) -> TypedPriceQuery<'a> {
+        TypedPriceQuery {
+            inner: PriceQuery { conn: self.conn },
+        }
     }
 
     /// Get the full nested price structure for a card UUID.
     ///
     /// Returns a nested object keyed by `source -> provider -> price_type -> finish -> date -> price`.
     pub fn get(&self, uuid: &str) -> Result<Value> {
-        self.conn.ensure_views(&["all_prices_today"])?;
+        self.conn.get()?.ensure_views(&["all_prices_today"])?;
 
         let (sql, params) = SqlBuilder::new("all_prices_today")
             .where_eq("uuid", uuid)
             .order_by(&["date DESC"])
             .build();
 
-        let rows = self.conn.execute(&sql, &params)?;
+        let rows = self.conn.get()?.execute(&sql, &params)?;
 
         // Build a nested map: source -> provider -> currency -> price_type -> finish -> {date: price}
-        let mut result: HashMap<String, HashMap<String, HashMap<String, HashMap<String, HashMap<String, HashMap<String, f64>>>>>> =
-            HashMap::new();
+        let mut result: NestedPrices = HashMap::new();
 
         for row in &rows {
             let source = row.get("source").and_then(|v| v.as_str()).unwrap_or("");
@@ -70,17 +122,23 @@ impl<'a> PriceQuery<'a> {
 
     /// Get the most recent price for each provider/price_type/finish group for a card UUID.
     pub fn today(&self, uuid: &str) -> Result<Vec<Value>> {
-        self.conn.ensure_views(&["all_prices_today"])?;
+        self.fetch_today(uuid)
+    }
 
-        let sql = r#"
-            SELECT *
-            FROM all_prices_today
-            WHERE uuid = ?
-              AND date = (SELECT MAX(date) FROM all_prices_today WHERE uuid = ?)
-        "#;
+    /// Arrow counterpart of [`today`](Self::today).
+    #[cfg(feature = "arrow")]
+    pub fn today_arrow(&self, uuid: &str) -> Result<Vec<RecordBatch>> {
+        self.conn.get()?.ensure_views(&["all_prices_today"])?;
+        self.conn
+            .get()?
+            .execute_arrow(TODAY_SQL, &[uuid.into(), uuid.into()])
+    }
 
-        let rows = self.conn.execute(sql, &[uuid.to_string(), uuid.to_string()])?;
-        Ok(rows_to_values(rows))
+    fn fetch_today<T: DeserializeOwned>(&self, uuid: &str) -> Result<Vec<T>> {
+        self.conn.get()?.ensure_views(&["all_prices_today"])?;
+        self.conn
+            .get()?
+            .execute_into(TODAY_SQL, &[uuid.into(), uuid.into()])
     }
 
     /// Get price history for a card UUID, optionally filtered by date range.
@@ -90,44 +148,64 @@ impl<'a> PriceQuery<'a> {
         date_from: Option<&str>,
         date_to: Option<&str>,
     ) -> Result<Vec<Value>> {
-        self.conn.ensure_views(&["all_prices"])?;
+        self.fetch_history(uuid, date_from, date_to)
+    }
+
+    /// Arrow counterpart of [`history`](Self::history).
+    ///
+    /// Returns the price history as record batches with `date` and `price`
+    /// kept as native DuckDB types.
+    #[cfg(feature = "arrow")]
+    pub fn history_arrow(
+        &self,
+        uuid: &str,
+        date_from: Option<&str>,
+        date_to: Option<&str>,
+    ) -> Result<Vec<RecordBatch>> {
+        let (sql, params) = self.history_sql(uuid, date_from, date_to)?;
+        self.conn.get()?.execute_arrow(&sql, &params)
+    }
+
+    fn fetch_history<T: DeserializeOwned>(
+        &self,
+        uuid: &str,
+        date_from: Option<&str>,
+        date_to: Option<&str>,
+    ) -> Result<Vec<T>> {
+        let (sql, params) = self.history_sql(uuid, date_from, date_to)?;
+        self.conn.get()?.execute_into(&sql, &params)
+    }
+
+    fn history_sql(
+        &self,
+        uuid: &str,
+        date_from: Option<&str>,
+        date_to: Option<&str>,
+    ) -> Result<(String, Vec<SqlValue>)> {
+        self.conn.get()?.ensure_views(&["all_prices"])?;
 
         let mut qb = SqlBuilder::new("all_prices");
         qb.where_eq("uuid", uuid);
         qb.order_by(&["date ASC"]);
 
         if let Some(df) = date_from {
-            qb.where_gte("date", df);
+            qb.where_gte("date", SqlValue::Date(df.to_string()));
         }
 
         if let Some(dt) = date_to {
-            qb.where_lte("date", dt);
+            qb.where_lte("date", SqlValue::Date(dt.to_string()));
         }
 
-        let (sql, params) = qb.build();
-        let rows = self.conn.execute(&sql, &params)?;
-        Ok(rows_to_values(rows))
+        Ok(qb.build())
     }
 
     /// Get aggregated price trend statistics for a card UUID.
     ///
     /// Returns `min_price`, `max_price`, `avg_price`, `first_date`, `last_date`, `data_points`.
     pub fn price_trend(&self, uuid: &str) -> Result<Value> {
-        self.conn.ensure_views(&["all_prices"])?;
-
-        let sql = r#"
-            SELECT
-                MIN(price) AS min_price,
-                MAX(price) AS max_price,
-                AVG(price) AS avg_price,
-                MIN(date) AS first_date,
-                MAX(date) AS last_date,
-                COUNT(*) AS data_points
-            FROM all_prices
-            WHERE uuid = ?
-        "#;
-
-        let rows = self.conn.execute(sql, &[uuid.to_string()])?;
+        self.conn.get()?.ensure_views(&["all_prices"])?;
+
+        let rows = self.conn.get()?.execute(PRICE_TREND_SQL, &[uuid.into()])?;
         Ok(rows
             .into_iter()
             .next()
@@ -139,47 +217,29 @@ impl<'a> PriceQuery<'a> {
     ///
     /// Joins `cards` to `all_prices_today` and returns the printing with the lowest price.
     pub fn cheapest_printing(&self, name: &str) -> Result<Option<Value>> {
-        self.conn.ensure_views(&["cards", "all_prices_today"])?;
-
-        let sql = r#"
-            SELECT c.*, p.price, p.source, p.provider, p.finish, p.date
-            FROM cards c
-            JOIN all_prices_today p ON c.uuid = p.uuid
-            WHERE c.name = ?
-            ORDER BY p.price ASC
-            LIMIT 1
-        "#;
-
-        let rows = self.conn.execute(sql, &[name.to_string()])?;
-        Ok(rows
-            .into_iter()
-            .next()
-            .map(|r| serde_json::to_value(r).unwrap_or(Value::Null)))
+        Ok(self.fetch_printings(name, "ASC", 1)?.into_iter().next())
     }
 
     /// Find the N cheapest printings of a card by name, ordered by ascending price.
     pub fn cheapest_printings(&self, name: &str, limit: usize) -> Result<Vec<Value>> {
-        self.conn.ensure_views(&["cards", "all_prices_today"])?;
-
-        let sql = format!(
-            r#"
-            SELECT c.*, p.price, p.source, p.provider, p.finish, p.date
-            FROM cards c
-            JOIN all_prices_today p ON c.uuid = p.uuid
-            WHERE c.name = ?
-            ORDER BY p.price ASC
-            LIMIT {}
-            "#,
-            limit
-        );
-
-        let rows = self.conn.execute(&sql, &[name.to_string()])?;
-        Ok(rows_to_values(rows))
+        self.fetch_printings(name, "ASC", limit)
     }
 
     /// Find the N most expensive printings of a card by name, ordered by descending price.
     pub fn most_expensive_printings(&self, name: &str, limit: usize) -> Result<Vec<Value>> {
-        self.conn.ensure_views(&["cards", "all_prices_today"])?;
+        self.fetch_printings(name, "DESC", limit)
+    }
+
+    /// Join `cards` to `all_prices_today` for a card name, ordered by price.
+    ///
+    /// `direction` is a fixed `"ASC"` / `"DESC"` literal supplied by the caller.
+    fn fetch_printings<T: DeserializeOwned>(
+        &self,
+        name: &str,
+        direction: &str,
+        limit: usize,
+    ) -> Result<Vec<T>> {
+        self.conn.get()?.ensure_views(&["cards", "all_prices_today"])?;
 
         let sql = format!(
             r#"
@@ -187,23 +247,122 @@ impl<'a> PriceQuery<'a> {
             FROM cards c
             JOIN all_prices_today p ON c.uuid = p.uuid
             WHERE c.name = ?
-            ORDER BY p.price DESC
+            ORDER BY p.price {}
             LIMIT {}
             "#,
-            limit
+            direction, limit
         );
 
-        let rows = self.conn.execute(&sql, &[name.to_string()])?;
-        Ok(rows_to_values(rows))
+        self.conn.get()?.execute_into(&sql, &[name.into()])
+    }
+
+    // -- Streaming scans ---------------------------------------------------
+
+    /// Lazily iterate over every row of the `all_prices` history, optionally
+    /// restricted to a date range.
+    ///
+    /// Rows are streamed from DuckDB instead of being collected, so this is
+    /// suitable for exporting full price history. Finish iterating before
+    /// running other queries on the same connection; see
+    /// [`Connection::stream`](crate::connection::Connection::stream).
+    pub fn scan(
+        &self,
+        date_from: Option<&str>,
+        date_to: Option<&str>,
+    ) -> Result<TypedRowStream<'a, Value>> {
+        self.scan_into("all_prices", date_from, date_to)
+    }
+
+    /// Lazily iterate over every row of `all_prices_today`.
+    pub fn scan_today(&self) -> Result<TypedRowStream<'a, Value>> {
+        self.scan_into("all_prices_today", None, None)
+    }
+
+    fn scan_into<T: DeserializeOwned>(
+        &self,
+        view: &str,
+        date_from: Option<&str>,
+        date_to: Option<&str>,
+    ) -> Result<TypedRowStream<'a, T>> {
+        self.conn.get()?.ensure_views(&[view])?;
+
+        let mut qb = SqlBuilder::new(view);
+        if let Some(df) = date_from {
+            qb.where_gte("date", SqlValue::Date(df.to_string()));
+        }
+        if let Some(dt) = date_to {
+            qb.where_lte("date", SqlValue::Date(dt.to_string()));
+        }
+
+        let (sql, params) = qb.build();
+        self.conn.get()?.execute_iter(&sql, &params)
     }
 }
 
 // ---------------------------------------------------------------------------
-// Helpers
+// TypedPriceQuery
 // ---------------------------------------------------------------------------
 
-fn rows_to_values(rows: Vec<HashMap<String, Value>>) -> Vec<Value> {
-    rows.into_iter()
-        .map(|r| serde_json::to_value(r).unwrap_or(Value::Null))
-        .collect()
+/// Typed counterpart of [`PriceQuery`] returning [`PriceRow`] and related models.
+///
+/// Obtained via [`PriceQuery::typed`].
+pub struct TypedPriceQuery<'a> {
+    inner: PriceQuery<'a>,
+}
+
+impl<'a> TypedPriceQuery<'a> {
+    /// Get the most recent price rows for a card UUID.
+    pub fn today(&self, uuid: &str) -> Result<Vec<PriceRow>> {
+        self.inner.fetch_today(uuid)
+    }
+
+    /// Get price history for a card UUID, optionally filtered by date range.
+    pub fn history(
+        &self,
+        uuid: &str,
+        date_from: Option<&str>,
+        date_to: Option<&str>,
+    ) -> Result<Vec<PriceRow>> {
+        self.inner.fetch_history(uuid, date_from, date_to)
+    }
+
+    /// Get aggregated price trend statistics for a card UUID.
+    ///
+    /// Returns `None` if the card has no price history.
+    pub fn price_trend(&self, uuid: &str) -> Result<Option<PriceTrend>> {
+        self.inner.conn.get()?.ensure_views(&["all_prices"])?;
+
+        let sql = format!("{} HAVING COUNT(*) > 0", PRICE_TREND_SQL.trim_end());
+        let rows = self.inner.conn.get()?.execute_into(&sql, &[uuid.into()])?;
+        Ok(rows.into_iter().next())
+    }
+
+    /// Find the cheapest printing of a card by name.
+    pub fn cheapest_printing(&self, name: &str) -> Result<Option<PricedCard>> {
+        Ok(self.inner.fetch_printings(name, "ASC", 1)?.into_iter().next())
+    }
+
+    /// Find the N cheapest printings of a card by name, ordered by ascending price.
+    pub fn cheapest_printings(&self, name: &str, limit: usize) -> Result<Vec<PricedCard>> {
+        self.inner.fetch_printings(name, "ASC", limit)
+    }
+
+    /// Find the N most expensive printings of a card by name, ordered by descending price.
+    pub fn most_expensive_printings(&self, name: &str, limit: usize) -> Result<Vec<PricedCard>> {
+        self.inner.fetch_printings(name, "DESC", limit)
+    }
+
+    /// Lazily iterate over the `all_prices` history as [`PriceRow`]s.
+    pub fn scan(
+        &self,
+        date_from: Option<&str>,
+        date_to: Option<&str>,
+    ) -> Result<TypedRowStream<'a, PriceRow>> {
+        self.inner.scan_into("all_prices", date_from, date_to)
+    }
+
+    /// Lazily iterate over `all_prices_today` as [`PriceRow`]s.
+    pub fn scan_today(&self) -> Result<TypedRowStream<'a, PriceRow>> {
+        self.inner.scan_into("all_prices_today", None, None)
+    }
 }
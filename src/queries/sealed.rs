@@ -3,12 +3,14 @@
 //! Sealed product data lives in the `sets` table's `sealedProduct` column. This module
 //! gracefully returns empty results if the column doesn't exist in the schema.
 
-use std::collections::HashMap;
-
+use serde::Deserialize;
 use serde_json::Value;
 
 use crate::error::Result;
+use crate::models::SealedProduct;
 use crate::sql_builder::SqlBuilder;
+use crate::sql_expr::SqlExpr;
+use crate::sql_value::SqlValue;
 
 // ---------------------------------------------------------------------------
 // SealedQuery
@@ -16,45 +18,54 @@ use crate::sql_builder::SqlBuilder;
 
 /// Query interface for MTG sealed products derived from set data.
 pub struct SealedQuery<'a> {
-    conn: &'a crate::connection::Connection,
+    conn: crate::connection::ConnectionRef<'a>,
 }
 
 impl<'a> SealedQuery<'a> {
     /// Create a new `SealedQuery` bound to the given connection.
     pub fn new(conn: &'a crate::connection::Connection) -> Self {
-        Self { conn }
+        Self {
+            conn: crate::connection::ConnectionRef::Direct(conn),
+        }
+    }
+
+    /// Create a new `SealedQuery` on the calling thread's SDK connection.
+    pub(crate) fn from_sdk(sdk: &'a crate::MtgjsonSdk) -> Self {
+        Self {
+            conn: crate::connection::ConnectionRef::Sdk(sdk),
+        }
+    }
+
+    /// Return a typed view of this query interface.
+    ///
+    /// The typed interface runs the same queries but deserializes each
+    /// product into [`SealedProduct`] via
+    /// [`Connection::execute_into`](crate::connection::Connection::execute_into).
+    pub fn typed(&self) -> TypedSealedQuery<'a> {
+        TypedSealedQuery {
+            inner: SealedQuery { conn: self.conn },
+        }
     }
 
     /// Check whether the `sealedProduct` column exists on the `sets` table.
-    fn has_sealed_column(&self) -> bool {
+    fn has_sealed_column(&self) -> Result<bool> {
         // Try a lightweight probe query; if it fails, the column doesn't exist.
         let sql = "SELECT sealedProduct FROM sets LIMIT 0";
-        self.conn.execute(sql, &[]).is_ok()
+        Ok(self.conn.get()?.execute(sql, &[]).is_ok())
     }
 
     /// List all sealed products, optionally filtered by set code.
     ///
     /// Returns an empty vector if the `sealedProduct` column is not present.
     pub fn list(&self, set_code: Option<&str>) -> Result<Vec<Value>> {
-        self.conn.ensure_views(&["sets"])?;
+        self.conn.get()?.ensure_views(&["sets"])?;
 
-        if !self.has_sealed_column() {
+        if !self.has_sealed_column()? {
             return Ok(Vec::new());
         }
 
-        let mut qb = SqlBuilder::new("sets");
-        qb.select(&["code", "name", "sealedProduct"]);
-
-        if let Some(sc) = set_code {
-            let upper = sc.to_uppercase();
-            qb.where_eq("code", &upper);
-        }
-
-        // Only include sets that actually have sealed product data
-        qb.where_clause("sealedProduct IS NOT NULL", &[]);
-
-        let (sql, params) = qb.build();
-        let rows = self.conn.execute(&sql, &params)?;
+        let (sql, params) = sealed_query(set_code);
+        let rows = self.conn.get()?.execute(&sql, &params)?;
 
         // Flatten: each row may contain a list of sealed products under the
         // `sealedProduct` key. We extract and tag each product with the set code.
@@ -95,13 +106,75 @@ impl<'a> SealedQuery<'a> {
     }
 }
 
+// ---------------------------------------------------------------------------
+// TypedSealedQuery
+// ---------------------------------------------------------------------------
+
+/// Typed counterpart of [`SealedQuery`] returning [`SealedProduct`] models.
+///
+/// Obtained via [`SealedQuery::typed`].
+pub struct TypedSealedQuery<'a> {
+    inner: SealedQuery<'a>,
+}
+
+impl<'a> TypedSealedQuery<'a> {
+    /// List all sealed products, optionally filtered by set code.
+    ///
+    /// Products without a `setCode` of their own are tagged with the code of
+    /// the set they were listed under.
+    pub fn list(&self, set_code: Option<&str>) -> Result<Vec<SealedProduct>> {
+        self.inner.conn.get()?.ensure_views(&["sets"])?;
+
+        if !self.inner.has_sealed_column()? {
+            return Ok(Vec::new());
+        }
+
+        let (sql, params) = sealed_query(set_code);
+        let rows: Vec<SealedRow> = self.inner.conn.get()?.execute_into(&sql, &params)?;
+
+        let mut results = Vec::new();
+        for row in rows {
+            for mut product in row.sealed_product.unwrap_or_default() {
+                if product.set_code.is_none() {
+                    product.set_code = Some(row.code.clone());
+                }
+                results.push(product);
+            }
+        }
+        Ok(results)
+    }
+
+    /// Get sealed products for a specific set code.
+    pub fn get(&self, set_code: &str) -> Result<Vec<SealedProduct>> {
+        self.list(Some(set_code))
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Helpers
 // ---------------------------------------------------------------------------
 
-#[allow(dead_code)]
-fn rows_to_values(rows: Vec<HashMap<String, Value>>) -> Vec<Value> {
-    rows.into_iter()
-        .map(|r| serde_json::to_value(r).unwrap_or(Value::Null))
-        .collect()
+/// A `sets` row projected down to its sealed product list.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SealedRow {
+    code: String,
+    #[serde(default, deserialize_with = "crate::models::de::json_column")]
+    sealed_product: Option<Vec<SealedProduct>>,
+}
+
+/// Build the query selecting sets that carry sealed product data.
+fn sealed_query(set_code: Option<&str>) -> (String, Vec<SqlValue>) {
+    let mut qb = SqlBuilder::new("sets");
+    qb.select(&["code", "name", "sealedProduct"]);
+
+    if let Some(sc) = set_code {
+        let upper = sc.to_uppercase();
+        qb.where_eq("code", &upper);
+    }
+
+    // Only include sets that actually have sealed product data
+    qb.where_expr(SqlExpr::col("sealedProduct").is_not_null());
+
+    qb.build()
 }
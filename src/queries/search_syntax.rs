@@ -0,0 +1,779 @@
+//! Scryfall-style search syntax for card queries.
+//!
+//! Parses query strings such as `t:creature c>=rg mv<=3 o:"draw a card" f:modern`
+//! into a boolean expression and compiles it to a single parameterized WHERE
+//! condition over the `cards` view. Legality and set-level filters become
+//! correlated subqueries against `card_legalities` and `sets`, so they compose
+//! freely with `or`, `-`/`not` and parentheses.
+//!
+//! # Grammar
+//!
+//! ```text
+//! query   := or_expr
+//! or_expr := and_expr ("or" and_expr)*
+//! and_expr:= unary (["and"] unary)*
+//! unary   := ("-" | "not") unary | "(" or_expr ")" | term
+//! term    := key op value | "!" name | name
+//! op      := ":" | "=" | "!=" | "<" | "<=" | ">" | ">="
+//! ```
+//!
+//! Values and bare names may be double-quoted to include spaces. Positions in
+//! error messages are 0-based character offsets into the query string.
+//!
+//! # Supported keys
+//!
+//! | Key | Meaning |
+//! |---|---|
+//! | `name` | name contains |
+//! | `t`, `type` | type line contains |
+//! | `o`, `oracle` | rules text contains |
+//! | `ft`, `flavor` | flavor text contains |
+//! | `a`, `artist` | artist contains |
+//! | `c`, `color` | colors (`:` means "at least these") |
+//! | `id`, `identity`, `ci` | color identity (`:` means "within these") |
+//! | `mv`, `cmc`, `manavalue` | mana value |
+//! | `pow`, `power`, `tou`, `toughness` | power / toughness |
+//! | `r`, `rarity` | rarity (ordered common < uncommon < rare < mythic) |
+//! | `f`, `format`, `legal` | legal in format |
+//! | `banned`, `restricted` | banned / restricted in format |
+//! | `s`, `set`, `e`, `edition` | set code |
+//! | `st`, `settype` | set type |
+//! | `year` | set release year |
+//! | `kw`, `keyword` | has keyword |
+//! | `is`, `not` | boolean flags (`promo`, `reprint`, `reserved`, `fullart`, ...) |
+//! | `lang`, `language` | printed language |
+//! | `layout` | card layout |
+//! | `game` | availability (`paper`, `mtgo`, `arena`) |
+
+use std::iter::Peekable;
+use std::vec::IntoIter;
+
+use crate::error::{MtgjsonError, Result};
+use crate::sql_builder::SqlBuilder;
+use crate::sql_value::SqlValue;
+
+/// SQL expression ranking `cards.rarity` so rarities can be compared with `<`/`>`.
+const RARITY_RANK_SQL: &str = "CASE cards.rarity \
+     WHEN 'common' THEN 0 WHEN 'uncommon' THEN 1 WHEN 'rare' THEN 2 \
+     WHEN 'mythic' THEN 3 WHEN 'special' THEN 4 WHEN 'bonus' THEN 5 END";
+
+const RARITIES: [&str; 6] = ["common", "uncommon", "rare", "mythic", "special", "bonus"];
+
+// ---------------------------------------------------------------------------
+// Public API
+// ---------------------------------------------------------------------------
+
+/// A parsed search query, ready to be applied to a [`SqlBuilder`] over `cards`.
+#[derive(Debug, Clone)]
+pub struct SearchQuery {
+    expr: Expr,
+}
+
+/// Parse a Scryfall-style search string.
+///
+/// Returns [`MtgjsonError::InvalidArgument`] describing the problem and its
+/// character position if the query is empty or malformed, or uses an unknown
+/// key, operator or value.
+pub fn parse(query: &str) -> Result<SearchQuery> {
+    let tokens = tokenize(query)?;
+    if tokens.is_empty() {
+        return Err(syntax_error("empty search query", 0));
+    }
+    let mut parser = Parser {
+        tokens: tokens.into_iter().peekable(),
+        end: query.chars().count(),
+    };
+    let expr = parser.parse_or()?;
+    if let Some(tok) = parser.tokens.peek() {
+        return Err(syntax_error("unexpected ')'", tok.pos));
+    }
+    Ok(SearchQuery { expr })
+}
+
+impl SearchQuery {
+    /// Views the compiled condition reads from, always including `cards`.
+    pub fn views(&self) -> Vec<&'static str> {
+        let mut views = vec!["cards"];
+        let mut needs = |view: &'static str| {
+            if !views.contains(&view) {
+                views.push(view);
+            }
+        };
+        self.expr.visit(&mut |cond| match cond {
+            Cond::Legality { .. } => needs("card_legalities"),
+            Cond::SetType(_) | Cond::Year { .. } => needs("sets"),
+            _ => {}
+        });
+        views
+    }
+
+    /// Compile to a single SQL condition with `?` placeholders and its params.
+    pub fn to_sql(&self) -> (String, Vec<SqlValue>) {
+        let mut params = Vec::new();
+        let sql = self.expr.compile(&mut params);
+        (sql, params)
+    }
+
+    /// Add the compiled condition to `qb` as one WHERE clause.
+    pub fn apply(&self, qb: &mut SqlBuilder) {
+        let (sql, params) = self.to_sql();
+        qb.where_clause(&sql, &params);
+    }
+}
+
+fn syntax_error(msg: &str, pos: usize) -> MtgjsonError {
+    MtgjsonError::InvalidArgument(format!(
+        "Invalid search query: {} at position {}",
+        msg, pos
+    ))
+}
+
+// ---------------------------------------------------------------------------
+// Expression tree
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+enum Expr {
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+    Not(Box<Expr>),
+    Cond(Cond),
+}
+
+impl Expr {
+    fn visit(&self, f: &mut impl FnMut(&Cond)) {
+        match self {
+            Expr::And(items) | Expr::Or(items) => items.iter().for_each(|e| e.visit(f)),
+            Expr::Not(inner) => inner.visit(f),
+            Expr::Cond(cond) => f(cond),
+        }
+    }
+
+    fn compile(&self, params: &mut Vec<SqlValue>) -> String {
+        match self {
+            Expr::And(items) => join_compiled(items, " AND ", params),
+            Expr::Or(items) => join_compiled(items, " OR ", params),
+            Expr::Not(inner) => format!("NOT ({})", inner.compile(params)),
+            Expr::Cond(cond) => cond.compile(params),
+        }
+    }
+}
+
+fn join_compiled(items: &[Expr], sep: &str, params: &mut Vec<SqlValue>) -> String {
+    let parts: Vec<String> = items.iter().map(|e| e.compile(params)).collect();
+    format!("({})", parts.join(sep))
+}
+
+/// Comparison operator as written in the query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Colon,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Op {
+    /// SQL comparison operator, treating `:` as equality.
+    fn sql(self) -> &'static str {
+        match self {
+            Op::Colon | Op::Eq => "=",
+            Op::Ne => "!=",
+            Op::Lt => "<",
+            Op::Le => "<=",
+            Op::Gt => ">",
+            Op::Ge => ">=",
+        }
+    }
+
+    fn is_equality(self) -> bool {
+        matches!(self, Op::Colon | Op::Eq)
+    }
+}
+
+/// A single leaf condition over the `cards` view.
+#[derive(Debug, Clone)]
+enum Cond {
+    NameExact(String),
+    Contains { column: &'static str, value: String },
+    Colors { column: &'static str, op: Op, colors: Vec<&'static str> },
+    Multicolor { column: &'static str },
+    Numeric { expr: &'static str, op: Op, value: f64 },
+    Rarity { op: Op, rank: usize },
+    Legality { format: String, status: &'static str },
+    Set(String),
+    SetType(String),
+    Year { op: Op, year: i64 },
+    Keyword(String),
+    Flag { column: &'static str, negate: bool },
+    Language(String),
+    Layout(String),
+    Game(String),
+}
+
+impl Cond {
+    fn compile(&self, params: &mut Vec<SqlValue>) -> String {
+        match self {
+            Cond::NameExact(name) => {
+                params.push(name.into());
+                "cards.name = ?".to_string()
+            }
+            Cond::Contains { column, value } => {
+                // A plain substring test, so `%` and `_` in the query match
+                // themselves rather than acting as LIKE wildcards
+                params.push(value.into());
+                format!("contains(LOWER({}), LOWER(?))", column)
+            }
+            Cond::Colors { column, op, colors } => compile_colors(column, *op, colors, params),
+            Cond::Multicolor { column } => format!("len({}) > 1", column),
+            Cond::Numeric { expr, op, value } => {
+                params.push((*value).into());
+                format!("{} {} ?", expr, op.sql())
+            }
+            Cond::Rarity { op, rank } => {
+                if op.is_equality() {
+                    params.push(RARITIES[*rank].into());
+                    "cards.rarity = ?".to_string()
+                } else {
+                    // An index into RARITIES, so it always fits
+                    params.push(SqlValue::Int(*rank as i64));
+                    format!("({}) {} ?", RARITY_RANK_SQL, op.sql())
+                }
+            }
+            Cond::Legality { format, status } => {
+                params.push(format.into());
+                params.push((*status).into());
+                "EXISTS (SELECT 1 FROM card_legalities cl \
+                 WHERE cl.uuid = cards.uuid AND cl.format = ? AND cl.status = ?)"
+                    .to_string()
+            }
+            Cond::Set(code) => {
+                params.push(code.into());
+                "UPPER(cards.setCode) = UPPER(?)".to_string()
+            }
+            Cond::SetType(set_type) => {
+                params.push(set_type.into());
+                "cards.setCode IN (SELECT code FROM sets WHERE LOWER(type) = LOWER(?))".to_string()
+            }
+            Cond::Year { op, year } => {
+                params.push((*year).into());
+                format!(
+                    "cards.setCode IN (SELECT code FROM sets \
+                     WHERE year(CAST(releaseDate AS DATE)) {} ?)",
+                    op.sql()
+                )
+            }
+            Cond::Keyword(keyword) => {
+                params.push(keyword.into());
+                "list_contains(string_split(LOWER(array_to_string(cards.keywords, '|')), '|'), LOWER(?))"
+                    .to_string()
+            }
+            Cond::Flag { column, negate } => {
+                let cond = format!("COALESCE(cards.{}, false)", column);
+                if *negate {
+                    format!("NOT {}", cond)
+                } else {
+                    cond
+                }
+            }
+            Cond::Language(language) => {
+                params.push(language.into());
+                "LOWER(cards.language) = LOWER(?)".to_string()
+            }
+            Cond::Layout(layout) => {
+                params.push(layout.into());
+                "LOWER(cards.layout) = LOWER(?)".to_string()
+            }
+            Cond::Game(game) => {
+                params.push(game.to_lowercase().into());
+                "list_contains(cards.availability, ?)".to_string()
+            }
+        }
+    }
+}
+
+/// Compile a color comparison against a `VARCHAR[]` color column.
+///
+/// `:` means "at least these colors" for `colors` and "within these colors"
+/// for `colorIdentity`, matching Scryfall.
+fn compile_colors(
+    column: &str,
+    op: Op,
+    colors: &[&'static str],
+    params: &mut Vec<SqlValue>,
+) -> String {
+    let superset = |params: &mut Vec<SqlValue>| -> String {
+        if colors.is_empty() {
+            return "TRUE".to_string();
+        }
+        params.push(colors.into());
+        format!("list_has_all({}, ?)", column)
+    };
+    let subset = |params: &mut Vec<SqlValue>| -> String {
+        if colors.is_empty() {
+            return format!("len({}) = 0", column);
+        }
+        params.push(colors.into());
+        format!("list_has_all(?, {})", column)
+    };
+
+    let op = match op {
+        Op::Colon if column.ends_with("colorIdentity") => Op::Le,
+        Op::Colon => Op::Ge,
+        other => other,
+    };
+    let n = colors.len();
+    match op {
+        Op::Ge => superset(params),
+        Op::Le => subset(params),
+        Op::Eq | Op::Colon => format!("({} AND {})", superset(params), subset(params)),
+        Op::Ne => format!("NOT ({} AND {})", superset(params), subset(params)),
+        Op::Gt => format!("({} AND len({}) > {})", superset(params), column, n),
+        Op::Lt => format!("({} AND len({}) < {})", subset(params), column, n),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tokenizer
+// ---------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct Token {
+    pos: usize,
+    kind: TokenKind,
+}
+
+#[derive(Debug)]
+enum TokenKind {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Term(Cond),
+}
+
+fn tokenize(query: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = query.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        let kind = match c {
+            '(' => {
+                i += 1;
+                TokenKind::LParen
+            }
+            ')' => {
+                i += 1;
+                TokenKind::RParen
+            }
+            '-' if chars.get(i + 1).is_some_and(|n| !n.is_whitespace()) => {
+                i += 1;
+                TokenKind::Not
+            }
+            '!' => {
+                i += 1;
+                let (name, next) = read_value(&chars, i)?;
+                if name.is_empty() {
+                    return Err(syntax_error("expected a card name after '!'", i));
+                }
+                i = next;
+                TokenKind::Term(Cond::NameExact(name))
+            }
+            '"' => {
+                let (name, next) = read_value(&chars, i)?;
+                i = next;
+                TokenKind::Term(name_contains(name))
+            }
+            _ => {
+                let key_end = (i..chars.len())
+                    .find(|&j| !(chars[j].is_alphanumeric() || chars[j] == '_'))
+                    .unwrap_or(chars.len());
+                match read_op(&chars, key_end).filter(|_| key_end > i) {
+                    Some((op, op_end)) => {
+                        let key: String = chars[i..key_end].iter().collect();
+                        let (value, next) = read_value(&chars, op_end)?;
+                        if value.is_empty() {
+                            return Err(syntax_error(
+                                &format!("expected a value after '{}'", query_slice(&chars, i, op_end)),
+                                op_end,
+                            ));
+                        }
+                        let cond = build_cond(&key.to_lowercase(), i, op, key_end, value, op_end)?;
+                        i = next;
+                        TokenKind::Term(cond)
+                    }
+                    None => {
+                        let (word, next) = read_value(&chars, i)?;
+                        i = next;
+                        match word.to_lowercase().as_str() {
+                            "and" => TokenKind::And,
+                            "or" => TokenKind::Or,
+                            "not" => TokenKind::Not,
+                            _ => TokenKind::Term(name_contains(word)),
+                        }
+                    }
+                }
+            }
+        };
+        tokens.push(Token { pos: start, kind });
+    }
+
+    Ok(tokens)
+}
+
+fn query_slice(chars: &[char], start: usize, end: usize) -> String {
+    chars[start..end].iter().collect()
+}
+
+fn name_contains(value: String) -> Cond {
+    Cond::Contains {
+        column: "cards.name",
+        value,
+    }
+}
+
+/// Read an operator starting at `i`, returning it and the index after it.
+fn read_op(chars: &[char], i: usize) -> Option<(Op, usize)> {
+    let next = chars.get(i + 1).copied();
+    match (chars.get(i)?, next) {
+        ('!', Some('=')) => Some((Op::Ne, i + 2)),
+        ('<', Some('=')) => Some((Op::Le, i + 2)),
+        ('>', Some('=')) => Some((Op::Ge, i + 2)),
+        (':', _) => Some((Op::Colon, i + 1)),
+        ('=', _) => Some((Op::Eq, i + 1)),
+        ('<', _) => Some((Op::Lt, i + 1)),
+        ('>', _) => Some((Op::Gt, i + 1)),
+        _ => None,
+    }
+}
+
+/// Read a bare word or a double-quoted string starting at `i`.
+///
+/// Bare words end at whitespace or a parenthesis. Inside quotes, `\"` and
+/// `\\` are escapes. Returns the value and the index after it.
+fn read_value(chars: &[char], i: usize) -> Result<(String, usize)> {
+    if chars.get(i) != Some(&'"') {
+        let end = (i..chars.len())
+            .find(|&j| chars[j].is_whitespace() || chars[j] == '(' || chars[j] == ')')
+            .unwrap_or(chars.len());
+        return Ok((chars[i..end].iter().collect(), end));
+    }
+
+    let mut value = String::new();
+    let mut j = i + 1;
+    while j < chars.len() {
+        match chars[j] {
+            '"' => return Ok((value, j + 1)),
+            '\\' if j + 1 < chars.len() => {
+                value.push(chars[j + 1]);
+                j += 2;
+            }
+            c => {
+                value.push(c);
+                j += 1;
+            }
+        }
+    }
+    Err(syntax_error("unterminated quoted string", i))
+}
+
+// ---------------------------------------------------------------------------
+// Key/value interpretation
+// ---------------------------------------------------------------------------
+
+/// Build a leaf condition from `key op value`.
+///
+/// `key_pos`, `op_pos` and `value_pos` are used to point errors at the part
+/// of the term that is wrong.
+fn build_cond(
+    key: &str,
+    key_pos: usize,
+    op: Op,
+    op_pos: usize,
+    value: String,
+    value_pos: usize,
+) -> Result<Cond> {
+    let require_equality = || -> Result<()> {
+        if op.is_equality() {
+            Ok(())
+        } else {
+            Err(syntax_error(
+                &format!("operator '{}' is not supported for '{}'", op.sql(), key),
+                op_pos,
+            ))
+        }
+    };
+    let number = |value: &str| -> Result<f64> {
+        value
+            .parse::<f64>()
+            .map_err(|_| syntax_error(&format!("expected a number, got '{}'", value), value_pos))
+    };
+
+    let cond = match key {
+        "name" => {
+            require_equality()?;
+            name_contains(value)
+        }
+        "t" | "type" => {
+            require_equality()?;
+            Cond::Contains { column: "cards.type", value }
+        }
+        "o" | "oracle" => {
+            require_equality()?;
+            Cond::Contains { column: "cards.text", value }
+        }
+        "ft" | "flavor" => {
+            require_equality()?;
+            Cond::Contains { column: "cards.flavorText", value }
+        }
+        "a" | "artist" => {
+            require_equality()?;
+            Cond::Contains { column: "cards.artist", value }
+        }
+        "c" | "color" | "colors" => parse_colors("cards.colors", op, &value, value_pos)?,
+        "id" | "identity" | "ci" => parse_colors("cards.colorIdentity", op, &value, value_pos)?,
+        "mv" | "cmc" | "manavalue" => Cond::Numeric {
+            expr: "cards.manaValue",
+            op,
+            value: number(&value)?,
+        },
+        "pow" | "power" => Cond::Numeric {
+            expr: "TRY_CAST(cards.power AS DOUBLE)",
+            op,
+            value: number(&value)?,
+        },
+        "tou" | "toughness" => Cond::Numeric {
+            expr: "TRY_CAST(cards.toughness AS DOUBLE)",
+            op,
+            value: number(&value)?,
+        },
+        "r" | "rarity" => {
+            let lower = value.to_lowercase();
+            let rank = RARITIES
+                .iter()
+                .position(|r| *r == lower || (lower.len() == 1 && r.starts_with(&lower)))
+                .ok_or_else(|| {
+                    syntax_error(&format!("unknown rarity '{}'", value), value_pos)
+                })?;
+            Cond::Rarity { op, rank }
+        }
+        "f" | "format" | "legal" | "banned" | "restricted" => {
+            require_equality()?;
+            let status = match key {
+                "banned" => "Banned",
+                "restricted" => "Restricted",
+                _ => "Legal",
+            };
+            Cond::Legality {
+                format: value.to_lowercase(),
+                status,
+            }
+        }
+        "s" | "set" | "e" | "edition" => {
+            require_equality()?;
+            Cond::Set(value)
+        }
+        "st" | "settype" => {
+            require_equality()?;
+            Cond::SetType(value)
+        }
+        "year" => {
+            let year = value.parse::<i64>().map_err(|_| {
+                syntax_error(&format!("expected a year, got '{}'", value), value_pos)
+            })?;
+            Cond::Year { op, year }
+        }
+        "kw" | "keyword" => {
+            require_equality()?;
+            Cond::Keyword(value)
+        }
+        "is" | "not" => {
+            require_equality()?;
+            let column = flag_column(&value.to_lowercase()).ok_or_else(|| {
+                syntax_error(&format!("unknown flag '{}'", value), value_pos)
+            })?;
+            Cond::Flag {
+                column,
+                negate: key == "not",
+            }
+        }
+        "lang" | "language" => {
+            require_equality()?;
+            Cond::Language(value)
+        }
+        "layout" => {
+            require_equality()?;
+            Cond::Layout(value)
+        }
+        "game" => {
+            require_equality()?;
+            Cond::Game(value)
+        }
+        _ => {
+            return Err(syntax_error(
+                &format!("unknown search key '{}'", key),
+                key_pos,
+            ))
+        }
+    };
+    Ok(cond)
+}
+
+/// Parse a color value such as `rg`, `wubrg`, `c` (colorless), `m`
+/// (multicolored) or a color name such as `red`.
+fn parse_colors(column: &'static str, op: Op, value: &str, value_pos: usize) -> Result<Cond> {
+    let lower = value.to_lowercase();
+    match lower.as_str() {
+        "m" | "multicolor" => {
+            if !op.is_equality() {
+                return Err(syntax_error(
+                    "multicolor only supports ':' or '='",
+                    value_pos,
+                ));
+            }
+            return Ok(Cond::Multicolor { column });
+        }
+        "c" | "colorless" => {
+            return Ok(Cond::Colors {
+                column,
+                op: if op == Op::Colon { Op::Eq } else { op },
+                colors: Vec::new(),
+            })
+        }
+        _ => {}
+    }
+
+    let named = match lower.as_str() {
+        "white" => Some("w"),
+        "blue" => Some("u"),
+        "black" => Some("b"),
+        "red" => Some("r"),
+        "green" => Some("g"),
+        _ => None,
+    };
+    let letters = named.unwrap_or(lower.as_str());
+
+    let mut colors: Vec<&'static str> = Vec::new();
+    for (offset, ch) in letters.chars().enumerate() {
+        let color = match ch {
+            'w' => "W",
+            'u' => "U",
+            'b' => "B",
+            'r' => "R",
+            'g' => "G",
+            _ => {
+                let pos = if named.is_some() { value_pos } else { value_pos + offset };
+                return Err(syntax_error(&format!("invalid color '{}'", ch), pos));
+            }
+        };
+        if !colors.contains(&color) {
+            colors.push(color);
+        }
+    }
+    Ok(Cond::Colors { column, op, colors })
+}
+
+/// Map an `is:`/`not:` flag to its boolean column on `cards`.
+fn flag_column(flag: &str) -> Option<&'static str> {
+    Some(match flag {
+        "promo" => "isPromo",
+        "reprint" => "isReprint",
+        "reserved" => "isReserved",
+        "fullart" => "isFullArt",
+        "textless" => "isTextless",
+        "funny" => "isFunny",
+        "oversized" => "isOversized",
+        "digital" | "onlineonly" => "isOnlineOnly",
+        "timeshifted" => "isTimeshifted",
+        "spotlight" => "isStorySpotlight",
+        "alternative" => "isAlternative",
+        "rebalanced" => "isRebalanced",
+        "gamechanger" => "isGameChanger",
+        _ => return None,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Parser
+// ---------------------------------------------------------------------------
+
+struct Parser {
+    tokens: Peekable<IntoIter<Token>>,
+    /// Character length of the query, used as the position of "end of input".
+    end: usize,
+}
+
+impl Parser {
+    fn parse_or(&mut self) -> Result<Expr> {
+        let mut items = vec![self.parse_and()?];
+        while matches!(self.tokens.peek(), Some(Token { kind: TokenKind::Or, .. })) {
+            self.tokens.next();
+            items.push(self.parse_and()?);
+        }
+        Ok(if items.len() == 1 {
+            items.pop().unwrap()
+        } else {
+            Expr::Or(items)
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<Expr> {
+        let mut items = vec![self.parse_unary()?];
+        loop {
+            match self.tokens.peek() {
+                None
+                | Some(Token {
+                    kind: TokenKind::Or | TokenKind::RParen,
+                    ..
+                }) => break,
+                Some(Token {
+                    kind: TokenKind::And,
+                    ..
+                }) => {
+                    self.tokens.next();
+                    items.push(self.parse_unary()?);
+                }
+                Some(_) => items.push(self.parse_unary()?),
+            }
+        }
+        Ok(if items.len() == 1 {
+            items.pop().unwrap()
+        } else {
+            Expr::And(items)
+        })
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr> {
+        let Some(tok) = self.tokens.next() else {
+            return Err(syntax_error("unexpected end of query", self.end));
+        };
+        match tok.kind {
+            TokenKind::Not => Ok(Expr::Not(Box::new(self.parse_unary()?))),
+            TokenKind::LParen => {
+                let inner = self.parse_or()?;
+                match self.tokens.next() {
+                    Some(Token {
+                        kind: TokenKind::RParen,
+                        ..
+                    }) => Ok(inner),
+                    _ => Err(syntax_error("unclosed '('", tok.pos)),
+                }
+            }
+            TokenKind::Term(cond) => Ok(Expr::Cond(cond)),
+            TokenKind::RParen => Err(syntax_error("unexpected ')'", tok.pos)),
+            TokenKind::And => Err(syntax_error("unexpected 'and'", tok.pos)),
+            TokenKind::Or => Err(syntax_error("unexpected 'or'", tok.pos)),
+        }
+    }
+}
@@ -2,9 +2,11 @@
 
 use std::collections::HashMap;
 
+use serde::de::DeserializeOwned;
 use serde_json::Value;
 
 use crate::error::Result;
+use crate::models::{FinancialSummary, MtgSet};
 use crate::sql_builder::SqlBuilder;
 
 // ---------------------------------------------------------------------------
@@ -26,20 +28,58 @@ pub struct SearchSetsParams {
 // SetQuery
 // ---------------------------------------------------------------------------
 
+/// Aggregate price statistics for all cards in a set.
+const FINANCIAL_SUMMARY_SQL: &str = r#"
+    SELECT
+        COUNT(DISTINCT c.uuid) AS card_count,
+        COALESCE(SUM(p.price), 0) AS total_value,
+        COALESCE(AVG(p.price), 0) AS avg_value,
+        COALESCE(MIN(p.price), 0) AS min_value,
+        COALESCE(MAX(p.price), 0) AS max_value,
+        MAX(p.date) AS date
+    FROM cards c
+    JOIN all_prices_today p ON c.uuid = p.uuid
+    WHERE c.setCode = ?
+"#;
+
 /// Query interface for MTG sets backed by the `sets` parquet view.
 pub struct SetQuery<'a> {
-    conn: &'a crate::connection::Connection,
+    conn: crate::connection::ConnectionRef<'a>,
 }
 
 impl<'a> SetQuery<'a> {
     /// Create a new `SetQuery` bound to the given connection.
     pub fn new(conn: &'a crate::connection::Connection) -> Self {
-        Self { conn }
+        Self {
+            conn: crate::connection::ConnectionRef::Direct(conn),
+        }
+    }
+
+    /// Create a new `SetQuery` on the calling thread's SDK connection.
+    pub(crate) fn from_sdk(sdk: &'a crate::MtgjsonSdk) -> Self {
+        Self {
+            conn: crate::connection::ConnectionRef::Sdk(sdk),
+        }
+    }
+
+    /// Return a typed view of this query interface.
+    ///
+    /// The typed interface runs the same queries but deserializes each row
+    /// into [`MtgSet`] via
+    /// [`Connection::execute_into`](crate::connection::Connection::execute_into).
+    pub fn typed(&self) -> TypedSetQuery<'a> {
+        TypedSetQuery {
+            inner: SetQuery { conn: self.conn },
+        }
     }
 
     /// Get a single set by its code (case-insensitive -- uppercased before lookup).
     pub fn get(&self, code: &str) -> Result<Option<Value>> {
-        self.conn.ensure_views(&["sets"])?;
+        self.fetch_one(code)
+    }
+
+    fn fetch_one<T: DeserializeOwned>(&self, code: &str) -> Result<Option<T>> {
+        self.conn.get()?.ensure_views(&["sets"])?;
 
         let upper = code.to_uppercase();
         let (sql, params) = SqlBuilder::new("sets")
@@ -47,8 +87,8 @@ impl<'a> SetQuery<'a> {
             .limit(1)
             .build();
 
-        let rows = self.conn.execute(&sql, &params)?;
-        Ok(rows.into_iter().next().map(|r| serde_json::to_value(r).unwrap_or(Value::Null)))
+        let rows = self.conn.get()?.execute_into(&sql, &params)?;
+        Ok(rows.into_iter().next())
     }
 
     /// List all sets ordered by release date (descending).
@@ -62,7 +102,17 @@ impl<'a> SetQuery<'a> {
         limit: Option<usize>,
         offset: Option<usize>,
     ) -> Result<Vec<Value>> {
-        self.conn.ensure_views(&["sets"])?;
+        self.fetch_list(set_type, name, limit, offset)
+    }
+
+    fn fetch_list<T: DeserializeOwned>(
+        &self,
+        set_type: Option<&str>,
+        name: Option<&str>,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<T>> {
+        self.conn.get()?.ensure_views(&["sets"])?;
 
         let mut qb = SqlBuilder::new("sets");
         qb.order_by(&["releaseDate DESC"]);
@@ -83,8 +133,7 @@ impl<'a> SetQuery<'a> {
         }
 
         let (sql, params) = qb.build();
-        let rows = self.conn.execute(&sql, &params)?;
-        Ok(rows_to_values(rows))
+        self.conn.get()?.execute_into(&sql, &params)
     }
 
     /// Search sets using a combination of filters.
@@ -94,7 +143,11 @@ impl<'a> SetQuery<'a> {
     /// - `block`: exact match on `block`
     /// - `release_year`: matches the year portion of `releaseDate`
     pub fn search(&self, params: &SearchSetsParams) -> Result<Vec<Value>> {
-        self.conn.ensure_views(&["sets"])?;
+        self.fetch_search(params)
+    }
+
+    fn fetch_search<T: DeserializeOwned>(&self, params: &SearchSetsParams) -> Result<Vec<T>> {
+        self.conn.get()?.ensure_views(&["sets"])?;
 
         let mut qb = SqlBuilder::new("sets");
         qb.order_by(&["releaseDate DESC"]);
@@ -114,7 +167,7 @@ impl<'a> SetQuery<'a> {
         if let Some(year) = params.release_year {
             qb.where_clause(
                 "EXTRACT(YEAR FROM CAST(releaseDate AS DATE)) = ?",
-                &[&year.to_string()],
+                &[year.into()],
             );
         }
 
@@ -124,8 +177,7 @@ impl<'a> SetQuery<'a> {
         qb.offset(offset);
 
         let (sql, sql_params) = qb.build();
-        let rows = self.conn.execute(&sql, &sql_params)?;
-        Ok(rows_to_values(rows))
+        self.conn.get()?.execute_into(&sql, &sql_params)
     }
 
     /// Get a financial summary for the given set code.
@@ -134,24 +186,10 @@ impl<'a> SetQuery<'a> {
     /// Returns a map with keys: `card_count`, `total_value`, `avg_value`,
     /// `min_value`, `max_value`, `date`.
     pub fn get_financial_summary(&self, set_code: &str) -> Result<HashMap<String, Value>> {
-        self.conn.ensure_views(&["cards", "all_prices_today"])?;
+        self.conn.get()?.ensure_views(&["cards", "all_prices_today"])?;
 
         let upper = set_code.to_uppercase();
-
-        let sql = r#"
-            SELECT
-                COUNT(DISTINCT c.uuid) AS card_count,
-                COALESCE(SUM(p.price), 0) AS total_value,
-                COALESCE(AVG(p.price), 0) AS avg_value,
-                COALESCE(MIN(p.price), 0) AS min_value,
-                COALESCE(MAX(p.price), 0) AS max_value,
-                MAX(p.date) AS date
-            FROM cards c
-            JOIN all_prices_today p ON c.uuid = p.uuid
-            WHERE c.setCode = ?
-        "#;
-
-        let rows = self.conn.execute(sql, &[upper])?;
+        let rows = self.conn.get()?.execute(FINANCIAL_SUMMARY_SQL, &[upper.into()])?;
 
         if let Some(row) = rows.into_iter().next() {
             Ok(row)
@@ -162,7 +200,7 @@ impl<'a> SetQuery<'a> {
 
     /// Count all sets, optionally filtered by `set_type`.
     pub fn count(&self, set_type: Option<&str>) -> Result<i64> {
-        self.conn.ensure_views(&["sets"])?;
+        self.conn.get()?.ensure_views(&["sets"])?;
 
         let mut qb = SqlBuilder::new("sets");
         qb.select(&["COUNT(*) AS cnt"]);
@@ -172,7 +210,7 @@ impl<'a> SetQuery<'a> {
         }
 
         let (sql, params) = qb.build();
-        let rows = self.conn.execute(&sql, &params)?;
+        let rows = self.conn.get()?.execute(&sql, &params)?;
 
         let cnt = rows
             .first()
@@ -185,11 +223,47 @@ impl<'a> SetQuery<'a> {
 }
 
 // ---------------------------------------------------------------------------
-// Helpers
+// TypedSetQuery
 // ---------------------------------------------------------------------------
 
-fn rows_to_values(rows: Vec<HashMap<String, Value>>) -> Vec<Value> {
-    rows.into_iter()
-        .map(|r| serde_json::to_value(r).unwrap_or(Value::Null))
-        .collect()
+/// Typed counterpart of [`SetQuery`] returning [`MtgSet`] models.
+///
+/// Obtained via [`SetQuery::typed`].
+pub struct TypedSetQuery<'a> {
+    inner: SetQuery<'a>,
+}
+
+impl<'a> TypedSetQuery<'a> {
+    /// Get a single set by its code (case-insensitive).
+    pub fn get(&self, code: &str) -> Result<Option<MtgSet>> {
+        self.inner.fetch_one(code)
+    }
+
+    /// List all sets ordered by release date (descending).
+    pub fn list(
+        &self,
+        set_type: Option<&str>,
+        name: Option<&str>,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<MtgSet>> {
+        self.inner.fetch_list(set_type, name, limit, offset)
+    }
+
+    /// Search sets using a combination of filters.
+    pub fn search(&self, params: &SearchSetsParams) -> Result<Vec<MtgSet>> {
+        self.inner.fetch_search(params)
+    }
+
+    /// Get a financial summary for the given set code.
+    ///
+    /// Returns `None` if no priced cards exist for the set.
+    pub fn get_financial_summary(&self, set_code: &str) -> Result<Option<FinancialSummary>> {
+        self.inner.conn.get()?.ensure_views(&["cards", "all_prices_today"])?;
+
+        let upper = set_code.to_uppercase();
+        let sql = format!("{} HAVING COUNT(*) > 0", FINANCIAL_SUMMARY_SQL.trim_end());
+        let rows = self.inner.conn.get()?.execute_into(&sql, &[upper.into()])?;
+        Ok(rows.into_iter().next())
+    }
 }
@@ -13,48 +13,57 @@ use crate::sql_builder::SqlBuilder;
 
 /// Query interface for TCGplayer SKU data backed by a DuckDB view.
 pub struct SkuQuery<'a> {
-    conn: &'a crate::connection::Connection,
+    conn: crate::connection::ConnectionRef<'a>,
 }
 
 impl<'a> SkuQuery<'a> {
     /// Create a new `SkuQuery` bound to the given connection.
     pub fn new(conn: &'a crate::connection::Connection) -> Self {
-        Self { conn }
+        Self {
+            conn: crate::connection::ConnectionRef::Direct(conn),
+        }
+    }
+
+    /// Create a new `SkuQuery` on the calling thread's SDK connection.
+    pub(crate) fn from_sdk(sdk: &'a crate::MtgjsonSdk) -> Self {
+        Self {
+            conn: crate::connection::ConnectionRef::Sdk(sdk),
+        }
     }
 
     /// Get all SKUs for a card by its UUID.
     pub fn get(&self, uuid: &str) -> Result<Vec<Value>> {
-        self.conn.ensure_views(&["tcgplayer_skus"])?;
+        self.conn.get()?.ensure_views(&["tcgplayer_skus"])?;
 
         let (sql, params) = SqlBuilder::new("tcgplayer_skus")
             .where_eq("uuid", uuid)
             .build();
 
-        let rows = self.conn.execute(&sql, &params)?;
+        let rows = self.conn.get()?.execute(&sql, &params)?;
         Ok(rows_to_values(rows))
     }
 
     /// Find the card/SKU entry for a specific TCGplayer SKU ID.
     pub fn find_by_sku_id(&self, sku_id: &str) -> Result<Vec<Value>> {
-        self.conn.ensure_views(&["tcgplayer_skus"])?;
+        self.conn.get()?.ensure_views(&["tcgplayer_skus"])?;
 
         let (sql, params) = SqlBuilder::new("tcgplayer_skus")
             .where_eq("skuId", sku_id)
             .build();
 
-        let rows = self.conn.execute(&sql, &params)?;
+        let rows = self.conn.get()?.execute(&sql, &params)?;
         Ok(rows_to_values(rows))
     }
 
     /// Find all SKUs for a given TCGplayer product ID.
     pub fn find_by_product_id(&self, product_id: &str) -> Result<Vec<Value>> {
-        self.conn.ensure_views(&["tcgplayer_skus"])?;
+        self.conn.get()?.ensure_views(&["tcgplayer_skus"])?;
 
         let (sql, params) = SqlBuilder::new("tcgplayer_skus")
             .where_eq("productId", product_id)
             .build();
 
-        let rows = self.conn.execute(&sql, &params)?;
+        let rows = self.conn.get()?.execute(&sql, &params)?;
         Ok(rows_to_values(rows))
     }
 }
@@ -2,9 +2,11 @@
 
 use std::collections::HashMap;
 
+use serde::de::DeserializeOwned;
 use serde_json::Value;
 
 use crate::error::Result;
+use crate::models::CardToken;
 use crate::sql_builder::SqlBuilder;
 
 // ---------------------------------------------------------------------------
@@ -29,43 +31,78 @@ pub struct SearchTokensParams {
 
 /// Query interface for MTG tokens backed by the `tokens` parquet view.
 pub struct TokenQuery<'a> {
-    conn: &'a crate::connection::Connection,
+    conn: crate::connection::ConnectionRef<'a>,
 }
 
 impl<'a> TokenQuery<'a> {
     /// Create a new `TokenQuery` bound to the given connection.
     pub fn new(conn: &'a crate::connection::Connection) -> Self {
-        Self { conn }
+        Self {
+            conn: crate::connection::ConnectionRef::Direct(conn),
+        }
+    }
+
+    /// Create a new `TokenQuery` on the calling thread's SDK connection.
+    pub(crate) fn from_sdk(sdk: &'a crate::MtgjsonSdk) -> Self {
+        Self {
+            conn: crate::connection::ConnectionRef::Sdk(sdk),
+        }
+    }
+
+    /// Return a typed view of this query interface.
+    ///
+    /// The typed interface runs the same queries but deserializes each row
+    /// into [`CardToken`] via
+    /// [`Connection::execute_into`](crate::connection::Connection::execute_into).
+    pub fn typed(&self) -> TypedTokenQuery<'a> {
+        TypedTokenQuery {
+            inner: TokenQuery { conn: self.conn },
+        }
     }
 
     /// Retrieve a single token by its UUID.
     pub fn get_by_uuid(&self, uuid: &str) -> Result<Option<Value>> {
-        self.conn.ensure_views(&["tokens"])?;
+        self.fetch_by_uuid(uuid)
+    }
+
+    fn fetch_by_uuid<T: DeserializeOwned>(&self, uuid: &str) -> Result<Option<T>> {
+        self.conn.get()?.ensure_views(&["tokens"])?;
 
         let (sql, params) = SqlBuilder::new("tokens")
             .where_eq("uuid", uuid)
             .limit(1)
             .build();
 
-        let rows = self.conn.execute(&sql, &params)?;
-        Ok(rows.into_iter().next().map(|r| serde_json::to_value(r).unwrap_or(Value::Null)))
+        let rows = self.conn.get()?.execute_into(&sql, &params)?;
+        Ok(rows.into_iter().next())
     }
 
     /// Retrieve multiple tokens by their UUIDs.
     pub fn get_by_uuids(&self, uuids: &[&str]) -> Result<Vec<Value>> {
-        self.conn.ensure_views(&["tokens"])?;
+        self.fetch_by_uuids(uuids)
+    }
+
+    fn fetch_by_uuids<T: DeserializeOwned>(&self, uuids: &[&str]) -> Result<Vec<T>> {
+        self.conn.get()?.ensure_views(&["tokens"])?;
 
         let (sql, params) = SqlBuilder::new("tokens")
             .where_in("uuid", uuids)
             .build();
 
-        let rows = self.conn.execute(&sql, &params)?;
-        Ok(rows_to_values(rows))
+        self.conn.get()?.execute_into(&sql, &params)
     }
 
     /// Get all tokens with the given name, optionally filtered by set code.
     pub fn get_by_name(&self, name: &str, set_code: Option<&str>) -> Result<Vec<Value>> {
-        self.conn.ensure_views(&["tokens"])?;
+        self.fetch_by_name(name, set_code)
+    }
+
+    fn fetch_by_name<T: DeserializeOwned>(
+        &self,
+        name: &str,
+        set_code: Option<&str>,
+    ) -> Result<Vec<T>> {
+        self.conn.get()?.ensure_views(&["tokens"])?;
 
         let mut qb = SqlBuilder::new("tokens");
         qb.where_eq("name", name);
@@ -75,13 +112,16 @@ impl<'a> TokenQuery<'a> {
         }
 
         let (sql, params) = qb.build();
-        let rows = self.conn.execute(&sql, &params)?;
-        Ok(rows_to_values(rows))
+        self.conn.get()?.execute_into(&sql, &params)
     }
 
     /// Search tokens using a combination of filters.
     pub fn search(&self, params: &SearchTokensParams) -> Result<Vec<Value>> {
-        self.conn.ensure_views(&["tokens"])?;
+        self.fetch_search(params)
+    }
+
+    fn fetch_search<T: DeserializeOwned>(&self, params: &SearchTokensParams) -> Result<Vec<T>> {
+        self.conn.get()?.ensure_views(&["tokens"])?;
 
         let mut qb = SqlBuilder::new("tokens");
 
@@ -101,7 +141,7 @@ impl<'a> TokenQuery<'a> {
             for color in colors {
                 qb.where_clause(
                     "list_contains(tokens.colors, ?)",
-                    &[color.as_str()],
+                    &[color.into()],
                 );
             }
         }
@@ -120,25 +160,27 @@ impl<'a> TokenQuery<'a> {
         qb.offset(offset);
 
         let (sql, sql_params) = qb.build();
-        let rows = self.conn.execute(&sql, &sql_params)?;
-        Ok(rows_to_values(rows))
+        self.conn.get()?.execute_into(&sql, &sql_params)
     }
 
     /// Get all tokens for a specific set code.
     pub fn for_set(&self, set_code: &str) -> Result<Vec<Value>> {
-        self.conn.ensure_views(&["tokens"])?;
+        self.fetch_for_set(set_code)
+    }
+
+    fn fetch_for_set<T: DeserializeOwned>(&self, set_code: &str) -> Result<Vec<T>> {
+        self.conn.get()?.ensure_views(&["tokens"])?;
 
         let (sql, params) = SqlBuilder::new("tokens")
             .where_eq("setCode", set_code)
             .build();
 
-        let rows = self.conn.execute(&sql, &params)?;
-        Ok(rows_to_values(rows))
+        self.conn.get()?.execute_into(&sql, &params)
     }
 
     /// Count tokens, optionally filtered by the supplied column/value pairs.
     pub fn count(&self, filters: &HashMap<String, String>) -> Result<i64> {
-        self.conn.ensure_views(&["tokens"])?;
+        self.conn.get()?.ensure_views(&["tokens"])?;
 
         let mut qb = SqlBuilder::new("tokens");
         qb.select(&["COUNT(*) AS cnt"]);
@@ -146,9 +188,10 @@ impl<'a> TokenQuery<'a> {
         for (col, val) in filters {
             qb.where_eq(col, val);
         }
+        self.conn.get()?.check_columns(&["tokens"], qb.columns())?;
 
-        let (sql, params) = qb.build();
-        let rows = self.conn.execute(&sql, &params)?;
+        let (sql, params) = qb.try_build()?;
+        let rows = self.conn.get()?.execute(&sql, &params)?;
 
         let cnt = rows
             .first()
@@ -161,11 +204,39 @@ impl<'a> TokenQuery<'a> {
 }
 
 // ---------------------------------------------------------------------------
-// Helpers
+// TypedTokenQuery
 // ---------------------------------------------------------------------------
 
-fn rows_to_values(rows: Vec<HashMap<String, Value>>) -> Vec<Value> {
-    rows.into_iter()
-        .map(|r| serde_json::to_value(r).unwrap_or(Value::Null))
-        .collect()
+/// Typed counterpart of [`TokenQuery`] returning [`CardToken`] models.
+///
+/// Obtained via [`TokenQuery::typed`].
+pub struct TypedTokenQuery<'a> {
+    inner: TokenQuery<'a>,
+}
+
+impl<'a> TypedTokenQuery<'a> {
+    /// Retrieve a single token by its UUID.
+    pub fn get_by_uuid(&self, uuid: &str) -> Result<Option<CardToken>> {
+        self.inner.fetch_by_uuid(uuid)
+    }
+
+    /// Retrieve multiple tokens by their UUIDs.
+    pub fn get_by_uuids(&self, uuids: &[&str]) -> Result<Vec<CardToken>> {
+        self.inner.fetch_by_uuids(uuids)
+    }
+
+    /// Get all tokens with the given name, optionally filtered by set code.
+    pub fn get_by_name(&self, name: &str, set_code: Option<&str>) -> Result<Vec<CardToken>> {
+        self.inner.fetch_by_name(name, set_code)
+    }
+
+    /// Search tokens using a combination of filters.
+    pub fn search(&self, params: &SearchTokensParams) -> Result<Vec<CardToken>> {
+        self.inner.fetch_search(params)
+    }
+
+    /// Get all tokens for a specific set code.
+    pub fn for_set(&self, set_code: &str) -> Result<Vec<CardToken>> {
+        self.inner.fetch_for_set(set_code)
+    }
 }
@@ -1,7 +1,8 @@
 //! SQL builder with parameterized query construction.
 //!
 //! All user-supplied values go through DuckDB's parameter binding (`?` placeholders),
-//! never through string interpolation. Builder methods return `&mut Self` for chaining.
+//! never through string interpolation. Values are [`SqlValue`]s, so numbers,
+//! booleans and dates bind natively. Builder methods return `&mut Self` for chaining.
 //!
 //! # Example
 //!
@@ -10,25 +11,43 @@
 //! let (sql, params) = SqlBuilder::new("cards")
 //!     .where_eq("setCode", "MH3")
 //!     .where_like("name", "Lightning%")
+//!     .where_lte("manaValue", 2.5)
 //!     .order_by(&["name ASC"])
 //!     .limit(10)
 //!     .build();
 //! ```
+//!
+//! Nested conditions, CTEs and set operations are built from
+//! [`SqlExpr`] and other builders; see [`where_expr`](SqlBuilder::where_expr),
+//! [`with`](SqlBuilder::with) and [`union`](SqlBuilder::union).
+
+use crate::error::{MtgjsonError, Result};
+use crate::sql_expr::{is_column, quote_ident, SqlExpr};
+use crate::sql_value::SqlValue;
 
 /// Builds parameterized SQL queries safely.
 ///
 /// All user-supplied values go through DuckDB's parameter binding (`?` placeholders),
 /// never through string interpolation. Methods return `&mut Self` for chaining.
+#[derive(Debug, Clone)]
 pub struct SqlBuilder {
+    ctes: Vec<String>,
+    cte_params: Vec<SqlValue>,
     select_cols: Vec<String>,
     is_distinct: bool,
     from_table: String,
     joins: Vec<String>,
     where_clauses: Vec<String>,
-    params: Vec<String>,
+    params: Vec<SqlValue>,
+    columns: Vec<String>,
+    invalid_columns: Vec<String>,
     group_by_cols: Vec<String>,
     having_clauses: Vec<String>,
+    having_params: Vec<SqlValue>,
+    unions: Vec<String>,
+    union_params: Vec<SqlValue>,
     order_by_cols: Vec<String>,
+    order_params: Vec<SqlValue>,
     limit_val: Option<usize>,
     offset_val: Option<usize>,
 }
@@ -37,20 +56,40 @@ impl SqlBuilder {
     /// Create a builder targeting the given table or view.
     pub fn new(table: &str) -> Self {
         Self {
+            ctes: Vec::new(),
+            cte_params: Vec::new(),
             select_cols: vec!["*".to_string()],
             is_distinct: false,
             from_table: table.to_string(),
             joins: Vec::new(),
             where_clauses: Vec::new(),
             params: Vec::new(),
+            columns: Vec::new(),
+            invalid_columns: Vec::new(),
             group_by_cols: Vec::new(),
             having_clauses: Vec::new(),
+            having_params: Vec::new(),
+            unions: Vec::new(),
+            union_params: Vec::new(),
             order_by_cols: Vec::new(),
+            order_params: Vec::new(),
             limit_val: None,
             offset_val: None,
         }
     }
 
+    /// Add a common table expression: `WITH "{name}" AS (<query>)`.
+    ///
+    /// CTEs are emitted in the order added, so later ones may refer to
+    /// earlier ones.
+    pub fn with(&mut self, name: &str, query: &SqlBuilder) -> &mut Self {
+        let (sql, params) = query.build();
+        self.ctes
+            .push(format!("{} AS (\n{}\n)", quote_ident(name), sql));
+        self.cte_params.extend(params);
+        self
+    }
+
     /// Set the columns to select (replaces the default `*`).
     pub fn select(&mut self, cols: &[&str]) -> &mut Self {
         self.select_cols = cols.iter().map(|c| c.to_string()).collect();
@@ -76,9 +115,26 @@ impl SqlBuilder {
     ///
     /// The caller provides a condition using `?` for each parameter value.
     /// Parameters are appended in order.
-    pub fn where_clause(&mut self, condition: &str, params: &[&str]) -> &mut Self {
+    pub fn where_clause(&mut self, condition: &str, params: &[SqlValue]) -> &mut Self {
         self.where_clauses.push(condition.to_string());
-        self.params.extend(params.iter().map(|p| p.to_string()));
+        self.params.extend_from_slice(params);
+        self
+    }
+
+    /// Add a condition built from an [`SqlExpr`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use mtgjson_sdk::{SqlBuilder, SqlExpr};
+    /// let mut builder = SqlBuilder::new("cards");
+    /// builder.where_expr(!SqlExpr::col("setCode").in_list(["LEA", "LEB"]));
+    /// // -> WHERE NOT ("setCode" IN (?, ?))
+    /// ```
+    pub fn where_expr(&mut self, expr: SqlExpr) -> &mut Self {
+        let mut sql = String::new();
+        expr.render(&mut sql, &mut self.params);
+        self.where_clauses.push(sql);
         self
     }
 
@@ -86,16 +142,18 @@ impl SqlBuilder {
     ///
     /// Generates: `LOWER({column}) LIKE LOWER(?)`
     pub fn where_like(&mut self, column: &str, value: &str) -> &mut Self {
+        let column = self.column(column);
         self.where_clauses
             .push(format!("LOWER({}) LIKE LOWER(?)", column));
-        self.params.push(value.to_string());
+        self.params.push(value.into());
         self
     }
 
     /// Add an IN condition with parameterized values.
     ///
     /// Empty values list produces `FALSE`.
-    pub fn where_in(&mut self, column: &str, values: &[&str]) -> &mut Self {
+    pub fn where_in<V: Clone + Into<SqlValue>>(&mut self, column: &str, values: &[V]) -> &mut Self {
+        let column = self.column(column);
         if values.is_empty() {
             self.where_clauses.push("FALSE".to_string());
             return self;
@@ -103,31 +161,34 @@ impl SqlBuilder {
         let placeholders: Vec<&str> = values.iter().map(|_| "?").collect();
         self.where_clauses
             .push(format!("{} IN ({})", column, placeholders.join(", ")));
-        self.params.extend(values.iter().map(|v| v.to_string()));
+        self.params.extend(values.iter().cloned().map(Into::into));
         self
     }
 
     /// Add an equality condition: `{column} = ?`.
-    pub fn where_eq(&mut self, column: &str, value: &str) -> &mut Self {
+    pub fn where_eq(&mut self, column: &str, value: impl Into<SqlValue>) -> &mut Self {
+        let column = self.column(column);
         self.where_clauses
             .push(format!("{} = ?", column));
-        self.params.push(value.to_string());
+        self.params.push(value.into());
         self
     }
 
     /// Add a greater-than-or-equal condition: `{column} >= ?`.
-    pub fn where_gte(&mut self, column: &str, value: &str) -> &mut Self {
+    pub fn where_gte(&mut self, column: &str, value: impl Into<SqlValue>) -> &mut Self {
+        let column = self.column(column);
         self.where_clauses
             .push(format!("{} >= ?", column));
-        self.params.push(value.to_string());
+        self.params.push(value.into());
         self
     }
 
     /// Add a less-than-or-equal condition: `{column} <= ?`.
-    pub fn where_lte(&mut self, column: &str, value: &str) -> &mut Self {
+    pub fn where_lte(&mut self, column: &str, value: impl Into<SqlValue>) -> &mut Self {
+        let column = self.column(column);
         self.where_clauses
             .push(format!("{} <= ?", column));
-        self.params.push(value.to_string());
+        self.params.push(value.into());
         self
     }
 
@@ -135,9 +196,10 @@ impl SqlBuilder {
     ///
     /// Generates: `regexp_matches({column}, ?)`
     pub fn where_regex(&mut self, column: &str, pattern: &str) -> &mut Self {
+        let column = self.column(column);
         self.where_clauses
             .push(format!("regexp_matches({}, ?)", column));
-        self.params.push(pattern.to_string());
+        self.params.push(pattern.into());
         self
     }
 
@@ -147,18 +209,20 @@ impl SqlBuilder {
     ///
     /// The threshold must be between 0.0 and 1.0 (inclusive).
     pub fn where_fuzzy(&mut self, column: &str, value: &str, threshold: f64) -> &mut Self {
+        let column = self.column(column);
         self.where_clauses.push(format!(
             "jaro_winkler_similarity({}, ?) > {}",
             column, threshold
         ));
-        self.params.push(value.to_string());
+        self.params.push(value.into());
         self
     }
 
     /// Add OR-combined conditions.
     ///
     /// Each condition is a `(sql_fragment, param_value)` tuple where the fragment
-    /// uses `?` as a placeholder.
+    /// uses `?` as a placeholder. For nested groups, use
+    /// [`where_expr`](Self::where_expr) with [`SqlExpr::any`].
     ///
     /// # Example
     ///
@@ -168,14 +232,14 @@ impl SqlBuilder {
     /// builder.where_or(&[("name = ?", "Bolt"), ("name = ?", "Counter")]);
     /// // -> WHERE (name = ? OR name = ?)
     /// ```
-    pub fn where_or(&mut self, conditions: &[(&str, &str)]) -> &mut Self {
+    pub fn where_or<V: Clone + Into<SqlValue>>(&mut self, conditions: &[(&str, V)]) -> &mut Self {
         if conditions.is_empty() {
             return self;
         }
         let mut or_parts = Vec::with_capacity(conditions.len());
         for (cond, param) in conditions {
             or_parts.push(cond.to_string());
-            self.params.push(param.to_string());
+            self.params.push(param.clone().into());
         }
         self.where_clauses
             .push(format!("({})", or_parts.join(" OR ")));
@@ -190,9 +254,39 @@ impl SqlBuilder {
     }
 
     /// Add a HAVING condition with `?` placeholders.
-    pub fn having(&mut self, condition: &str, params: &[&str]) -> &mut Self {
+    pub fn having(&mut self, condition: &str, params: &[SqlValue]) -> &mut Self {
         self.having_clauses.push(condition.to_string());
-        self.params.extend(params.iter().map(|p| p.to_string()));
+        self.having_params.extend_from_slice(params);
+        self
+    }
+
+    /// Add a HAVING condition built from an [`SqlExpr`].
+    pub fn having_expr(&mut self, expr: SqlExpr) -> &mut Self {
+        let mut sql = String::new();
+        expr.render(&mut sql, &mut self.having_params);
+        self.having_clauses.push(sql);
+        self
+    }
+
+    /// Append `UNION <query>`, dropping duplicate rows.
+    ///
+    /// ORDER BY, LIMIT and OFFSET on this builder apply to the combined
+    /// result.
+    pub fn union(&mut self, query: &SqlBuilder) -> &mut Self {
+        self.set_operation("UNION", query)
+    }
+
+    /// Append `UNION ALL <query>`, keeping duplicate rows.
+    pub fn union_all(&mut self, query: &SqlBuilder) -> &mut Self {
+        self.set_operation("UNION ALL", query)
+    }
+
+    fn set_operation(&mut self, op: &str, query: &SqlBuilder) -> &mut Self {
+        let (sql, params) = query.build();
+        // Wrapped so the other query's own ORDER BY / LIMIT stay its own
+        self.unions
+            .push(format!("{}\nSELECT * FROM (\n{}\n)", op, sql));
+        self.union_params.extend(params);
         self
     }
 
@@ -203,6 +297,15 @@ impl SqlBuilder {
         self
     }
 
+    /// Add an ORDER BY term built from an [`SqlExpr`].
+    pub fn order_by_expr(&mut self, expr: SqlExpr, descending: bool) -> &mut Self {
+        let mut sql = String::new();
+        expr.render(&mut sql, &mut self.order_params);
+        sql.push_str(if descending { " DESC" } else { " ASC" });
+        self.order_by_cols.push(sql);
+        self
+    }
+
     /// Set the maximum number of rows to return.
     pub fn limit(&mut self, n: usize) -> &mut Self {
         self.limit_val = Some(n);
@@ -215,16 +318,60 @@ impl SqlBuilder {
         self
     }
 
+    /// Record a column argument of a `where_*` method and return it as it
+    /// goes into the SQL: as written if it is a plain identifier (optionally
+    /// `alias.`-qualified), quoted otherwise so it cannot inject SQL.
+    fn column(&mut self, column: &str) -> String {
+        self.columns.push(column.to_string());
+        if is_column(column) {
+            column.to_string()
+        } else {
+            self.invalid_columns.push(column.to_string());
+            quote_ident(column)
+        }
+    }
+
+    /// The column arguments given to the `where_*` methods so far.
+    ///
+    /// [`try_build`](Self::try_build) rejects any that are not plain
+    /// identifiers. When they come from user input, also check them against
+    /// the view schema with
+    /// [`Connection::check_columns`](crate::Connection::check_columns)
+    /// before running the query.
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// [`build`](Self::build), failing with [`MtgjsonError::InvalidArgument`]
+    /// if a `where_*` method was given a column that is not a plain
+    /// identifier or `alias.identifier`.
+    pub fn try_build(&self) -> Result<(String, Vec<SqlValue>)> {
+        if let Some(column) = self.invalid_columns.first() {
+            return Err(MtgjsonError::InvalidArgument(format!(
+                "Invalid column name: {:?}",
+                column
+            )));
+        }
+        Ok(self.build())
+    }
+
     /// Build the final SQL string and parameter list.
     ///
     /// Returns a tuple of `(sql_string, params_list)` ready for execution.
-    pub fn build(&self) -> (String, Vec<String>) {
+    /// Invalid `where_*` columns are quoted as identifiers, so the query
+    /// fails when run; use [`try_build`](Self::try_build) to reject them
+    /// up front.
+    pub fn build(&self) -> (String, Vec<SqlValue>) {
         let distinct = if self.is_distinct { "DISTINCT " } else { "" };
         let cols = self.select_cols.join(", ");
-        let mut parts = vec![
-            format!("SELECT {}{}", distinct, cols),
-            format!("FROM {}", self.from_table),
-        ];
+        let mut parts = Vec::new();
+
+        if !self.ctes.is_empty() {
+            parts.push(format!("WITH {}", self.ctes.join(",\n")));
+        }
+
+        parts.push(format!("SELECT {}{}", distinct, cols));
+        parts.push(format!("FROM {}", self.from_table));
 
         for j in &self.joins {
             parts.push(j.clone());
@@ -242,6 +389,8 @@ impl SqlBuilder {
             parts.push(format!("HAVING {}", self.having_clauses.join(" AND ")));
         }
 
+        parts.extend(self.unions.iter().cloned());
+
         if !self.order_by_cols.is_empty() {
             parts.push(format!("ORDER BY {}", self.order_by_cols.join(", ")));
         }
@@ -254,6 +403,18 @@ impl SqlBuilder {
             parts.push(format!("OFFSET {}", n));
         }
 
-        (parts.join("\n"), self.params.clone())
+        let params = [
+            &self.cte_params,
+            &self.params,
+            &self.having_params,
+            &self.union_params,
+            &self.order_params,
+        ]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect();
+
+        (parts.join("\n"), params)
     }
 }
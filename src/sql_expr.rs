@@ -0,0 +1,371 @@
+//! Composable SQL expressions.
+//!
+//! [`SqlExpr`] is a small expression tree for conditions that the flat
+//! `where_*` methods of [`SqlBuilder`] cannot express: nested AND/OR groups,
+//! `NOT`, `IS NULL`, `BETWEEN`, `IN` lists and subqueries, and `EXISTS`.
+//! Column names are quoted as identifiers and values are bound as `?`
+//! parameters, so neither can change the shape of the query.
+//!
+//! # Example
+//!
+//! ```rust
+//! use mtgjson_sdk::{SqlBuilder, SqlExpr};
+//!
+//! // rarity = 'mythic' AND (manaValue BETWEEN 2 AND 4 OR power IS NULL)
+//! let cond = SqlExpr::col("rarity").eq("mythic").and(
+//!     SqlExpr::col("manaValue")
+//!         .between(2, 4)
+//!         .or(SqlExpr::col("power").is_null()),
+//! );
+//! let (sql, params) = SqlBuilder::new("cards").where_expr(cond).build();
+//! ```
+
+use crate::sql_builder::SqlBuilder;
+use crate::sql_value::SqlValue;
+use std::ops::Not;
+
+/// A SQL expression with its bound parameters.
+///
+/// Build leaves with [`col`](Self::col), [`val`](Self::val),
+/// [`func`](Self::func) and [`raw`](Self::raw), then combine them with the
+/// comparison and boolean methods. `!expr` negates. Anything convertible to
+/// [`SqlValue`] converts to a bound value, so `col("x").eq(3)` binds `3`.
+#[derive(Debug, Clone)]
+pub struct SqlExpr {
+    node: Node,
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Column(String),
+    Value(SqlValue),
+    Raw { sql: String, params: Vec<SqlValue> },
+    Func { name: &'static str, args: Vec<SqlExpr> },
+    Binary { left: Box<SqlExpr>, op: &'static str, right: Box<SqlExpr> },
+    Group { op: &'static str, items: Vec<SqlExpr> },
+    Not(Box<SqlExpr>),
+    IsNull { expr: Box<SqlExpr>, negated: bool },
+    Between { expr: Box<SqlExpr>, low: Box<SqlExpr>, high: Box<SqlExpr> },
+    InList { expr: Box<SqlExpr>, items: Vec<SqlExpr> },
+    Subquery { sql: String, params: Vec<SqlValue> },
+    Exists { sql: String, params: Vec<SqlValue> },
+}
+
+impl SqlExpr {
+    fn new(node: Node) -> Self {
+        Self { node }
+    }
+
+    /// A column reference. Dots separate qualifiers (`"cards.name"`) and
+    /// each part is quoted, so the name cannot inject SQL.
+    pub fn col(name: &str) -> Self {
+        Self::new(Node::Column(
+            name.split('.').map(quote_ident).collect::<Vec<_>>().join("."),
+        ))
+    }
+
+    /// A value bound to a `?` placeholder.
+    pub fn val(value: impl Into<SqlValue>) -> Self {
+        Self::new(Node::Value(value.into()))
+    }
+
+    /// A trusted SQL fragment with `?` placeholders for `params`.
+    ///
+    /// The fragment is inserted as written; never build it from user input.
+    pub fn raw(sql: &str, params: &[SqlValue]) -> Self {
+        Self::new(Node::Raw {
+            sql: sql.to_string(),
+            params: params.to_vec(),
+        })
+    }
+
+    /// A function call, e.g. `func("jaro_winkler_similarity", [a, b])`.
+    pub fn func(name: &'static str, args: impl IntoIterator<Item = SqlExpr>) -> Self {
+        Self::new(Node::Func {
+            name,
+            args: args.into_iter().collect(),
+        })
+    }
+
+    /// All of `items` hold. An empty list is `TRUE`.
+    pub fn all(items: impl IntoIterator<Item = SqlExpr>) -> Self {
+        Self::group("AND", items)
+    }
+
+    /// Any of `items` holds. An empty list is `FALSE`.
+    pub fn any(items: impl IntoIterator<Item = SqlExpr>) -> Self {
+        Self::group("OR", items)
+    }
+
+    fn group(op: &'static str, items: impl IntoIterator<Item = SqlExpr>) -> Self {
+        let mut flat = Vec::new();
+        for item in items {
+            // Flatten `a AND (b AND c)` into one group
+            match item.node {
+                Node::Group { op: inner, items } if inner == op => flat.extend(items),
+                node => flat.push(Self::new(node)),
+            }
+        }
+        Self::new(Node::Group { op, items: flat })
+    }
+
+    /// `EXISTS (<query>)`.
+    pub fn exists(query: &SqlBuilder) -> Self {
+        let (sql, params) = query.build();
+        Self::new(Node::Exists { sql, params })
+    }
+
+    /// A scalar subquery, `(<query>)`.
+    pub fn subquery(query: &SqlBuilder) -> Self {
+        let (sql, params) = query.build();
+        Self::new(Node::Subquery { sql, params })
+    }
+
+    /// `self AND other`.
+    pub fn and(self, other: SqlExpr) -> Self {
+        Self::all([self, other])
+    }
+
+    /// `self OR other`.
+    pub fn or(self, other: SqlExpr) -> Self {
+        Self::any([self, other])
+    }
+
+    /// `self = other`.
+    pub fn eq(self, other: impl Into<SqlExpr>) -> Self {
+        self.binary("=", other)
+    }
+
+    /// `self != other`.
+    pub fn ne(self, other: impl Into<SqlExpr>) -> Self {
+        self.binary("!=", other)
+    }
+
+    /// `self < other`.
+    pub fn lt(self, other: impl Into<SqlExpr>) -> Self {
+        self.binary("<", other)
+    }
+
+    /// `self <= other`.
+    pub fn lte(self, other: impl Into<SqlExpr>) -> Self {
+        self.binary("<=", other)
+    }
+
+    /// `self > other`.
+    pub fn gt(self, other: impl Into<SqlExpr>) -> Self {
+        self.binary(">", other)
+    }
+
+    /// `self >= other`.
+    pub fn gte(self, other: impl Into<SqlExpr>) -> Self {
+        self.binary(">=", other)
+    }
+
+    /// Case-sensitive `self LIKE pattern`.
+    pub fn like(self, pattern: impl Into<SqlExpr>) -> Self {
+        self.binary("LIKE", pattern)
+    }
+
+    /// Case-insensitive `self ILIKE pattern`.
+    pub fn ilike(self, pattern: impl Into<SqlExpr>) -> Self {
+        self.binary("ILIKE", pattern)
+    }
+
+    /// `self IS NULL`.
+    pub fn is_null(self) -> Self {
+        Self::new(Node::IsNull {
+            expr: Box::new(self),
+            negated: false,
+        })
+    }
+
+    /// `self IS NOT NULL`.
+    pub fn is_not_null(self) -> Self {
+        Self::new(Node::IsNull {
+            expr: Box::new(self),
+            negated: true,
+        })
+    }
+
+    /// `self BETWEEN low AND high`, inclusive on both ends.
+    pub fn between(self, low: impl Into<SqlExpr>, high: impl Into<SqlExpr>) -> Self {
+        Self::new(Node::Between {
+            expr: Box::new(self),
+            low: Box::new(low.into()),
+            high: Box::new(high.into()),
+        })
+    }
+
+    /// `self IN (a, b, ...)`. An empty list is `FALSE`.
+    pub fn in_list<T: Into<SqlExpr>>(self, items: impl IntoIterator<Item = T>) -> Self {
+        Self::new(Node::InList {
+            expr: Box::new(self),
+            items: items.into_iter().map(Into::into).collect(),
+        })
+    }
+
+    /// `self IN (<query>)`.
+    pub fn in_query(self, query: &SqlBuilder) -> Self {
+        self.binary("IN", Self::subquery(query))
+    }
+
+    fn binary(self, op: &'static str, other: impl Into<SqlExpr>) -> Self {
+        Self::new(Node::Binary {
+            left: Box::new(self),
+            op,
+            right: Box::new(other.into()),
+        })
+    }
+
+    /// Render the expression as SQL with `?` placeholders and its params.
+    pub fn to_sql(&self) -> (String, Vec<SqlValue>) {
+        let mut sql = String::new();
+        let mut params = Vec::new();
+        self.render(&mut sql, &mut params);
+        (sql, params)
+    }
+
+    pub(crate) fn render(&self, sql: &mut String, params: &mut Vec<SqlValue>) {
+        match &self.node {
+            Node::Column(name) => sql.push_str(name),
+            Node::Value(value) => {
+                sql.push('?');
+                params.push(value.clone());
+            }
+            Node::Raw { sql: raw, params: bound } => {
+                sql.push_str(raw);
+                params.extend_from_slice(bound);
+            }
+            Node::Func { name, args } => {
+                sql.push_str(name);
+                sql.push('(');
+                render_list(args, sql, params);
+                sql.push(')');
+            }
+            Node::Binary { left, op, right } => {
+                left.render_operand(sql, params);
+                sql.push(' ');
+                sql.push_str(op);
+                sql.push(' ');
+                right.render_operand(sql, params);
+            }
+            Node::Group { op, items } => match items.as_slice() {
+                [] => sql.push_str(if *op == "AND" { "TRUE" } else { "FALSE" }),
+                [only] => only.render_operand(sql, params),
+                _ => {
+                    sql.push('(');
+                    for (i, item) in items.iter().enumerate() {
+                        if i > 0 {
+                            sql.push(' ');
+                            sql.push_str(op);
+                            sql.push(' ');
+                        }
+                        item.render_operand(sql, params);
+                    }
+                    sql.push(')');
+                }
+            },
+            Node::Not(inner) => {
+                sql.push_str("NOT ");
+                inner.render_operand(sql, params);
+            }
+            Node::IsNull { expr, negated } => {
+                expr.render_operand(sql, params);
+                sql.push_str(if *negated { " IS NOT NULL" } else { " IS NULL" });
+            }
+            Node::Between { expr, low, high } => {
+                expr.render_operand(sql, params);
+                sql.push_str(" BETWEEN ");
+                low.render_operand(sql, params);
+                sql.push_str(" AND ");
+                high.render_operand(sql, params);
+            }
+            Node::InList { expr, items } => {
+                if items.is_empty() {
+                    sql.push_str("FALSE");
+                    return;
+                }
+                expr.render_operand(sql, params);
+                sql.push_str(" IN (");
+                render_list(items, sql, params);
+                sql.push(')');
+            }
+            Node::Subquery { sql: query, params: bound } => {
+                sql.push_str("(\n");
+                sql.push_str(query);
+                sql.push_str("\n)");
+                params.extend_from_slice(bound);
+            }
+            Node::Exists { sql: query, params: bound } => {
+                sql.push_str("EXISTS (\n");
+                sql.push_str(query);
+                sql.push_str("\n)");
+                params.extend_from_slice(bound);
+            }
+        }
+    }
+
+    /// Render as the operand of an operator, parenthesized unless atomic.
+    fn render_operand(&self, sql: &mut String, params: &mut Vec<SqlValue>) {
+        let atomic = matches!(
+            self.node,
+            Node::Column(_)
+                | Node::Value(_)
+                | Node::Func { .. }
+                | Node::Group { .. }
+                | Node::Subquery { .. }
+                | Node::Exists { .. }
+        );
+        if atomic {
+            self.render(sql, params);
+        } else {
+            sql.push('(');
+            self.render(sql, params);
+            sql.push(')');
+        }
+    }
+}
+
+fn render_list(items: &[SqlExpr], sql: &mut String, params: &mut Vec<SqlValue>) {
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            sql.push_str(", ");
+        }
+        item.render(sql, params);
+    }
+}
+
+/// Whether `name` is a plain identifier (letters, digits, `_`, not starting
+/// with a digit) that is safe to interpolate unquoted.
+pub(crate) fn is_identifier(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Whether `name` is a plain identifier, optionally qualified by a table
+/// alias that is one too, e.g. `c.name`.
+pub(crate) fn is_column(name: &str) -> bool {
+    match name.split_once('.') {
+        Some((alias, column)) => is_identifier(alias) && is_identifier(column),
+        None => is_identifier(name),
+    }
+}
+
+/// Quote `name` as a SQL identifier, doubling any embedded quotes.
+pub(crate) fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+impl Not for SqlExpr {
+    type Output = SqlExpr;
+
+    fn not(self) -> SqlExpr {
+        SqlExpr::new(Node::Not(Box::new(self)))
+    }
+}
+
+impl<T: Into<SqlValue>> From<T> for SqlExpr {
+    fn from(value: T) -> Self {
+        SqlExpr::val(value)
+    }
+}
@@ -0,0 +1,245 @@
+//! Typed SQL parameter values.
+//!
+//! [`SqlValue`] is what [`SqlBuilder`](crate::SqlBuilder) collects and what
+//! [`Connection::execute`](crate::Connection::execute) and friends bind to
+//! `?` placeholders. Numbers, booleans and dates bind natively, so
+//! comparisons never depend on DuckDB casting strings:
+//!
+//! ```rust
+//! use mtgjson_sdk::SqlValue;
+//! let params: Vec<SqlValue> = vec![
+//!     2.5.into(),
+//!     true.into(),
+//!     SqlValue::Date("2024-01-01".into()),
+//!     vec!["R", "G"].into(),
+//! ];
+//! ```
+
+use crate::error::{MtgjsonError, Result};
+use duckdb::types::{ToSql, ToSqlOutput, Value, ValueRef};
+use std::borrow::Cow;
+
+/// A value bound to a `?` placeholder.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SqlValue {
+    /// SQL `NULL`.
+    Null,
+    /// A `BIGINT`.
+    Int(i64),
+    /// A `DOUBLE`.
+    Float(f64),
+    /// A `BOOLEAN`.
+    Bool(bool),
+    /// A `VARCHAR`.
+    Text(String),
+    /// A `DATE` in `YYYY-MM-DD` form. Its placeholder is rewritten to
+    /// `CAST(? AS DATE)`, so a malformed date fails the query.
+    Date(String),
+    /// A `LIST`. Its placeholder is rewritten to a list literal with one
+    /// placeholder per element, e.g. `[?, ?]`.
+    List(Vec<SqlValue>),
+}
+
+impl SqlValue {
+    /// Whether binding this value rewrites its placeholder.
+    fn expands(&self) -> bool {
+        matches!(self, SqlValue::Date(_) | SqlValue::List(_))
+    }
+
+    /// Append the SQL for this value's placeholder to `sql`, and the values
+    /// bound to it to `bound`.
+    fn expand<'a>(&'a self, sql: &mut String, bound: &mut Vec<&'a SqlValue>) {
+        match self {
+            SqlValue::Date(_) => {
+                sql.push_str("CAST(? AS DATE)");
+                bound.push(self);
+            }
+            SqlValue::List(items) => {
+                sql.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        sql.push_str(", ");
+                    }
+                    item.expand(sql, bound);
+                }
+                sql.push(']');
+            }
+            _ => {
+                sql.push('?');
+                bound.push(self);
+            }
+        }
+    }
+}
+
+impl ToSql for SqlValue {
+    fn to_sql(&self) -> duckdb::Result<ToSqlOutput<'_>> {
+        Ok(match self {
+            SqlValue::Null => ToSqlOutput::Owned(Value::Null),
+            SqlValue::Int(n) => ToSqlOutput::Owned(Value::BigInt(*n)),
+            SqlValue::Float(f) => ToSqlOutput::Owned(Value::Double(*f)),
+            SqlValue::Bool(b) => ToSqlOutput::Owned(Value::Boolean(*b)),
+            // Dates are bound as text inside the CAST added by `bind`
+            SqlValue::Text(s) | SqlValue::Date(s) => {
+                ToSqlOutput::Borrowed(ValueRef::Text(s.as_bytes()))
+            }
+            SqlValue::List(_) => {
+                return Err(duckdb::Error::ToSqlConversionFailure(
+                    "list parameters must be bound through Connection".into(),
+                ))
+            }
+        })
+    }
+}
+
+/// Prepare `sql` and `params` for binding: the placeholders of dates and
+/// lists are rewritten (see [`SqlValue::Date`] and [`SqlValue::List`]) and
+/// the values flattened to one per remaining placeholder.
+///
+/// Placeholders inside string literals, quoted identifiers and comments are
+/// left alone. Numbered placeholders (`?1`, `$1`) cannot be combined with
+/// date or list parameters.
+pub(crate) fn bind<'a>(
+    sql: &'a str,
+    params: &'a [SqlValue],
+) -> Result<(Cow<'a, str>, Vec<&'a SqlValue>)> {
+    if !params.iter().any(SqlValue::expands) {
+        return Ok((Cow::Borrowed(sql), params.iter().collect()));
+    }
+
+    let mut out = String::with_capacity(sql.len());
+    let mut bound = Vec::with_capacity(params.len());
+    let mut next = params.iter();
+    let mut chars = sql.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' | '"' => {
+                out.push(c);
+                for q in chars.by_ref() {
+                    out.push(q);
+                    // A doubled quote reopens the literal on the next pass
+                    if q == c {
+                        break;
+                    }
+                }
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                out.push(c);
+                for q in chars.by_ref() {
+                    out.push(q);
+                    if q == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                out.push(c);
+                let mut prev = '\0';
+                for q in chars.by_ref() {
+                    out.push(q);
+                    if prev == '*' && q == '/' {
+                        break;
+                    }
+                    prev = q;
+                }
+            }
+            '$' if chars.peek().is_some_and(char::is_ascii_digit) => {
+                return Err(numbered_placeholder());
+            }
+            '?' => {
+                if chars.peek().is_some_and(char::is_ascii_digit) {
+                    return Err(numbered_placeholder());
+                }
+                match next.next() {
+                    Some(value) => value.expand(&mut out, &mut bound),
+                    None => out.push('?'),
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    // Surplus values are passed on so DuckDB reports the count mismatch
+    bound.extend(next);
+    Ok((Cow::Owned(out), bound))
+}
+
+fn numbered_placeholder() -> MtgjsonError {
+    MtgjsonError::InvalidArgument(
+        "Date and list parameters need plain `?` placeholders".into(),
+    )
+}
+
+/// Text values compare equal to the same string.
+impl PartialEq<&str> for SqlValue {
+    fn eq(&self, other: &&str) -> bool {
+        matches!(self, SqlValue::Text(s) if s == other)
+    }
+}
+
+impl From<&str> for SqlValue {
+    fn from(s: &str) -> Self {
+        SqlValue::Text(s.to_string())
+    }
+}
+
+impl From<String> for SqlValue {
+    fn from(s: String) -> Self {
+        SqlValue::Text(s)
+    }
+}
+
+impl From<&String> for SqlValue {
+    fn from(s: &String) -> Self {
+        SqlValue::Text(s.clone())
+    }
+}
+
+impl From<bool> for SqlValue {
+    fn from(b: bool) -> Self {
+        SqlValue::Bool(b)
+    }
+}
+
+impl From<f64> for SqlValue {
+    fn from(f: f64) -> Self {
+        SqlValue::Float(f)
+    }
+}
+
+impl From<f32> for SqlValue {
+    fn from(f: f32) -> Self {
+        SqlValue::Float(f as f64)
+    }
+}
+
+macro_rules! from_int {
+    ($($t:ty),*) => {$(
+        impl From<$t> for SqlValue {
+            fn from(n: $t) -> Self {
+                SqlValue::Int(n as i64)
+            }
+        }
+    )*};
+}
+
+// `u64` and `usize` can exceed `i64::MAX`, so callers convert those
+// explicitly rather than have large values wrap to negative numbers
+from_int!(i8, i16, i32, i64, u8, u16, u32);
+
+impl<T: Into<SqlValue>> From<Option<T>> for SqlValue {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(SqlValue::Null, Into::into)
+    }
+}
+
+impl<T: Into<SqlValue>> From<Vec<T>> for SqlValue {
+    fn from(items: Vec<T>) -> Self {
+        SqlValue::List(items.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T: Clone + Into<SqlValue>> From<&[T]> for SqlValue {
+    fn from(items: &[T]) -> Self {
+        SqlValue::List(items.iter().cloned().map(Into::into).collect())
+    }
+}
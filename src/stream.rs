@@ -0,0 +1,250 @@
+//! Lazy row iteration over DuckDB's streaming result API.
+//!
+//! [`Connection::execute`](crate::connection::Connection::execute) collects every
+//! row before returning. The iterators here instead pull one DuckDB data chunk
+//! (a few thousand rows, as an Arrow batch) at a time, so memory use is bounded
+//! by the chunk size rather than the result size. Values are converted to
+//! `serde_json::Value` with the same conventions as `execute`: lists become
+//! arrays, structs and maps become objects, dates and timestamps become
+//! ISO-8601 strings and decimals become floats.
+
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use duckdb::arrow::array::{Array, ArrayRef, AsArray, StructArray};
+use duckdb::arrow::datatypes::{
+    DataType, Date32Type, Date64Type, Decimal128Type, Float16Type, Float32Type, Float64Type,
+    Int16Type, Int32Type, Int64Type, Int8Type, SchemaRef, TimeUnit, TimestampMicrosecondType,
+    TimestampMillisecondType, TimestampNanosecondType, TimestampSecondType, UInt16Type,
+    UInt32Type, UInt64Type, UInt8Type,
+};
+use duckdb::arrow::util::display::{ArrayFormatter, FormatOptions};
+use duckdb::Statement;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::connection::{format_blob, format_date, format_timestamp};
+use crate::error::Result;
+
+// ---------------------------------------------------------------------------
+// RowStream
+// ---------------------------------------------------------------------------
+
+/// Lazy iterator over query rows as `HashMap<String, serde_json::Value>`.
+///
+/// Created by [`Connection::stream`](crate::connection::Connection::stream).
+/// The underlying prepared statement stays open until the iterator is
+/// dropped. Running another query on the same connection ends the stream
+/// early, so finish iterating before issuing further queries.
+pub struct RowStream<'a> {
+    stmt: Statement<'a>,
+    schema: SchemaRef,
+    columns: Vec<String>,
+    batch: Option<StructArray>,
+    row: usize,
+}
+
+impl<'a> RowStream<'a> {
+    /// Wrap a statement that has already been executed in streaming mode.
+    pub(crate) fn new(stmt: Statement<'a>, schema: SchemaRef) -> Self {
+        let columns = schema.fields().iter().map(|f| f.name().clone()).collect();
+        Self {
+            stmt,
+            schema,
+            columns,
+            batch: None,
+            row: 0,
+        }
+    }
+
+    /// Names of the result columns, in query order.
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+}
+
+impl Iterator for RowStream<'_> {
+    type Item = Result<HashMap<String, Value>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(batch) = &self.batch {
+                if self.row < batch.len() {
+                    let row = self.row;
+                    self.row += 1;
+                    let map = self
+                        .columns
+                        .iter()
+                        .zip(batch.columns())
+                        .map(|(name, column)| (name.clone(), arrow_value(column, row)))
+                        .collect();
+                    return Some(Ok(map));
+                }
+            }
+            // Current chunk exhausted (or none yet): fetch the next one
+            self.batch = Some(self.stmt.stream_step(self.schema.clone())?);
+            self.row = 0;
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// TypedRowStream
+// ---------------------------------------------------------------------------
+
+/// Lazy iterator deserializing each row into `T`.
+///
+/// Created by [`Connection::execute_iter`](crate::connection::Connection::execute_iter)
+/// and the `scan` methods on the query interfaces.
+pub struct TypedRowStream<'a, T> {
+    rows: RowStream<'a>,
+    _marker: PhantomData<T>,
+}
+
+impl<'a, T: DeserializeOwned> TypedRowStream<'a, T> {
+    pub(crate) fn new(rows: RowStream<'a>) -> Self {
+        Self {
+            rows,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Names of the result columns, in query order.
+    pub fn columns(&self) -> &[String] {
+        self.rows.columns()
+    }
+}
+
+impl<T: DeserializeOwned> Iterator for TypedRowStream<'_, T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let row = match self.rows.next()? {
+            Ok(row) => row,
+            Err(e) => return Some(Err(e)),
+        };
+        let value = Value::Object(row.into_iter().collect());
+        Some(serde_json::from_value(value).map_err(Into::into))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Arrow -> JSON conversion
+// ---------------------------------------------------------------------------
+
+/// Convert the value at `row` of an Arrow column to a `serde_json::Value`.
+fn arrow_value(column: &ArrayRef, row: usize) -> Value {
+    array_value(column.as_ref(), row)
+}
+
+fn array_value(column: &dyn Array, row: usize) -> Value {
+    if column.is_null(row) {
+        return Value::Null;
+    }
+    match column.data_type() {
+        DataType::Null => Value::Null,
+        DataType::Boolean => Value::Bool(column.as_boolean().value(row)),
+        DataType::Int8 => column.as_primitive::<Int8Type>().value(row).into(),
+        DataType::Int16 => column.as_primitive::<Int16Type>().value(row).into(),
+        DataType::Int32 => column.as_primitive::<Int32Type>().value(row).into(),
+        DataType::Int64 => column.as_primitive::<Int64Type>().value(row).into(),
+        DataType::UInt8 => column.as_primitive::<UInt8Type>().value(row).into(),
+        DataType::UInt16 => column.as_primitive::<UInt16Type>().value(row).into(),
+        DataType::UInt32 => column.as_primitive::<UInt32Type>().value(row).into(),
+        DataType::UInt64 => column.as_primitive::<UInt64Type>().value(row).into(),
+        DataType::Float16 => float(column.as_primitive::<Float16Type>().value(row).to_f64()),
+        DataType::Float32 => float(column.as_primitive::<Float32Type>().value(row) as f64),
+        DataType::Float64 => float(column.as_primitive::<Float64Type>().value(row)),
+        DataType::Decimal128(_, scale) => {
+            let raw = column.as_primitive::<Decimal128Type>().value(row);
+            if *scale == 0 {
+                // HUGEINT arrives as DECIMAL(38, 0)
+                i64::try_from(raw)
+                    .map(Value::from)
+                    .unwrap_or_else(|_| Value::String(raw.to_string()))
+            } else {
+                float(raw as f64 / 10f64.powi(*scale as i32))
+            }
+        }
+        DataType::Utf8 => Value::String(column.as_string::<i32>().value(row).to_string()),
+        DataType::LargeUtf8 => Value::String(column.as_string::<i64>().value(row).to_string()),
+        DataType::Utf8View => Value::String(column.as_string_view().value(row).to_string()),
+        DataType::Binary => Value::String(format_blob(column.as_binary::<i32>().value(row))),
+        DataType::LargeBinary => Value::String(format_blob(column.as_binary::<i64>().value(row))),
+        DataType::FixedSizeBinary(_) => {
+            Value::String(format_blob(column.as_fixed_size_binary().value(row)))
+        }
+        DataType::Date32 => {
+            let days = column.as_primitive::<Date32Type>().value(row) as i64;
+            Value::String(format_date(days))
+        }
+        DataType::Date64 => {
+            let millis = column.as_primitive::<Date64Type>().value(row);
+            Value::String(format_date(millis.div_euclid(86_400_000)))
+        }
+        DataType::Timestamp(unit, _) => {
+            let micros = match unit {
+                TimeUnit::Second => column
+                    .as_primitive::<TimestampSecondType>()
+                    .value(row)
+                    .saturating_mul(1_000_000),
+                TimeUnit::Millisecond => column
+                    .as_primitive::<TimestampMillisecondType>()
+                    .value(row)
+                    .saturating_mul(1_000),
+                TimeUnit::Microsecond => column.as_primitive::<TimestampMicrosecondType>().value(row),
+                TimeUnit::Nanosecond => {
+                    column.as_primitive::<TimestampNanosecondType>().value(row) / 1_000
+                }
+            };
+            Value::String(format_timestamp(micros))
+        }
+        DataType::List(_) => list_value(column.as_list::<i32>().value(row).as_ref()),
+        DataType::LargeList(_) => list_value(column.as_list::<i64>().value(row).as_ref()),
+        DataType::FixedSizeList(_, _) => {
+            list_value(column.as_fixed_size_list().value(row).as_ref())
+        }
+        DataType::Struct(fields) => {
+            let array = column.as_struct();
+            Value::Object(
+                fields
+                    .iter()
+                    .zip(array.columns())
+                    .map(|(field, child)| (field.name().clone(), array_value(child.as_ref(), row)))
+                    .collect(),
+            )
+        }
+        DataType::Map(_, _) => {
+            let entries = column.as_map().value(row);
+            let keys = entries.column(0);
+            let values = entries.column(1);
+            Value::Object(
+                (0..entries.len())
+                    .map(|i| {
+                        let key = match array_value(keys.as_ref(), i) {
+                            Value::String(s) => s,
+                            other => other.to_string(),
+                        };
+                        (key, array_value(values.as_ref(), i))
+                    })
+                    .collect(),
+            )
+        }
+        // ENUM columns arrive dictionary-encoded; display gives the label
+        DataType::Dictionary(_, _) => ArrayFormatter::try_new(column, &FormatOptions::default())
+            .map(|f| Value::String(f.value(row).to_string()))
+            .unwrap_or(Value::Null),
+        // Time-of-day and interval values have no natural JSON representation
+        _ => Value::Null,
+    }
+}
+
+fn list_value(items: &dyn Array) -> Value {
+    Value::Array((0..items.len()).map(|i| array_value(items, i)).collect())
+}
+
+fn float(f: f64) -> Value {
+    serde_json::Number::from_f64(f)
+        .map(Value::Number)
+        .unwrap_or(Value::Null)
+}
@@ -0,0 +1,497 @@
+//! Deck legality validation per format.
+//!
+//! [`DeckValidator`] checks a whole deck against a format's construction
+//! rules, not just per-card legality:
+//!
+//! - Card legality from `card_legalities` (banned, not legal, and at most one
+//!   copy of a restricted card).
+//! - Deck size: at least 60 main-deck cards in constructed formats, an exact
+//!   size (commander included) in commander formats.
+//! - Copy limits: 4 per name in constructed, 1 in singleton formats, counted
+//!   across all boards. Basic lands and cards with `hasAlternativeDeckLimit`
+//!   are exempt.
+//! - Sideboard size (15 in constructed, including a companion; none in
+//!   commander formats) and at most one companion.
+//! - Commander formats: one or two commanders, each able to lead the deck per
+//!   `leadershipSkills`, and every card within the commanders' combined
+//!   `colorIdentity`.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::error::Result;
+use crate::formats::{DeckEntry, Section};
+use crate::models::Deck;
+use crate::queries::cards::CardQuery;
+use crate::sql_builder::SqlBuilder;
+
+// ---------------------------------------------------------------------------
+// Format rules
+// ---------------------------------------------------------------------------
+
+/// Deck construction rules for one format.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormatRules {
+    /// Minimum main-deck size (commanders included in commander formats).
+    pub min_deck_size: i64,
+    /// Exact deck size, for formats that require one.
+    pub exact_deck_size: Option<i64>,
+    pub max_sideboard: i64,
+    /// Copies allowed per card name, across all boards.
+    pub max_copies: i64,
+    /// Whether the deck is led by one or two commanders.
+    pub commander: bool,
+    /// `leadershipSkills` key commanders must have, if checked.
+    pub leadership_skill: Option<&'static str>,
+}
+
+impl FormatRules {
+    /// Rules for a format name as used in `card_legalities` (lowercase).
+    ///
+    /// Unknown formats get the constructed defaults (60 cards, 15-card
+    /// sideboard, 4 copies).
+    pub fn for_format(format: &str) -> Self {
+        let constructed = FormatRules {
+            min_deck_size: 60,
+            exact_deck_size: None,
+            max_sideboard: 15,
+            max_copies: 4,
+            commander: false,
+            leadership_skill: None,
+        };
+        let commander = |size: i64, skill: Option<&'static str>| FormatRules {
+            min_deck_size: size,
+            exact_deck_size: Some(size),
+            max_sideboard: 0,
+            max_copies: 1,
+            commander: true,
+            leadership_skill: skill,
+        };
+        match format {
+            "commander" | "duel" | "predh" => commander(100, Some("commander")),
+            "paupercommander" => commander(100, None),
+            "brawl" => commander(100, Some("brawl")),
+            "standardbrawl" => commander(60, Some("brawl")),
+            "oathbreaker" => commander(60, Some("oathbreaker")),
+            "gladiator" => FormatRules {
+                min_deck_size: 100,
+                exact_deck_size: Some(100),
+                max_sideboard: 0,
+                max_copies: 1,
+                commander: false,
+                leadership_skill: None,
+            },
+            _ => constructed,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Report
+// ---------------------------------------------------------------------------
+
+/// One rule a deck breaks.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+    /// An entry's UUID or name matches no card.
+    UnknownCard { card: String },
+    /// The card is banned or not legal in the format.
+    NotLegal { name: String, status: String },
+    /// More than one copy of a restricted card.
+    Restricted { name: String, count: i64 },
+    /// More copies of a card than the format allows.
+    TooManyCopies { name: String, count: i64, max: i64 },
+    DeckTooSmall { count: i64, min: i64 },
+    WrongDeckSize { count: i64, expected: i64 },
+    SideboardTooLarge { count: i64, max: i64 },
+    TooManyCompanions { count: i64 },
+    /// Commander formats need one or two commanders.
+    CommanderCount { count: i64 },
+    /// The card cannot be this format's commander.
+    InvalidCommander { name: String },
+    /// The card has colors outside the commanders' color identity.
+    ColorIdentity { name: String, colors: Vec<String> },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::UnknownCard { card } => write!(f, "unknown card '{}'", card),
+            Violation::NotLegal { name, status } => write!(f, "{} is {}", name, status),
+            Violation::Restricted { name, count } => {
+                write!(f, "{} is restricted but the deck has {} copies", name, count)
+            }
+            Violation::TooManyCopies { name, count, max } => {
+                write!(f, "{} copies of {} (max {})", count, name, max)
+            }
+            Violation::DeckTooSmall { count, min } => {
+                write!(f, "deck has {} cards (min {})", count, min)
+            }
+            Violation::WrongDeckSize { count, expected } => {
+                write!(f, "deck has {} cards (must be exactly {})", count, expected)
+            }
+            Violation::SideboardTooLarge { count, max } => {
+                write!(f, "sideboard has {} cards (max {})", count, max)
+            }
+            Violation::TooManyCompanions { count } => {
+                write!(f, "{} companions (max 1)", count)
+            }
+            Violation::CommanderCount { count } => {
+                write!(f, "{} commanders (must be 1 or 2)", count)
+            }
+            Violation::InvalidCommander { name } => write!(f, "{} cannot be a commander", name),
+            Violation::ColorIdentity { name, colors } => write!(
+                f,
+                "{} has colors {} outside the commander's color identity",
+                name,
+                colors.join("")
+            ),
+        }
+    }
+}
+
+/// Result of validating a deck.
+#[derive(Debug, Clone)]
+pub struct ValidationReport {
+    pub format: String,
+    /// Main-deck card count, commanders included in commander formats.
+    pub deck_size: i64,
+    /// Sideboard card count, companion included.
+    pub sideboard_size: i64,
+    pub violations: Vec<Violation>,
+}
+
+impl ValidationReport {
+    /// `true` if the deck breaks no rules.
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// DeckValidator
+// ---------------------------------------------------------------------------
+
+/// Card facts needed for validation.
+struct CardInfo {
+    name: String,
+    is_basic: bool,
+    alternative_limit: bool,
+    color_identity: Vec<String>,
+    leadership: Value,
+}
+
+/// Per-entry UUIDs (`None` if unresolved) and card facts keyed by UUID.
+type Resolved = (Vec<Option<String>>, HashMap<String, CardInfo>);
+
+/// Validates decks against format construction rules.
+pub struct DeckValidator<'a> {
+    conn: crate::connection::ConnectionRef<'a>,
+}
+
+impl<'a> DeckValidator<'a> {
+    /// Create a new `DeckValidator` bound to the given connection.
+    pub fn new(conn: &'a crate::connection::Connection) -> Self {
+        Self {
+            conn: crate::connection::ConnectionRef::Direct(conn),
+        }
+    }
+
+    /// Create a new `DeckValidator` on the calling thread's SDK connection.
+    pub(crate) fn from_sdk(sdk: &'a crate::MtgjsonSdk) -> Self {
+        Self {
+            conn: crate::connection::ConnectionRef::Sdk(sdk),
+        }
+    }
+
+    /// Validate deck entries against `format`'s rules.
+    ///
+    /// Each entry is identified by `uuid` if set, otherwise by exact `name`.
+    /// `Section::Companion` entries count towards the sideboard in
+    /// constructed formats.
+    pub fn validate(&self, entries: &[DeckEntry], format: &str) -> Result<ValidationReport> {
+        self.validate_with_rules(entries, format, &FormatRules::for_format(&format.to_lowercase()))
+    }
+
+    /// Validate a full [`Deck`] from [`DeckQuery::get`](crate::queries::decks::DeckQuery::get).
+    pub fn validate_deck(&self, deck: &Deck, format: &str) -> Result<ValidationReport> {
+        self.validate(&DeckEntry::from_deck(deck), format)
+    }
+
+    /// Validate against explicit rules, for formats not covered by
+    /// [`FormatRules::for_format`] or house rules.
+    pub fn validate_with_rules(
+        &self,
+        entries: &[DeckEntry],
+        format: &str,
+        rules: &FormatRules,
+    ) -> Result<ValidationReport> {
+        let format = format.to_lowercase();
+        let mut violations = Vec::new();
+
+        // -- Resolve entries to cards ---------------------------------------
+        let (uuids, cards) = self.resolve(entries)?;
+        let resolved: Vec<(&DeckEntry, &str)> = entries
+            .iter()
+            .zip(&uuids)
+            .filter_map(|(e, u)| match u {
+                Some(uuid) => Some((e, uuid.as_str())),
+                None => {
+                    violations.push(Violation::UnknownCard {
+                        card: e.uuid.clone().unwrap_or_else(|| e.name.clone()),
+                    });
+                    None
+                }
+            })
+            .collect();
+
+        let count_in = |sections: &[Section]| -> i64 {
+            entries
+                .iter()
+                .filter(|e| sections.contains(&e.section))
+                .map(|e| e.quantity)
+                .sum()
+        };
+        // -- Deck and sideboard size ----------------------------------------
+        let deck_size = if rules.commander {
+            count_in(&[Section::Main, Section::Commander])
+        } else {
+            count_in(&[Section::Main])
+        };
+        let sideboard_size = if rules.commander {
+            count_in(&[Section::Sideboard])
+        } else {
+            count_in(&[Section::Sideboard, Section::Companion])
+        };
+        match rules.exact_deck_size {
+            Some(expected) if deck_size != expected => {
+                violations.push(Violation::WrongDeckSize {
+                    count: deck_size,
+                    expected,
+                });
+            }
+            None if deck_size < rules.min_deck_size => {
+                violations.push(Violation::DeckTooSmall {
+                    count: deck_size,
+                    min: rules.min_deck_size,
+                });
+            }
+            _ => {}
+        }
+        if sideboard_size > rules.max_sideboard {
+            violations.push(Violation::SideboardTooLarge {
+                count: sideboard_size,
+                max: rules.max_sideboard,
+            });
+        }
+        let companions = count_in(&[Section::Companion]);
+        if companions > 1 {
+            violations.push(Violation::TooManyCompanions { count: companions });
+        }
+
+        // -- Commanders and color identity ----------------------------------
+        if rules.commander {
+            let commanders: Vec<&CardInfo> = resolved
+                .iter()
+                .filter(|(e, _)| e.section == Section::Commander)
+                .filter_map(|(_, u)| cards.get(*u))
+                .collect();
+            let commander_count = count_in(&[Section::Commander]);
+            if !(1..=2).contains(&commander_count) {
+                violations.push(Violation::CommanderCount {
+                    count: commander_count,
+                });
+            }
+            if let Some(skill) = rules.leadership_skill {
+                for card in &commanders {
+                    if card.leadership.get(skill).and_then(Value::as_bool) != Some(true) {
+                        violations.push(Violation::InvalidCommander {
+                            name: card.name.clone(),
+                        });
+                    }
+                }
+            }
+
+            if !commanders.is_empty() {
+                let identity: HashSet<&str> = commanders
+                    .iter()
+                    .flat_map(|c| c.color_identity.iter().map(String::as_str))
+                    .collect();
+                let mut reported = HashSet::new();
+                for (_, uuid) in &resolved {
+                    let Some(card) = cards.get(*uuid) else { continue };
+                    let outside: Vec<String> = card
+                        .color_identity
+                        .iter()
+                        .filter(|c| !identity.contains(c.as_str()))
+                        .cloned()
+                        .collect();
+                    if !outside.is_empty() && reported.insert(card.name.clone()) {
+                        violations.push(Violation::ColorIdentity {
+                            name: card.name.clone(),
+                            colors: outside,
+                        });
+                    }
+                }
+            }
+        }
+
+        // -- Per-card legality and copy limits ------------------------------
+        let statuses = self.statuses(&uuids, &format)?;
+        let mut by_name: BTreeMap<&str, (i64, &str)> = BTreeMap::new();
+        for (entry, uuid) in &resolved {
+            let Some(card) = cards.get(*uuid) else { continue };
+            let slot = by_name.entry(card.name.as_str()).or_insert((0, uuid));
+            slot.0 += entry.quantity;
+        }
+        for (name, (count, uuid)) in by_name {
+            let card = &cards[uuid];
+            let status = statuses.get(uuid).map(String::as_str).unwrap_or("Not Legal");
+            match status {
+                "Legal" => {}
+                "Restricted" => {
+                    if count > 1 {
+                        violations.push(Violation::Restricted {
+                            name: name.to_string(),
+                            count,
+                        });
+                    }
+                    continue;
+                }
+                other => {
+                    violations.push(Violation::NotLegal {
+                        name: name.to_string(),
+                        status: other.to_string(),
+                    });
+                    continue;
+                }
+            }
+            if !card.is_basic && !card.alternative_limit && count > rules.max_copies {
+                violations.push(Violation::TooManyCopies {
+                    name: name.to_string(),
+                    count,
+                    max: rules.max_copies,
+                });
+            }
+        }
+
+        Ok(ValidationReport {
+            format,
+            deck_size,
+            sideboard_size,
+            violations,
+        })
+    }
+
+    /// Resolve each entry to a UUID (by `uuid`, else by name) and load the
+    /// card facts for every resolved UUID.
+    fn resolve(&self, entries: &[DeckEntry]) -> Result<Resolved> {
+        let cq = CardQuery::new(self.conn.get()?);
+        let mut rows: HashMap<String, Value> = HashMap::new();
+
+        let wanted: Vec<&str> = entries.iter().filter_map(|e| e.uuid.as_deref()).collect();
+        if !wanted.is_empty() {
+            for row in cq.get_by_uuids(&wanted)? {
+                if let Some(uuid) = row.get("uuid").and_then(Value::as_str) {
+                    rows.insert(uuid.to_string(), row.clone());
+                }
+            }
+        }
+
+        let mut by_name: HashMap<&str, Option<String>> = HashMap::new();
+        let mut uuids = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let uuid = match &entry.uuid {
+                Some(u) => rows.contains_key(u).then(|| u.clone()),
+                None => {
+                    if !by_name.contains_key(entry.name.as_str()) {
+                        // Legality and identity are per card, so any printing will do
+                        let hit = cq.get_by_name(&entry.name, None)?.into_iter().next();
+                        let uuid = hit.as_ref().and_then(|r| r.get("uuid")?.as_str().map(String::from));
+                        if let (Some(u), Some(row)) = (&uuid, hit) {
+                            rows.insert(u.clone(), row);
+                        }
+                        by_name.insert(&entry.name, uuid);
+                    }
+                    by_name[entry.name.as_str()].clone()
+                }
+            };
+            uuids.push(uuid);
+        }
+
+        let cards = rows
+            .into_iter()
+            .map(|(uuid, row)| (uuid, card_info(&row)))
+            .collect();
+        Ok((uuids, cards))
+    }
+
+    /// Legality status per UUID in `format`; absent UUIDs are not legal.
+    fn statuses(&self, uuids: &[Option<String>], format: &str) -> Result<HashMap<String, String>> {
+        let wanted: Vec<&str> = uuids.iter().flatten().map(String::as_str).collect();
+        if wanted.is_empty() {
+            return Ok(HashMap::new());
+        }
+        self.conn.get()?.ensure_views(&["card_legalities"])?;
+
+        let (sql, params) = SqlBuilder::new("card_legalities")
+            .select(&["uuid", "status"])
+            .where_eq("format", format)
+            .where_in("uuid", &wanted)
+            .build();
+
+        Ok(self
+            .conn
+            .get()?
+            .execute(&sql, &params)?
+            .into_iter()
+            .filter_map(|row| {
+                Some((
+                    row.get("uuid")?.as_str()?.to_string(),
+                    row.get("status")?.as_str()?.to_string(),
+                ))
+            })
+            .collect())
+    }
+}
+
+/// Extract validation facts from a `cards` row.
+fn card_info(row: &Value) -> CardInfo {
+    let text = |key: &str| row.get(key).and_then(Value::as_str).unwrap_or_default();
+    let type_line = text("type");
+
+    // List columns arrive as arrays from parquet views, or as comma-separated
+    // strings from plain tables
+    let color_identity = match row.get("colorIdentity") {
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(Value::as_str)
+            .filter(|c| !c.is_empty())
+            .map(String::from)
+            .collect(),
+        Some(Value::String(s)) => s
+            .split(',')
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty())
+            .collect(),
+        _ => Vec::new(),
+    };
+    let leadership = match row.get("leadershipSkills") {
+        Some(Value::String(s)) => serde_json::from_str(s).unwrap_or(Value::Null),
+        Some(v) => v.clone(),
+        None => Value::Null,
+    };
+
+    CardInfo {
+        name: text("name").to_string(),
+        is_basic: type_line.starts_with("Basic") && type_line.contains("Land"),
+        alternative_limit: row
+            .get("hasAlternativeDeckLimit")
+            .and_then(Value::as_bool)
+            .unwrap_or(false),
+        color_identity,
+        leadership,
+    }
+}
@@ -0,0 +1,270 @@
+//! Local HTTP stand-in for the MTGJSON CDN.
+//!
+//! A deliberately small HTTP/1.1 server on a raw `TcpListener`: one request
+//! per connection, `Range: bytes=N-` support, `ETag` / `If-None-Match`
+//! revalidation, and responses that can be cut off midway to simulate flaky
+//! connections. [`meta`], [`parquet`] and [`cache_for`] build the files it
+//! serves and a cache manager pointed at it.
+
+#![allow(dead_code)]
+
+use mtgjson_sdk::CacheManager;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// A request received by the stand-in.
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub path: String,
+    /// Header names are lowercased.
+    pub headers: HashMap<String, String>,
+}
+
+#[derive(Default)]
+struct Route {
+    body: Vec<u8>,
+    /// Changes whenever the body is replaced.
+    etag: String,
+    /// Remaining responses to cut off, and after how many body bytes.
+    cuts: usize,
+    cut_at: usize,
+    /// How long to wait before answering.
+    delay: Duration,
+}
+
+#[derive(Default)]
+struct State {
+    routes: HashMap<String, Route>,
+    log: Vec<Request>,
+    revision: usize,
+    in_flight: usize,
+    max_in_flight: usize,
+}
+
+/// A running stand-in; the server thread lives until the test process exits.
+#[derive(Clone)]
+pub struct Cdn {
+    pub base: String,
+    state: Arc<Mutex<State>>,
+}
+
+impl Cdn {
+    /// Serve `files` (path without leading `/` -> body). Unknown paths get a
+    /// 404.
+    pub fn start(files: Vec<(&str, Vec<u8>)>) -> Cdn {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let cdn = Cdn {
+            base,
+            state: Arc::default(),
+        };
+        for (path, body) in files {
+            cdn.set(path, body);
+        }
+
+        let state = cdn.state.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let state = state.clone();
+                std::thread::spawn(move || handle(stream, &state));
+            }
+        });
+        cdn
+    }
+
+    /// Add or replace a file. Replacing a file changes its ETag, even if the
+    /// body is the same.
+    pub fn set(&self, path: &str, body: Vec<u8>) {
+        let mut state = self.state.lock().unwrap();
+        state.revision += 1;
+        let etag = format!("\"rev-{}\"", state.revision);
+        let route = state.routes.entry(format!("/{}", path)).or_default();
+        route.body = body;
+        route.etag = etag;
+    }
+
+    /// Cut the next `times` responses for `path` after `after` body bytes.
+    pub fn cut(&self, path: &str, times: usize, after: usize) {
+        let mut state = self.state.lock().unwrap();
+        let route = state.routes.get_mut(&format!("/{}", path)).unwrap();
+        route.cuts = times;
+        route.cut_at = after;
+    }
+
+    /// Wait `delay` before answering each request for `path`.
+    pub fn delay(&self, path: &str, delay: Duration) {
+        let mut state = self.state.lock().unwrap();
+        state.routes.get_mut(&format!("/{}", path)).unwrap().delay = delay;
+    }
+
+    /// Largest number of requests that were being answered at once.
+    pub fn max_in_flight(&self) -> usize {
+        self.state.lock().unwrap().max_in_flight
+    }
+
+    /// Requests received so far for `path`.
+    pub fn requests(&self, path: &str) -> Vec<Request> {
+        let path = format!("/{}", path);
+        let state = self.state.lock().unwrap();
+        state.log.iter().filter(|r| r.path == path).cloned().collect()
+    }
+}
+
+/// Start a stand-in serving `files` and return its base URL.
+pub fn serve(files: Vec<(&str, Vec<u8>)>) -> String {
+    Cdn::start(files).base
+}
+
+/// A base URL nothing is listening on.
+pub fn unreachable() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    drop(listener);
+    format!("http://127.0.0.1:{}", port)
+}
+
+/// A `Meta.json` body announcing `version`.
+pub fn meta(version: &str) -> Vec<u8> {
+    format!(r#"{{"data": {{"version": "{}"}}}}"#, version).into_bytes()
+}
+
+/// A parquet file holding the rows of `select`.
+pub fn parquet(select: &str) -> Vec<u8> {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("data.parquet");
+    duckdb::Connection::open_in_memory()
+        .unwrap()
+        .execute_batch(&format!(
+            "COPY ({}) TO '{}' (FORMAT PARQUET)",
+            select,
+            path.to_string_lossy().replace('\\', "/")
+        ))
+        .unwrap();
+    fs::read(path).unwrap()
+}
+
+/// A cache manager in `dir` downloading from `cdn`, retrying without delay.
+pub fn cache_for(dir: &Path, cdn: &Cdn) -> CacheManager {
+    let mut cache =
+        CacheManager::new(Some(dir.to_path_buf()), false, Duration::from_secs(10)).unwrap();
+    cache.cdn_base = cdn.base.clone();
+    cache.retry_backoff = Duration::from_millis(1);
+    cache
+}
+
+fn handle(mut stream: TcpStream, state: &Mutex<State>) {
+    let Some(request) = read_request(&stream) else {
+        return;
+    };
+    let delay = {
+        let mut state = state.lock().unwrap();
+        state.in_flight += 1;
+        state.max_in_flight = state.max_in_flight.max(state.in_flight);
+        state.routes.get(&request.path).map_or(Duration::ZERO, |r| r.delay)
+    };
+    std::thread::sleep(delay);
+    answer(&mut stream, request, state);
+    state.lock().unwrap().in_flight -= 1;
+}
+
+fn answer(stream: &mut TcpStream, request: Request, state: &Mutex<State>) {
+    let (status, extra, body, cut_at) = {
+        let mut state = state.lock().unwrap();
+        state.log.push(request.clone());
+        match state.routes.get_mut(&request.path) {
+            None => ("404 Not Found", Vec::new(), Vec::new(), None),
+            Some(route) => {
+                let cut_at = (route.cuts > 0).then(|| {
+                    route.cuts -= 1;
+                    route.cut_at
+                });
+                let len = route.body.len();
+                let etag = format!("ETag: {}", route.etag);
+                if request.headers.get("if-none-match") == Some(&route.etag) {
+                    return respond(stream, "304 Not Modified", &[etag], &[], None);
+                }
+                match range_start(&request) {
+                    Some(start) if start >= len => (
+                        "416 Range Not Satisfiable",
+                        vec![format!("Content-Range: bytes */{}", len)],
+                        Vec::new(),
+                        None,
+                    ),
+                    Some(start) => (
+                        "206 Partial Content",
+                        vec![
+                            format!("Content-Range: bytes {}-{}/{}", start, len - 1, len),
+                            etag,
+                        ],
+                        route.body[start..].to_vec(),
+                        cut_at,
+                    ),
+                    None => ("200 OK", vec![etag], route.body.clone(), cut_at),
+                }
+            }
+        }
+    };
+
+    respond(stream, status, &extra, &body, cut_at);
+}
+
+fn respond(
+    stream: &mut TcpStream,
+    status: &str,
+    headers: &[String],
+    body: &[u8],
+    cut_at: Option<usize>,
+) {
+    let mut head = format!(
+        "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        status,
+        body.len()
+    );
+    for header in headers {
+        head.push_str(header);
+        head.push_str("\r\n");
+    }
+    head.push_str("\r\n");
+
+    let sent = cut_at.map_or(body.len(), |n| n.min(body.len()));
+    let _ = stream.write_all(head.as_bytes());
+    let _ = stream.write_all(&body[..sent]);
+    let _ = stream.flush();
+    // Dropping the stream closes the connection, short of Content-Length if cut
+}
+
+fn read_request(stream: &TcpStream) -> Option<Request> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line).ok()?;
+    let path = line.split_whitespace().nth(1)?.to_string();
+
+    let mut headers = HashMap::new();
+    loop {
+        line.clear();
+        reader.read_line(&mut line).ok()?;
+        let trimmed = line.trim_end();
+        if trimmed.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = trimmed.split_once(':') {
+            headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+    }
+    Some(Request { path, headers })
+}
+
+fn range_start(request: &Request) -> Option<usize> {
+    request
+        .headers
+        .get("range")?
+        .strip_prefix("bytes=")?
+        .strip_suffix('-')?
+        .parse()
+        .ok()
+}
@@ -2,7 +2,11 @@
 //!
 //! Provides `setup_sample_db()` which creates an in-memory DuckDB connection
 //! populated with small sample tables (cards, sets, tokens, card_identifiers,
-//! card_legalities) via NDJSON temp files.
+//! card_legalities) via NDJSON temp files, `setup_parquet_db()` which writes
+//! caller-supplied parquet files into the cache directory, and [`cdn`], a
+//! local HTTP stand-in for the CDN.
+
+pub mod cdn;
 
 use mtgjson_sdk::{CacheManager, Connection};
 use std::io::Write;
@@ -38,6 +42,28 @@ pub fn setup_sample_db() -> (Connection, tempfile::TempDir) {
     (conn, tmp_dir)
 }
 
+/// Create an offline `Connection` whose cache directory holds the parquet
+/// files written by `sql`, a batch of `COPY ... TO '{dir}/<file>.parquet'`
+/// statements where `{dir}` stands for the cache's `parquet` directory.
+///
+/// Unlike [`setup_sample_db`], views are registered from these files on first
+/// use, so they go through the connection's CSV-to-array and JSON-cast
+/// rewriting.
+#[allow(dead_code)]
+pub fn setup_parquet_db(sql: &str) -> (Connection, tempfile::TempDir) {
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let parquet_dir = tmp_dir.path().join("parquet");
+    std::fs::create_dir_all(&parquet_dir).unwrap();
+
+    let cache = CacheManager::new(Some(tmp_dir.path().to_path_buf()), true, Duration::from_secs(30)).unwrap();
+    let conn = Connection::new(cache).unwrap();
+
+    let dir = parquet_dir.to_string_lossy().replace('\\', "/");
+    conn.raw().execute_batch(&sql.replace("{dir}", &dir)).unwrap();
+
+    (conn, tmp_dir)
+}
+
 fn register_cards(conn: &Connection) {
     let cards = vec![
         serde_json::json!({
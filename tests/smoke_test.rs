@@ -1926,7 +1926,7 @@ fn smoke_test() {
     let rows_param = sdk
         .sql(
             "SELECT name FROM cards WHERE setCode = ? LIMIT 5",
-            &["MH3".to_string()],
+            &["MH3".into()],
         )
         .unwrap();
     c.check(
@@ -0,0 +1,123 @@
+//! Arrow result tests: `Connection::execute_arrow` and the `_arrow` query
+//! variants. Only built with `--features arrow`.
+
+#![cfg(feature = "arrow")]
+
+mod common;
+
+use mtgjson_sdk::arrow::array::{AsArray, RecordBatch};
+use mtgjson_sdk::arrow::datatypes::{DataType, Float64Type, Int64Type};
+use mtgjson_sdk::queries::cards::{CardQuery, SearchCardsParams};
+use mtgjson_sdk::queries::prices::PriceQuery;
+
+fn total_rows(batches: &[RecordBatch]) -> usize {
+    batches.iter().map(|b| b.num_rows()).sum()
+}
+
+// ---------------------------------------------------------------------------
+// Connection::execute_arrow
+// ---------------------------------------------------------------------------
+
+#[test]
+fn execute_arrow_preserves_types() {
+    let (conn, _tmp) = common::setup_sample_db();
+
+    let batches = conn
+        .execute_arrow(
+            "SELECT 42::BIGINT AS n, 1.5::DOUBLE AS f, 'x' AS s, DATE '2024-06-01' AS d",
+            &[],
+        )
+        .unwrap();
+    assert_eq!(total_rows(&batches), 1);
+
+    let schema = batches[0].schema();
+    assert_eq!(schema.field(0).data_type(), &DataType::Int64);
+    assert_eq!(schema.field(1).data_type(), &DataType::Float64);
+    assert_eq!(schema.field(3).data_type(), &DataType::Date32);
+    assert_eq!(batches[0].column(0).as_primitive::<Int64Type>().value(0), 42);
+    assert_eq!(batches[0].column(1).as_primitive::<Float64Type>().value(0), 1.5);
+}
+
+#[test]
+fn execute_arrow_binds_params() {
+    let (conn, _tmp) = common::setup_sample_db();
+
+    let batches = conn
+        .execute_arrow(
+            "SELECT uuid, name FROM cards WHERE setCode = ? ORDER BY uuid",
+            &["A25".into()],
+        )
+        .unwrap();
+    assert_eq!(total_rows(&batches), 2);
+    let names = batches[0].column_by_name("name").unwrap().as_string::<i32>();
+    assert_eq!(names.value(0), "Lightning Bolt");
+}
+
+#[test]
+fn execute_arrow_spans_multiple_batches() {
+    let (conn, _tmp) = common::setup_sample_db();
+
+    let batches = conn
+        .execute_arrow("SELECT range AS n FROM range(5000)", &[])
+        .unwrap();
+    assert!(batches.len() > 1);
+    assert_eq!(total_rows(&batches), 5000);
+}
+
+// ---------------------------------------------------------------------------
+// Query interface variants
+// ---------------------------------------------------------------------------
+
+#[test]
+fn card_search_arrow_matches_search() {
+    let (conn, _tmp) = common::setup_sample_db();
+    let cq = CardQuery::new(&conn);
+    let params = SearchCardsParams {
+        set_code: Some("A25".to_string()),
+        ..Default::default()
+    };
+
+    let rows = cq.search(&params).unwrap();
+    let batches = cq.search_arrow(&params).unwrap();
+    assert_eq!(total_rows(&batches), rows.len());
+}
+
+#[test]
+fn price_history_arrow_filters_by_date() {
+    let (conn, tmp) = common::setup_sample_db();
+
+    let ndjson: String = [
+        ("card-uuid-001", "2024-01-01", 1.0),
+        ("card-uuid-001", "2024-02-01", 1.5),
+        ("card-uuid-001", "2024-03-01", 2.0),
+        ("card-uuid-002", "2024-03-01", 9.0),
+    ]
+    .iter()
+    .map(|(uuid, date, price)| {
+        serde_json::json!({
+            "uuid": uuid, "source": "paper", "provider": "tcgplayer",
+            "currency": "USD", "price_type": "retail", "finish": "normal",
+            "date": date, "price": price
+        })
+        .to_string()
+            + "\n"
+    })
+    .collect();
+    let path = tmp.path().join("all_prices.ndjson");
+    std::fs::write(&path, ndjson).unwrap();
+    conn.register_table_from_ndjson("all_prices", &path.to_string_lossy())
+        .unwrap();
+
+    let pq = PriceQuery::new(&conn);
+    let batches = pq
+        .history_arrow("card-uuid-001", Some("2024-02-01"), None)
+        .unwrap();
+    assert_eq!(total_rows(&batches), 2);
+
+    let prices = batches[0]
+        .column_by_name("price")
+        .unwrap()
+        .as_primitive::<Float64Type>();
+    assert_eq!(prices.value(0), 1.5);
+    assert_eq!(prices.value(1), 2.0);
+}
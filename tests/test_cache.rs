@@ -0,0 +1,199 @@
+//! Cache prefetch and bundle export/import tests.
+
+use mtgjson_sdk::{CacheManager, Connection, MtgjsonError, MtgjsonSdk};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+fn offline_cache(dir: &Path) -> CacheManager {
+    CacheManager::new(Some(dir.to_path_buf()), true, Duration::from_secs(30)).unwrap()
+}
+
+/// Populate a cache directory with a real `cards.parquet`, a JSON file, a
+/// deck, a collection database and a leftover partial download.
+fn populate(dir: &Path) {
+    let conn = Connection::new(offline_cache(dir)).unwrap();
+    let parquet_dir = dir.join("parquet");
+    fs::create_dir_all(&parquet_dir).unwrap();
+    conn.raw()
+        .execute_batch(&format!(
+            "COPY (SELECT * FROM (VALUES ('uuid-bolt', 'Lightning Bolt', 'A25')) \
+             AS t(uuid, name, setCode)) TO '{}/cards.parquet' (FORMAT PARQUET);",
+            parquet_dir.to_string_lossy().replace('\\', "/")
+        ))
+        .unwrap();
+
+    fs::write(dir.join("Meta.json"), r#"{"data": {"version": "5.2.2"}}"#).unwrap();
+    fs::create_dir_all(dir.join("decks")).unwrap();
+    fs::write(dir.join("decks/Starter_M10.json"), "{}").unwrap();
+    fs::write(dir.join("collection.duckdb"), "user data").unwrap();
+    fs::write(dir.join("parquet/sets.parquet.tmp"), "partial").unwrap();
+    fs::write(dir.join("version.txt"), "5.2.2+20240101").unwrap();
+}
+
+// ---------------------------------------------------------------------------
+// Prefetch
+// ---------------------------------------------------------------------------
+
+#[test]
+fn prefetch_reports_progress_per_file() {
+    let tmp = tempfile::tempdir().unwrap();
+    populate(tmp.path());
+    let mut cache = offline_cache(tmp.path());
+
+    let mut seen = Vec::new();
+    let paths = cache
+        .prefetch(&["meta", "cards"], |p| {
+            seen.push((p.name.to_string(), p.completed, p.total));
+        })
+        .unwrap();
+
+    assert_eq!(
+        paths,
+        vec![
+            tmp.path().join("Meta.json"),
+            tmp.path().join("parquet/cards.parquet")
+        ]
+    );
+    assert_eq!(
+        seen,
+        vec![("meta".to_string(), 1, 2), ("cards".to_string(), 2, 2)]
+    );
+}
+
+#[test]
+fn prefetch_rejects_unknown_names_before_fetching() {
+    let tmp = tempfile::tempdir().unwrap();
+    populate(tmp.path());
+    let mut cache = offline_cache(tmp.path());
+
+    let mut calls = 0;
+    let err = cache
+        .prefetch(&["cards", "not_a_view"], |_| calls += 1)
+        .unwrap_err();
+    assert!(matches!(err, MtgjsonError::NotFound(_)));
+    assert_eq!(calls, 0);
+}
+
+#[test]
+fn prefetch_all_offline_fails_on_missing_file() {
+    let tmp = tempfile::tempdir().unwrap();
+    populate(tmp.path());
+    let mut cache = offline_cache(tmp.path());
+
+    let mut done = Vec::new();
+    let err = cache
+        .prefetch_all(|p| done.push(p.name.to_string()))
+        .unwrap_err();
+    assert!(matches!(err, MtgjsonError::NotFound(_)));
+    // Name order: "all_prices" sorts first and is not cached
+    assert!(done.is_empty());
+}
+
+// ---------------------------------------------------------------------------
+// Bundles
+// ---------------------------------------------------------------------------
+
+#[test]
+fn bundle_round_trip() {
+    let src = tempfile::tempdir().unwrap();
+    populate(src.path());
+    let out = tempfile::tempdir().unwrap();
+    let bundle = out.path().join("mtgjson.tar");
+
+    let manifest = offline_cache(src.path()).export_bundle(&bundle).unwrap();
+    assert_eq!(manifest.version.as_deref(), Some("5.2.2+20240101"));
+    let files: Vec<_> = manifest.files.iter().map(|f| f.path.as_str()).collect();
+    assert_eq!(
+        files,
+        vec!["Meta.json", "decks/Starter_M10.json", "parquet/cards.parquet"]
+    );
+    assert_eq!(CacheManager::read_bundle_manifest(&bundle).unwrap(), manifest);
+
+    let dest = tempfile::tempdir().unwrap();
+    let mut cache = offline_cache(dest.path());
+    cache.import_bundle(&bundle).unwrap();
+
+    assert_eq!(
+        fs::read_to_string(dest.path().join("version.txt")).unwrap(),
+        "5.2.2+20240101"
+    );
+    assert_eq!(
+        fs::read(dest.path().join("parquet/cards.parquet")).unwrap(),
+        fs::read(src.path().join("parquet/cards.parquet")).unwrap()
+    );
+    assert!(dest.path().join("decks/Starter_M10.json").exists());
+    assert!(!dest.path().join("collection.duckdb").exists());
+}
+
+#[test]
+fn import_rejects_non_bundle_archives() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("other.tar");
+    let mut tar = tar::Builder::new(fs::File::create(&path).unwrap());
+    let mut header = tar::Header::new_gnu();
+    header.set_size(2);
+    header.set_mode(0o644);
+    header.set_cksum();
+    tar.append_data(&mut header, "Meta.json", &b"{}"[..]).unwrap();
+    tar.into_inner().unwrap();
+
+    let mut cache = offline_cache(tmp.path());
+    let err = cache.import_bundle(&path).unwrap_err();
+    assert!(matches!(err, MtgjsonError::InvalidArgument(_)));
+    assert!(!tmp.path().join("Meta.json").exists());
+}
+
+#[test]
+fn import_rejects_manifest_paths_outside_the_cache() {
+    let tmp = tempfile::tempdir().unwrap();
+    let cache_dir = tmp.path().join("cache");
+    fs::create_dir_all(&cache_dir).unwrap();
+    // A cache file the hostile entry resolves to from the staging directory
+    fs::write(cache_dir.join("victim.json"), "{}").unwrap();
+    let absolute = tmp.path().join("absolute.json");
+
+    for hostile in ["../victim.json", absolute.to_str().unwrap(), ""] {
+        let path = tmp.path().join("hostile.tar");
+        let manifest = serde_json::json!({
+            "format": 1,
+            "version": null,
+            "files": [{"path": hostile, "size": 2}],
+        })
+        .to_string();
+        let mut tar = tar::Builder::new(fs::File::create(&path).unwrap());
+        let mut header = tar::Header::new_gnu();
+        header.set_size(manifest.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        tar.append_data(&mut header, "manifest.json", manifest.as_bytes()).unwrap();
+        tar.into_inner().unwrap();
+
+        let mut cache = offline_cache(&cache_dir);
+        let err = cache.import_bundle(&path).unwrap_err();
+        assert!(matches!(err, MtgjsonError::InvalidArgument(_)), "{}", err);
+    }
+    assert!(cache_dir.join("victim.json").exists());
+    assert!(!tmp.path().join("victim.json").exists());
+    assert!(!absolute.exists());
+}
+
+#[test]
+fn builder_loads_bundle_offline() {
+    let src = tempfile::tempdir().unwrap();
+    populate(src.path());
+    let out = tempfile::tempdir().unwrap();
+    let bundle = out.path().join("mtgjson.tar");
+    offline_cache(src.path()).export_bundle(&bundle).unwrap();
+
+    let dest = tempfile::tempdir().unwrap();
+    let sdk = MtgjsonSdk::builder()
+        .cache_dir(dest.path())
+        .offline(true)
+        .bundle(&bundle)
+        .build()
+        .unwrap();
+
+    let card = sdk.cards().get_by_uuid("uuid-bolt").unwrap().unwrap();
+    assert_eq!(card["name"], "Lightning Bolt");
+}
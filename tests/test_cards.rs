@@ -125,6 +125,20 @@ fn search_with_name_wildcard() {
     assert_eq!(results[0]["name"], "Lightning Bolt");
 }
 
+#[test]
+fn search_fuzzy_name_binds_quotes() {
+    let (conn, _tmp) = common::setup_sample_db();
+    let cq = CardQuery::new(&conn);
+
+    let results = cq
+        .search(&SearchCardsParams {
+            fuzzy_name: Some("Lightnin' Bolt".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!(results[0]["name"], "Lightning Bolt");
+}
+
 #[test]
 fn search_with_rarity_filter() {
     let (conn, _tmp) = common::setup_sample_db();
@@ -0,0 +1,212 @@
+//! Collection tracking tests: persistence, add/remove, valuation and set
+//! completion.
+
+mod common;
+
+use mtgjson_sdk::models::CollectionEntry;
+use mtgjson_sdk::queries::collection::CollectionQuery;
+use mtgjson_sdk::{CacheManager, Connection, MtgjsonError};
+use std::time::Duration;
+
+fn register_prices_today(conn: &Connection, dir: &std::path::Path) {
+    let ndjson: String = [
+        ("card-uuid-001", "tcgplayer", "USD", "normal", "2024-06-01", 2.0),
+        ("card-uuid-001", "tcgplayer", "USD", "normal", "2024-05-31", 1.0),
+        ("card-uuid-001", "tcgplayer", "USD", "foil", "2024-06-01", 10.0),
+        ("card-uuid-002", "tcgplayer", "USD", "normal", "2024-06-01", 1.5),
+        ("card-uuid-001", "cardmarket", "EUR", "normal", "2024-06-01", 1.8),
+    ]
+    .iter()
+    .map(|(uuid, provider, currency, finish, date, price)| {
+        serde_json::json!({
+            "uuid": uuid, "source": "paper", "provider": provider,
+            "currency": currency, "price_type": "retail", "finish": finish,
+            "date": date, "price": price
+        })
+        .to_string()
+            + "\n"
+    })
+    .collect();
+    let path = dir.join("all_prices_today.ndjson");
+    std::fs::write(&path, ndjson).unwrap();
+    conn.register_table_from_ndjson("all_prices_today", &path.to_string_lossy())
+        .unwrap();
+}
+
+// ---------------------------------------------------------------------------
+// Editing
+// ---------------------------------------------------------------------------
+
+#[test]
+fn add_merges_quantities_for_same_key() {
+    let (conn, _tmp) = common::setup_sample_db();
+    let coll = CollectionQuery::new(&conn);
+
+    coll.add(&CollectionEntry::new("card-uuid-001", 2)).unwrap();
+    coll.add(&CollectionEntry {
+        acquired_price: Some(1.25),
+        acquired_date: Some("2024-01-15".to_string()),
+        ..CollectionEntry::new("card-uuid-001", 1)
+    })
+    .unwrap();
+    coll.add(&CollectionEntry {
+        finish: "foil".to_string(),
+        ..CollectionEntry::new("card-uuid-001", 1)
+    })
+    .unwrap();
+
+    let entries = coll.list(None).unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].finish, "foil");
+    assert_eq!(entries[1].quantity, 3);
+    assert_eq!(entries[1].acquired_price, Some(1.25));
+    assert_eq!(entries[1].acquired_date.as_deref(), Some("2024-01-15"));
+}
+
+#[test]
+fn remove_decrements_then_deletes() {
+    let (conn, _tmp) = common::setup_sample_db();
+    let coll = CollectionQuery::new(&conn);
+
+    coll.add(&CollectionEntry::new("card-uuid-002", 4)).unwrap();
+    assert_eq!(coll.remove(&CollectionEntry::new("card-uuid-002", 1)).unwrap(), 3);
+    assert_eq!(coll.remove(&CollectionEntry::new("card-uuid-002", 3)).unwrap(), 0);
+    assert!(coll.list(None).unwrap().is_empty());
+
+    let err = coll
+        .remove(&CollectionEntry::new("card-uuid-002", 1))
+        .unwrap_err();
+    assert!(matches!(err, MtgjsonError::NotFound(_)));
+}
+
+#[test]
+fn remove_more_than_owned_is_rejected() {
+    let (conn, _tmp) = common::setup_sample_db();
+    let coll = CollectionQuery::new(&conn);
+
+    coll.add(&CollectionEntry::new("card-uuid-002", 2)).unwrap();
+    let err = coll
+        .remove(&CollectionEntry::new("card-uuid-002", 3))
+        .unwrap_err();
+    assert!(matches!(err, MtgjsonError::InvalidArgument(_)), "{:?}", err);
+    assert_eq!(coll.list(None).unwrap()[0].quantity, 2);
+}
+
+#[test]
+fn concurrent_removes_do_not_lose_decrements() {
+    let (conn, _tmp) = common::setup_sample_db();
+    CollectionQuery::new(&conn)
+        .add(&CollectionEntry::new("card-uuid-002", 40))
+        .unwrap();
+
+    std::thread::scope(|s| {
+        for _ in 0..4 {
+            let clone = conn.try_clone().unwrap();
+            s.spawn(move || {
+                let coll = CollectionQuery::new(&clone);
+                let mut removed = 0;
+                while removed < 5 {
+                    // A conflicting transaction fails whole; retry it
+                    if coll.remove(&CollectionEntry::new("card-uuid-002", 1)).is_ok() {
+                        removed += 1;
+                    }
+                }
+            });
+        }
+    });
+    assert_eq!(CollectionQuery::new(&conn).list(None).unwrap()[0].quantity, 20);
+}
+
+#[test]
+fn add_rejects_non_positive_quantity() {
+    let (conn, _tmp) = common::setup_sample_db();
+    let coll = CollectionQuery::new(&conn);
+
+    let err = coll.add(&CollectionEntry::new("card-uuid-001", 0)).unwrap_err();
+    assert!(matches!(err, MtgjsonError::InvalidArgument(_)));
+}
+
+#[test]
+fn collection_persists_across_connections_and_cache_clear() {
+    let (conn, tmp) = common::setup_sample_db();
+    CollectionQuery::new(&conn)
+        .add(&CollectionEntry::new("card-uuid-003", 1))
+        .unwrap();
+    conn.cache().clear().unwrap();
+    drop(conn);
+
+    let cache = CacheManager::new(Some(tmp.path().to_path_buf()), true, Duration::from_secs(30))
+        .unwrap();
+    let conn = Connection::new(cache).unwrap();
+    let entries = CollectionQuery::new(&conn).list(None).unwrap();
+    assert_eq!(entries, vec![CollectionEntry::new("card-uuid-003", 1)]);
+}
+
+// ---------------------------------------------------------------------------
+// Analytics
+// ---------------------------------------------------------------------------
+
+#[test]
+fn list_filters_by_set() {
+    let (conn, _tmp) = common::setup_sample_db();
+    let coll = CollectionQuery::new(&conn);
+
+    coll.add(&CollectionEntry::new("card-uuid-001", 1)).unwrap();
+    coll.add(&CollectionEntry::new("card-uuid-003", 1)).unwrap();
+
+    let mh2 = coll.list(Some("mh2")).unwrap();
+    assert_eq!(mh2.len(), 1);
+    assert_eq!(mh2[0].uuid, "card-uuid-003");
+}
+
+#[test]
+fn value_uses_latest_price_per_finish() {
+    let (conn, tmp) = common::setup_sample_db();
+    register_prices_today(&conn, tmp.path());
+    let coll = CollectionQuery::new(&conn);
+
+    coll.add(&CollectionEntry {
+        acquired_price: Some(0.5),
+        ..CollectionEntry::new("card-uuid-001", 2)
+    })
+    .unwrap();
+    coll.add(&CollectionEntry {
+        finish: "foil".to_string(),
+        ..CollectionEntry::new("card-uuid-001", 1)
+    })
+    .unwrap();
+    coll.add(&CollectionEntry::new("card-uuid-003", 1)).unwrap();
+
+    let value = coll.value("tcgplayer").unwrap();
+    assert_eq!(value.currency.as_deref(), Some("USD"));
+    assert_eq!(value.total_value, 2.0 * 2.0 + 10.0);
+    assert_eq!(value.cost_basis, 1.0);
+    assert_eq!(value.total_quantity, 4);
+    assert_eq!(value.priced_quantity, 3);
+
+    let eur = coll.value("cardmarket").unwrap();
+    assert_eq!(eur.currency.as_deref(), Some("EUR"));
+    assert_eq!(eur.priced_quantity, 2);
+}
+
+#[test]
+fn set_completion_and_missing_cards() {
+    let (conn, _tmp) = common::setup_sample_db();
+    let coll = CollectionQuery::new(&conn);
+
+    coll.add(&CollectionEntry::new("card-uuid-001", 1)).unwrap();
+    coll.add(&CollectionEntry {
+        finish: "foil".to_string(),
+        ..CollectionEntry::new("card-uuid-001", 1)
+    })
+    .unwrap();
+
+    let completion = coll.set_completion("a25").unwrap();
+    assert_eq!(completion.set_code, "A25");
+    assert_eq!((completion.owned, completion.total), (1, 2));
+    assert_eq!(completion.percentage, 50.0);
+
+    let missing = coll.missing_cards("A25").unwrap();
+    assert_eq!(missing.len(), 1);
+    assert_eq!(missing[0].name, "Counterspell");
+}
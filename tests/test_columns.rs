@@ -0,0 +1,153 @@
+//! Column names checked against introspected view schemas.
+
+#[allow(dead_code)]
+mod common;
+
+use mtgjson_sdk::queries::CardQuery;
+use mtgjson_sdk::{MtgjsonError, SqlBuilder};
+use std::collections::HashMap;
+
+fn rejected(result: mtgjson_sdk::Result<()>) -> String {
+    match result {
+        Err(MtgjsonError::InvalidArgument(msg)) => msg,
+        other => panic!("expected InvalidArgument, got {:?}", other),
+    }
+}
+
+#[test]
+fn view_columns_reflect_the_schema() {
+    let (conn, _tmp) = common::setup_sample_db();
+
+    let columns = conn.view_columns("card_identifiers").unwrap();
+    assert!(columns.contains(&"uuid".to_string()));
+    assert!(columns.contains(&"scryfallId".to_string()));
+    assert!(!columns.contains(&"name".to_string()));
+}
+
+#[test]
+fn check_columns_accepts_known_and_qualified_names() {
+    let (conn, _tmp) = common::setup_sample_db();
+
+    conn.check_columns(&["cards"], &["name", "setCode", "cards.manaValue", "SETCODE"])
+        .unwrap();
+    conn.check_columns(&["cards", "sets"], &["releaseDate"]).unwrap();
+    conn.check_columns(&["cards c", "sets s"], &["c.manaValue", "s.releaseDate", "name"])
+        .unwrap();
+}
+
+#[test]
+fn qualified_columns_must_match_their_alias() {
+    let (conn, _tmp) = common::setup_sample_db();
+
+    for (views, column) in [
+        (&["cards"][..], "c.manaValue"),
+        (&["cards c", "sets s"][..], "s.manaValue"),
+        (&["cards c", "sets s"][..], "cards.manaValue"),
+    ] {
+        let msg = rejected(conn.check_columns(views, &[column]));
+        assert!(msg.contains("Unknown column"), "{:?}: {}", column, msg);
+    }
+}
+
+#[test]
+fn views_must_be_identifiers() {
+    let (conn, _tmp) = common::setup_sample_db();
+
+    for view in ["cards; DROP TABLE sets", "cards c extra", "cards 1c", ""] {
+        rejected(conn.check_columns(&[view], &["name"]));
+    }
+    for view in ["cards; DROP TABLE sets", "main.cards", "\"cards\""] {
+        let msg = match conn.view_columns(view) {
+            Err(MtgjsonError::InvalidArgument(msg)) => msg,
+            other => panic!("{:?}: expected InvalidArgument, got {:?}", view, other),
+        };
+        assert!(msg.contains("Invalid view name"), "{}", msg);
+    }
+    assert!(conn.has_view("sets"));
+}
+
+#[test]
+fn check_columns_rejects_unknown_columns() {
+    let (conn, _tmp) = common::setup_sample_db();
+
+    let msg = rejected(conn.check_columns(&["cards"], &["releaseDate"]));
+    assert!(msg.contains("Unknown column"), "{}", msg);
+}
+
+#[test]
+fn check_columns_rejects_injection_attempts() {
+    let (conn, _tmp) = common::setup_sample_db();
+
+    for column in [
+        "name = name OR 1=1 --",
+        "name; DROP TABLE cards",
+        "\"name\"",
+        "LOWER(name)",
+        "a.b.name",
+        "c.",
+        "",
+    ] {
+        let msg = rejected(conn.check_columns(&["cards"], &[column]));
+        assert!(msg.contains("Invalid column name"), "{:?}: {}", column, msg);
+    }
+}
+
+#[test]
+fn builder_columns_are_checked_before_running() {
+    let (conn, _tmp) = common::setup_sample_db();
+
+    let mut qb = SqlBuilder::new("cards");
+    qb.where_eq("setCode", "A25").where_gte("manaValue", 1);
+    assert_eq!(qb.columns(), ["setCode", "manaValue"]);
+    conn.check_columns(&["cards"], qb.columns()).unwrap();
+
+    let mut qb = SqlBuilder::new("cards");
+    qb.where_eq("1=1 OR setCode", "A25");
+    rejected(conn.check_columns(&["cards"], qb.columns()));
+}
+
+#[test]
+fn builder_rejects_unsafe_columns() {
+    let mut qb = SqlBuilder::new("cards c");
+    qb.where_eq("c.setCode", "A25").where_in("rarity", &["rare"]);
+    let (sql, _) = qb.try_build().unwrap();
+    assert!(sql.contains("WHERE c.setCode = ? AND rarity IN (?)"), "{}", sql);
+
+    for column in ["1=1 OR setCode", "name) OR (1=1", "a.b.name", "c.", ""] {
+        let mut qb = SqlBuilder::new("cards");
+        qb.where_like(column, "x");
+        match qb.try_build() {
+            Err(MtgjsonError::InvalidArgument(msg)) => {
+                assert!(msg.contains("Invalid column name"), "{:?}: {}", column, msg)
+            }
+            other => panic!("{:?}: expected InvalidArgument, got {:?}", column, other),
+        }
+        // Quoted when built anyway, so it cannot change the query
+        let (sql, _) = qb.build();
+        assert!(sql.contains(&format!("LOWER(\"{}\")", column)), "{}", sql);
+    }
+}
+
+#[test]
+fn count_filters_are_checked() {
+    let (conn, _tmp) = common::setup_sample_db();
+    let cards = CardQuery::new(&conn);
+
+    let filters = HashMap::from([("setCode".to_string(), "A25".to_string())]);
+    assert_eq!(cards.count(&filters).unwrap(), 2);
+
+    for column in ["setCode = setCode OR 1", "releaseDate"] {
+        let filters = HashMap::from([(column.to_string(), "A25".to_string())]);
+        rejected(cards.count(&filters).map(|_| ()));
+    }
+}
+
+#[test]
+fn reset_views_forgets_introspected_columns() {
+    let (conn, _tmp) = common::setup_sample_db();
+    assert!(conn.view_columns("tokens").is_ok());
+
+    conn.reset_views();
+    // The sample tables are not parquet views, so they cannot be re-created
+    assert!(conn.view_columns("tokens").is_err());
+}
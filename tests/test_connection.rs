@@ -31,7 +31,7 @@ fn execute_with_params() {
     let rows = conn
         .execute(
             "SELECT * FROM cards WHERE setCode = ?",
-            &["A25".to_string()],
+            &["A25".into()],
         )
         .unwrap();
     assert_eq!(rows.len(), 2);
@@ -44,7 +44,7 @@ fn execute_returns_empty_for_no_matches() {
     let rows = conn
         .execute(
             "SELECT * FROM cards WHERE uuid = ?",
-            &["nonexistent".to_string()],
+            &["nonexistent".into()],
         )
         .unwrap();
     assert!(rows.is_empty());
@@ -72,7 +72,7 @@ fn execute_scalar_returns_none_for_empty_result() {
     let result = conn
         .execute_scalar(
             "SELECT uuid FROM cards WHERE uuid = ?",
-            &["nonexistent".to_string()],
+            &["nonexistent".into()],
         )
         .unwrap();
     assert!(result.is_none());
@@ -253,7 +253,7 @@ fn null_values_are_converted_to_json_null() {
     let rows = conn
         .execute(
             "SELECT power FROM cards WHERE uuid = ?",
-            &["card-uuid-001".to_string()],
+            &["card-uuid-001".into()],
         )
         .unwrap();
     assert_eq!(rows.len(), 1);
@@ -267,7 +267,7 @@ fn boolean_values_are_converted_correctly() {
     let rows = conn
         .execute(
             "SELECT isPromo FROM cards WHERE uuid = ?",
-            &["card-uuid-001".to_string()],
+            &["card-uuid-001".into()],
         )
         .unwrap();
     assert_eq!(rows.len(), 1);
@@ -281,7 +281,7 @@ fn numeric_values_are_converted_correctly() {
     let rows = conn
         .execute(
             "SELECT manaValue FROM cards WHERE uuid = ?",
-            &["card-uuid-001".to_string()],
+            &["card-uuid-001".into()],
         )
         .unwrap();
     assert_eq!(rows.len(), 1);
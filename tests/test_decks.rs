@@ -0,0 +1,141 @@
+//! Deck query integration tests against JSON files placed in an offline cache.
+
+use mtgjson_sdk::queries::decks::DeckQuery;
+use mtgjson_sdk::{CacheManager, Connection, MtgjsonError};
+use std::time::Duration;
+
+// ---------------------------------------------------------------------------
+// Fixtures
+// ---------------------------------------------------------------------------
+
+/// Create an offline `Connection` whose cache directory holds `DeckList.json`
+/// and one deck file under `decks/`.
+fn setup_deck_cache() -> (Connection, tempfile::TempDir) {
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let decks_dir = tmp_dir.path().join("decks");
+    std::fs::create_dir_all(&decks_dir).unwrap();
+
+    let deck_list = serde_json::json!({
+        "meta": {"date": "2024-01-01", "version": "5.2.2+20240101"},
+        "data": [
+            {"code": "40K", "name": "Necron Dynasties", "fileName": "NecronDynasties_40K",
+             "type": "Commander Deck", "releaseDate": "2022-10-07"},
+            {"code": "M10", "name": "Presence of Mind", "fileName": "PresenceOfMind_M10",
+             "type": "Intro Pack", "releaseDate": "2009-07-17"}
+        ]
+    });
+    std::fs::write(
+        tmp_dir.path().join("DeckList.json"),
+        deck_list.to_string(),
+    )
+    .unwrap();
+
+    let deck = serde_json::json!({
+        "meta": {"date": "2024-01-01", "version": "5.2.2+20240101"},
+        "data": {
+            "code": "40K",
+            "name": "Necron Dynasties",
+            "type": "Commander Deck",
+            "releaseDate": "2022-10-07",
+            "commander": [card("cmd-uuid", "Imotekh the Stormlord", "Legendary Artifact Creature — Necron", 1)],
+            "mainBoard": [
+                card("main-uuid-1", "Sol Ring", "Artifact", 1),
+                card("main-uuid-2", "Island", "Basic Land — Island", 5)
+            ],
+            "sideBoard": [],
+            "tokens": [card("token-uuid", "Necron Warrior", "Token Artifact Creature — Necron Warrior", 1)]
+        }
+    });
+    std::fs::write(decks_dir.join("NecronDynasties_40K.json"), deck.to_string()).unwrap();
+
+    let cache = CacheManager::new(
+        Some(tmp_dir.path().to_path_buf()),
+        true,
+        Duration::from_secs(30),
+    )
+    .unwrap();
+    let conn = Connection::new(cache).unwrap();
+    (conn, tmp_dir)
+}
+
+fn card(uuid: &str, name: &str, type_line: &str, count: i64) -> serde_json::Value {
+    serde_json::json!({
+        "uuid": uuid,
+        "name": name,
+        "type": type_line,
+        "layout": "normal",
+        "setCode": "40K",
+        "number": "1",
+        "count": count,
+        "isFoil": false
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Deck list
+// ---------------------------------------------------------------------------
+
+#[test]
+fn list_and_search_deck_metadata() {
+    let (conn, _tmp) = setup_deck_cache();
+    let dq = DeckQuery::new(&conn);
+
+    assert_eq!(dq.list(Some("40k"), None).unwrap().len(), 1);
+    assert_eq!(dq.count(None, Some("Intro Pack")).unwrap(), 1);
+    let found = dq.search("necron", None).unwrap();
+    assert_eq!(found[0]["fileName"], "NecronDynasties_40K");
+}
+
+// ---------------------------------------------------------------------------
+// Deck contents
+// ---------------------------------------------------------------------------
+
+#[test]
+fn get_deck_returns_typed_boards() {
+    let (conn, _tmp) = setup_deck_cache();
+    let dq = DeckQuery::new(&conn);
+
+    let deck = dq.get("NecronDynasties_40K").unwrap().unwrap();
+    assert_eq!(deck.name, "Necron Dynasties");
+    assert_eq!(deck.type_field, "Commander Deck");
+    assert_eq!(deck.commander.len(), 1);
+    assert_eq!(deck.commander[0].name, "Imotekh the Stormlord");
+    assert_eq!(deck.main_board.len(), 2);
+    assert_eq!(deck.main_board[1].count, 5);
+    assert!(deck.side_board.is_empty());
+    assert_eq!(deck.tokens[0].uuid, "token-uuid");
+}
+
+#[test]
+fn get_deck_unknown_file_name_is_none() {
+    let (conn, _tmp) = setup_deck_cache();
+    let dq = DeckQuery::new(&conn);
+
+    assert!(dq.get("NoSuchDeck_XXX").unwrap().is_none());
+}
+
+#[test]
+fn get_deck_not_cached_offline_is_not_found() {
+    let (conn, _tmp) = setup_deck_cache();
+    let dq = DeckQuery::new(&conn);
+
+    // Listed in DeckList.json, but its deck file was never downloaded.
+    let err = dq.get("PresenceOfMind_M10").unwrap_err();
+    assert!(matches!(err, MtgjsonError::NotFound(_)));
+}
+
+#[test]
+fn ensure_deck_rejects_path_traversal() {
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let mut cache = CacheManager::new(
+        Some(tmp_dir.path().to_path_buf()),
+        true,
+        Duration::from_secs(30),
+    )
+    .unwrap();
+
+    for bad in ["../DeckList", "decks/Foo", "", "a\\b"] {
+        let err = cache.ensure_deck(bad).unwrap_err();
+        assert!(matches!(err, MtgjsonError::InvalidArgument(_)), "{:?}", bad);
+    }
+}
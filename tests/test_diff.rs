@@ -0,0 +1,173 @@
+//! Changelog between two MTGJSON versions laid out side by side in an
+//! offline cache.
+
+use mtgjson_sdk::models::{ChangeKind, DiffEntry};
+use mtgjson_sdk::{MtgjsonError, MtgjsonSdk};
+use std::fs;
+use std::path::Path;
+
+const OLD: &str = "5.2.2+20240101";
+const NEW: &str = "5.2.3+20240201";
+
+/// Write the four compared parquet files into `dir` from SELECT statements.
+fn write_version(dir: &Path, cards: &str, sets: &str, legalities: &str, identifiers: &str) {
+    let parquet = dir.join("parquet");
+    fs::create_dir_all(&parquet).unwrap();
+    let conn = duckdb::Connection::open_in_memory().unwrap();
+    for (file, select) in [
+        ("cards", cards),
+        ("sets", sets),
+        ("cardLegalities", legalities),
+        ("cardIdentifiers", identifiers),
+    ] {
+        let path = parquet.join(format!("{}.parquet", file));
+        conn.execute_batch(&format!(
+            "COPY ({}) TO '{}' (FORMAT PARQUET)",
+            select,
+            path.to_string_lossy().replace('\\', "/")
+        ))
+        .unwrap();
+    }
+}
+
+/// `OLD` archived under `versions/`, `NEW` as the current cache.
+fn setup() -> (MtgjsonSdk, tempfile::TempDir) {
+    let tmp = tempfile::tempdir().unwrap();
+    write_version(
+        &tmp.path().join("versions").join(OLD),
+        "SELECT * FROM (VALUES \
+            ('c1', 'Lightning Bolt', 'Deal 3 damage.'), \
+            ('c2', 'Shock', 'Deal 2 damage.'), \
+            ('c3', 'Old Card', NULL)) t(uuid, name, text)",
+        "SELECT * FROM (VALUES ('A25', 'Masters 25'), ('OLD', 'Old Set')) t(code, name)",
+        "SELECT * FROM (VALUES \
+            ('c1', 'Legal', 'Legal'), ('c2', 'Legal', NULL), ('c3', 'Legal', 'Legal')) \
+            t(uuid, modern, legacy)",
+        "SELECT * FROM (VALUES ('c1', 'sf-1'), ('c2', NULL)) t(uuid, scryfallId)",
+    );
+    write_version(
+        tmp.path(),
+        "SELECT * FROM (VALUES \
+            ('c1', 'Lightning Bolt', 'Lightning Bolt deals 3 damage to any target.'), \
+            ('c2', 'Shock', 'Deal 2 damage.'), \
+            ('c4', 'New Card', NULL)) t(uuid, name, text)",
+        "SELECT * FROM (VALUES ('A25', 'Masters 25'), ('NEW', 'New Set')) t(code, name)",
+        "SELECT * FROM (VALUES \
+            ('c1', 'Banned', 'Legal'), ('c2', 'Legal', 'Legal'), ('c4', 'Legal', 'Legal')) \
+            t(uuid, modern, legacy)",
+        "SELECT * FROM (VALUES ('c1', 'sf-1', 'mo-1'), ('c2', 'sf-2', NULL)) \
+            t(uuid, scryfallId, mtgoId)",
+    );
+    fs::write(tmp.path().join("version.txt"), NEW).unwrap();
+
+    let sdk = MtgjsonSdk::builder()
+        .cache_dir(tmp.path())
+        .offline(true)
+        .build()
+        .unwrap();
+    (sdk, tmp)
+}
+
+fn entry(
+    kind: ChangeKind,
+    key: &str,
+    field: Option<&str>,
+    old: Option<&str>,
+    new: Option<&str>,
+) -> DiffEntry {
+    DiffEntry {
+        kind,
+        key: key.to_string(),
+        field: field.map(String::from),
+        old_value: old.map(String::from),
+        new_value: new.map(String::from),
+    }
+}
+
+#[test]
+fn changes_between_versions() {
+    let (sdk, _tmp) = setup();
+    let changes = sdk.diff().changes(OLD, NEW).unwrap();
+    assert_eq!(
+        changes,
+        vec![
+            entry(ChangeKind::SetAdded, "NEW", None, None, Some("New Set")),
+            entry(ChangeKind::SetRemoved, "OLD", None, Some("Old Set"), None),
+            entry(ChangeKind::LegalityChanged, "c1", Some("modern"), Some("Legal"), Some("Banned")),
+            entry(ChangeKind::IdentifierAdded, "c1", Some("mtgoId"), None, Some("mo-1")),
+            entry(
+                ChangeKind::TextChanged,
+                "c1",
+                Some("text"),
+                Some("Deal 3 damage."),
+                Some("Lightning Bolt deals 3 damage to any target."),
+            ),
+            entry(ChangeKind::LegalityChanged, "c2", Some("legacy"), None, Some("Legal")),
+            entry(ChangeKind::IdentifierAdded, "c2", Some("scryfallId"), None, Some("sf-2")),
+            entry(ChangeKind::CardRemoved, "c3", None, Some("Old Card"), None),
+            entry(ChangeKind::CardAdded, "c4", None, None, Some("New Card")),
+        ]
+    );
+}
+
+#[test]
+fn identical_versions_have_no_changes() {
+    let (sdk, _tmp) = setup();
+    assert!(sdk.diff().changes(NEW, NEW).unwrap().is_empty());
+}
+
+#[test]
+fn changes_as_ndjson() {
+    let (sdk, _tmp) = setup();
+    let ndjson = sdk.diff().changes_ndjson(OLD, NEW).unwrap();
+    let lines: Vec<serde_json::Value> = ndjson
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 9);
+    assert_eq!(lines[0]["kind"], "set_added");
+    assert_eq!(lines[0]["key"], "NEW");
+    assert!(lines[0]["field"].is_null());
+    assert_eq!(lines[8]["kind"], "card_added");
+}
+
+#[test]
+fn uncached_version_is_not_found() {
+    let (sdk, _tmp) = setup();
+    let err = sdk.diff().changes("5.1.0+20230101", NEW).unwrap_err();
+    assert!(matches!(err, MtgjsonError::NotFound(_)), "{}", err);
+}
+
+#[test]
+fn concurrent_diffs_use_their_own_schemas() {
+    let (sdk, _tmp) = setup();
+    std::thread::scope(|s| {
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                let sdk = &sdk;
+                s.spawn(move || {
+                    for _ in 0..5 {
+                        let changes = if i % 2 == 0 {
+                            sdk.diff().changes(OLD, NEW).unwrap()
+                        } else {
+                            sdk.diff().changes(NEW, NEW).unwrap()
+                        };
+                        assert_eq!(changes.len(), if i % 2 == 0 { 9 } else { 0 });
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    });
+
+    let schemas = sdk
+        .sql(
+            "SELECT schema_name FROM information_schema.schemata \
+             WHERE schema_name LIKE 'diff_%'",
+            &[],
+        )
+        .unwrap();
+    assert!(schemas.is_empty(), "{:?}", schemas);
+}
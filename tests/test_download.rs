@@ -0,0 +1,151 @@
+//! Streamed, resumable and retrying downloads, against a local HTTP
+//! stand-in that drops connections midway.
+
+#[allow(dead_code)]
+mod common;
+
+use common::cdn::{cache_for, Cdn};
+use mtgjson_sdk::{DownloadProgress, MtgjsonError};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::sync::{Arc, Mutex};
+
+/// A ~200 KB JSON file, large enough to be cut off midway.
+fn large_json() -> Vec<u8> {
+    let words: Vec<String> = (0..20_000).map(|i| format!("word{:05}", i)).collect();
+    serde_json::to_vec(&serde_json::json!({ "data": { "abilityWords": words } })).unwrap()
+}
+
+fn start(body: &[u8]) -> Cdn {
+    Cdn::start(vec![
+        ("Keywords.json", body.to_vec()),
+        (
+            "Keywords.json.sha256",
+            format!("{:x}", Sha256::digest(body)).into_bytes(),
+        ),
+    ])
+}
+
+fn ranges(cdn: &Cdn) -> Vec<Option<String>> {
+    cdn.requests("Keywords.json")
+        .into_iter()
+        .map(|r| r.headers.get("range").cloned())
+        .collect()
+}
+
+#[test]
+fn dropped_connection_is_resumed_with_range() {
+    let body = large_json();
+    let cdn = start(&body);
+    cdn.cut("Keywords.json", 1, 50_000);
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cache = cache_for(tmp.path(), &cdn);
+    cache.max_retries = 2;
+
+    let path = cache.ensure_json("keywords").unwrap();
+    assert_eq!(fs::read(path).unwrap(), body);
+    assert_eq!(ranges(&cdn), vec![None, Some("bytes=50000-".to_string())]);
+    assert!(!tmp.path().join("Keywords.json.tmp").exists());
+}
+
+#[test]
+fn gives_up_after_max_retries_and_keeps_partial_file() {
+    let body = large_json();
+    let cdn = start(&body);
+    cdn.cut("Keywords.json", 3, 0);
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cache = cache_for(tmp.path(), &cdn);
+    cache.max_retries = 2;
+
+    let err = cache.ensure_json("keywords").unwrap_err();
+    assert!(
+        matches!(err, MtgjsonError::Http(_) | MtgjsonError::Io(_)),
+        "{:?}",
+        err
+    );
+    assert_eq!(cdn.requests("Keywords.json").len(), 3);
+    assert!(!tmp.path().join("Keywords.json").exists());
+
+    // A later call picks up where the connection dropped
+    cache.max_retries = 0;
+    cdn.cut("Keywords.json", 1, 70_000);
+    cache.ensure_json("keywords").unwrap_err();
+    cache.ensure_json("keywords").unwrap();
+    assert_eq!(
+        ranges(&cdn)[3..],
+        [None, Some("bytes=70000-".to_string())]
+    );
+}
+
+#[test]
+fn leftover_temp_file_is_resumed() {
+    let body = large_json();
+    let cdn = start(&body);
+    let tmp = tempfile::tempdir().unwrap();
+    fs::write(tmp.path().join("Keywords.json.tmp"), &body[..1234]).unwrap();
+    let mut cache = cache_for(tmp.path(), &cdn);
+    cache.max_retries = 0;
+
+    let path = cache.ensure_json("keywords").unwrap();
+    assert_eq!(fs::read(path).unwrap(), body);
+    assert_eq!(ranges(&cdn), vec![Some("bytes=1234-".to_string())]);
+}
+
+#[test]
+fn stale_temp_file_is_discarded() {
+    let body = large_json();
+    let cdn = start(&body);
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cache = cache_for(tmp.path(), &cdn);
+    cache.max_retries = 0;
+
+    // Not a prefix of the current file: fails the checksum, then restarts
+    fs::write(tmp.path().join("Keywords.json.tmp"), vec![b'x'; 1000]).unwrap();
+    cache.ensure_json("keywords").unwrap();
+    assert_eq!(ranges(&cdn), vec![Some("bytes=1000-".to_string()), None]);
+
+    // Longer than the current file: 416, then restarts
+    fs::remove_file(tmp.path().join("Keywords.json")).unwrap();
+    fs::write(tmp.path().join("Keywords.json.tmp"), vec![b'x'; body.len() + 1]).unwrap();
+    let path = cache.ensure_json("keywords").unwrap();
+    assert_eq!(fs::read(path).unwrap(), body);
+    assert_eq!(ranges(&cdn)[2..], [Some(format!("bytes={}-", body.len() + 1)), None]);
+}
+
+#[test]
+fn client_errors_are_not_retried() {
+    let cdn = Cdn::start(vec![]);
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cache = cache_for(tmp.path(), &cdn);
+    cache.max_retries = 3;
+
+    let err = cache.ensure_json("keywords").unwrap_err();
+    assert!(matches!(err, MtgjsonError::Http(_)), "{:?}", err);
+    assert_eq!(cdn.requests("Keywords.json").len(), 1);
+}
+
+#[test]
+fn progress_is_reported_while_downloading() {
+    let body = large_json();
+    let cdn = start(&body);
+    cdn.cut("Keywords.json", 1, 50_000);
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cache = cache_for(tmp.path(), &cdn);
+    cache.max_retries = 2;
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&reports);
+    cache.download_progress = Some(Arc::new(move |p: &DownloadProgress<'_>| {
+        sink.lock()
+            .unwrap()
+            .push((p.file.to_string(), p.downloaded, p.total));
+    }));
+
+    cache.ensure_json("keywords").unwrap();
+    let reports = reports.lock().unwrap();
+    let total = Some(body.len() as u64);
+    assert!(reports.iter().all(|(file, _, t)| file == "Keywords.json" && *t == total));
+    assert_eq!(reports.first().unwrap().1, 0);
+    // The retry picks up where the dropped connection left off
+    assert!(reports.iter().any(|r| r.1 == 50_000));
+    assert_eq!(reports.last().unwrap().1, body.len() as u64);
+}
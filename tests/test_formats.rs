@@ -0,0 +1,205 @@
+//! Decklist, MTG Arena and collection CSV import/export tests.
+
+mod common;
+
+use mtgjson_sdk::formats::{csv, text, DeckEntry, ImportErrorKind, Section};
+use mtgjson_sdk::models::CollectionEntry;
+use mtgjson_sdk::queries::cards::CardQuery;
+use mtgjson_sdk::MtgjsonError;
+
+fn entry(quantity: i64, name: &str, set: Option<&str>, number: Option<&str>, section: Section) -> DeckEntry {
+    DeckEntry {
+        quantity,
+        name: name.to_string(),
+        set_code: set.map(String::from),
+        number: number.map(String::from),
+        section,
+        uuid: None,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Text parsing
+// ---------------------------------------------------------------------------
+
+#[test]
+fn parse_decklist_lines() {
+    let parsed = text::parse(
+        "4 Lightning Bolt (a25) 141\n\
+         4x Counterspell\n\
+         2 Fire // Ice (MH2) 290 *F*\n\
+         // a comment\n\
+         SB: 1 Pyroblast\n",
+    );
+    assert!(parsed.is_complete());
+    assert_eq!(
+        parsed.entries,
+        vec![
+            entry(4, "Lightning Bolt", Some("A25"), Some("141"), Section::Main),
+            entry(4, "Counterspell", None, None, Section::Main),
+            entry(2, "Fire // Ice", Some("MH2"), Some("290"), Section::Main),
+            entry(1, "Pyroblast", None, None, Section::Sideboard),
+        ]
+    );
+}
+
+#[test]
+fn parse_mtga_sections() {
+    let parsed = text::parse(
+        "About\nName Izzet Tempo\n\n\
+         Commander\n1 Fire // Ice (MH2) 290\n\n\
+         Deck\n4 Lightning Bolt (A25) 141\n\n\
+         Sideboard\n2 Counterspell (A25) 50\n",
+    );
+    assert!(parsed.is_complete());
+    let sections: Vec<_> = parsed.entries.iter().map(|e| e.section).collect();
+    assert_eq!(sections, vec![Section::Commander, Section::Main, Section::Sideboard]);
+}
+
+#[test]
+fn parse_blank_line_starts_sideboard_without_headers() {
+    let parsed = text::parse("4 Lightning Bolt\n\n2 Counterspell\n");
+    assert_eq!(parsed.entries[0].section, Section::Main);
+    assert_eq!(parsed.entries[1].section, Section::Sideboard);
+}
+
+#[test]
+fn parse_reports_malformed_lines() {
+    let parsed = text::parse("4 Lightning Bolt\nLightning Bolt\n0 Counterspell\n");
+    assert_eq!(parsed.entries.len(), 1);
+    assert_eq!(parsed.errors.len(), 2);
+    assert_eq!(parsed.errors[0].line, 2);
+    assert_eq!(parsed.errors[0].text, "Lightning Bolt");
+    assert!(matches!(parsed.errors[1].kind, ImportErrorKind::Malformed(_)));
+}
+
+// ---------------------------------------------------------------------------
+// Text writing
+// ---------------------------------------------------------------------------
+
+#[test]
+fn write_decklist_and_mtga() {
+    let entries = vec![
+        entry(4, "Lightning Bolt", Some("A25"), Some("141"), Section::Main),
+        entry(2, "Counterspell", None, None, Section::Sideboard),
+    ];
+    assert_eq!(
+        text::write_decklist(&entries),
+        "4 Lightning Bolt (A25) 141\n\nSideboard\n2 Counterspell\n"
+    );
+    assert_eq!(
+        text::write_mtga(&entries),
+        "Deck\n4 Lightning Bolt (A25) 141\n\nSideboard\n2 Counterspell\n"
+    );
+    assert_eq!(text::parse(&text::write_mtga(&entries)).entries, entries);
+}
+
+// ---------------------------------------------------------------------------
+// Resolution
+// ---------------------------------------------------------------------------
+
+#[test]
+fn import_resolves_and_reports_unresolved() {
+    let (conn, _tmp) = common::setup_sample_db();
+    let cq = CardQuery::new(&conn);
+
+    let result = text::import(
+        &cq,
+        "4 Lightning Bolt (A25) 141\n\
+         2 Fire // Ice\n\
+         1 Counterspell (MH2)\n\
+         1 Black Lotus\n\
+         nonsense\n",
+    )
+    .unwrap();
+
+    let uuids: Vec<_> = result.entries.iter().map(|e| e.uuid.as_deref().unwrap()).collect();
+    assert_eq!(uuids, vec!["card-uuid-001", "card-uuid-003"]);
+
+    let lines: Vec<_> = result.errors.iter().map(|e| e.line).collect();
+    assert_eq!(lines, vec![3, 4, 5]);
+    assert_eq!(
+        result.errors[0].kind,
+        ImportErrorKind::UnknownPrinting {
+            name: "Counterspell".to_string(),
+            set_code: Some("MH2".to_string()),
+            number: None,
+        }
+    );
+    assert_eq!(
+        result.errors[1].kind,
+        ImportErrorKind::UnknownCard {
+            name: "Black Lotus".to_string()
+        }
+    );
+    assert!(result.errors[1].to_string().starts_with("line 4: unknown card"));
+}
+
+// ---------------------------------------------------------------------------
+// CSV
+// ---------------------------------------------------------------------------
+
+#[test]
+fn csv_import_maps_app_headers() {
+    let (conn, _tmp) = common::setup_sample_db();
+    let cq = CardQuery::new(&conn);
+
+    let result = csv::import_collection(
+        &cq,
+        "Count,Card Name,Set Code,Collector Number,Foil,Condition,Purchase Price\n\
+         3,Lightning Bolt,A25,141,foil,LP,$1.50\n\
+         1,\"Fire // Ice\",mh2,290,,,\n\
+         x,Counterspell,A25,50,,,\n\
+         1,Counterspell,A25,999,,,\n",
+    )
+    .unwrap();
+
+    assert_eq!(result.entries.len(), 2);
+    let bolt = &result.entries[0];
+    assert_eq!(bolt.uuid, "card-uuid-001");
+    assert_eq!(bolt.quantity, 3);
+    assert_eq!(bolt.finish, "foil");
+    assert_eq!(bolt.condition, "LP");
+    assert_eq!(bolt.acquired_price, Some(1.5));
+    assert_eq!(result.entries[1], CollectionEntry::new("card-uuid-003", 1));
+
+    assert_eq!(result.errors.len(), 2);
+    assert_eq!(result.errors[0].line, 4);
+    assert!(matches!(result.errors[0].kind, ImportErrorKind::Malformed(_)));
+    assert!(matches!(
+        result.errors[1].kind,
+        ImportErrorKind::UnknownPrinting { .. }
+    ));
+}
+
+#[test]
+fn csv_requires_name_or_uuid_column() {
+    let (conn, _tmp) = common::setup_sample_db();
+    let cq = CardQuery::new(&conn);
+
+    let err = csv::import_collection(&cq, "Quantity,Set\n1,A25\n").unwrap_err();
+    assert!(matches!(err, MtgjsonError::InvalidArgument(_)));
+}
+
+#[test]
+fn csv_export_round_trips() {
+    let (conn, _tmp) = common::setup_sample_db();
+    let cq = CardQuery::new(&conn);
+
+    let entries = vec![
+        CollectionEntry {
+            finish: "foil".to_string(),
+            acquired_price: Some(2.25),
+            acquired_date: Some("2024-03-01".to_string()),
+            ..CollectionEntry::new("card-uuid-003", 2)
+        },
+        CollectionEntry::new("card-uuid-002", 1),
+    ];
+    let out = csv::export_collection(&cq, &entries).unwrap();
+    assert!(out.starts_with("Quantity,Name,Set Code,Collector Number,"));
+    assert!(out.contains("2,Fire // Ice,MH2,290,foil,NM,English,2.25,2024-03-01,card-uuid-003"));
+
+    let reimported = csv::import_collection(&cq, &out).unwrap();
+    assert!(reimported.is_complete());
+    assert_eq!(reimported.entries, entries);
+}
@@ -3,6 +3,7 @@
 mod common;
 
 use mtgjson_sdk::queries::identifiers::IdentifierQuery;
+use mtgjson_sdk::MtgjsonError;
 
 // ---------------------------------------------------------------------------
 // find_by (generic)
@@ -48,6 +49,43 @@ fn find_by_invalid_column_returns_error() {
     assert!(err_msg.contains("Unknown identifier column"));
 }
 
+#[test]
+fn find_by_column_is_case_insensitive() {
+    let (conn, _tmp) = common::setup_sample_db();
+    let iq = IdentifierQuery::new(&conn);
+
+    for column in ["SCRYFALLID", "scryfallid"] {
+        let results = iq.find_by(column, "scryfall-001").unwrap();
+        assert_eq!(results.len(), 1, "{}", column);
+        assert_eq!(results[0]["name"], "Lightning Bolt");
+    }
+    match iq.find_by("UUID", "card-uuid-001") {
+        Err(MtgjsonError::InvalidArgument(_)) => {}
+        other => panic!("expected InvalidArgument, got {:?}", other),
+    }
+}
+
+#[test]
+fn find_by_rejects_injected_columns() {
+    let (conn, _tmp) = common::setup_sample_db();
+    let iq = IdentifierQuery::new(&conn);
+
+    for column in [
+        "scryfallId = scryfallId OR 1=1 --",
+        "scryfallId; DROP TABLE cards; --",
+        "\"scryfallId\"",
+        "ci.scryfallId",
+        "uuid",
+    ] {
+        match iq.find_by(column, "x") {
+            Err(MtgjsonError::InvalidArgument(_)) => {}
+            other => panic!("expected InvalidArgument for {:?}, got {:?}", column, other),
+        }
+    }
+    let count = conn.execute_scalar("SELECT COUNT(*) FROM cards", &[]).unwrap();
+    assert_eq!(count, Some(serde_json::json!(3)));
+}
+
 // ---------------------------------------------------------------------------
 // get_identifiers
 // ---------------------------------------------------------------------------
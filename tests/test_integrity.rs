@@ -0,0 +1,207 @@
+//! SHA-256 verification of downloads and cached files, against a local
+//! HTTP stand-in for the CDN.
+
+#[allow(dead_code)]
+mod common;
+
+use common::cdn::{cache_for, meta, parquet, serve, Cdn};
+use mtgjson_sdk::{MtgjsonError, MtgjsonSdk};
+use sha2::{Digest, Sha256};
+use std::fs;
+
+fn sha256(data: &[u8]) -> Vec<u8> {
+    format!("{:x}  file\n", Sha256::digest(data)).into_bytes()
+}
+
+const META: &[u8] = br#"{"data": {"version": "5.2.2+20240101"}}"#;
+const KEYWORDS: &[u8] = br#"{"data": {"abilityWords": ["Landfall"]}}"#;
+
+// ---------------------------------------------------------------------------
+// Downloads
+// ---------------------------------------------------------------------------
+
+#[test]
+fn download_checks_and_saves_checksum() {
+    let cdn = Cdn::start(vec![
+        ("Meta.json", META.to_vec()),
+        ("Keywords.json", KEYWORDS.to_vec()),
+        ("Keywords.json.sha256", sha256(KEYWORDS)),
+    ]);
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cache = cache_for(tmp.path(), &cdn);
+
+    let data = cache.load_json("keywords").unwrap();
+    assert_eq!(data["data"]["abilityWords"][0], "Landfall");
+    let saved = fs::read_to_string(tmp.path().join("Keywords.json.sha256")).unwrap();
+    assert_eq!(saved.as_bytes(), &sha256(KEYWORDS)[..64]);
+    assert_eq!(
+        fs::read_to_string(tmp.path().join("version.txt")).unwrap(),
+        "5.2.2+20240101"
+    );
+}
+
+#[test]
+fn download_rejects_checksum_mismatch() {
+    let cdn = Cdn::start(vec![
+        ("Meta.json", META.to_vec()),
+        ("Keywords.json", b"<html>Sign in to Wi-Fi</html>".to_vec()),
+        ("Keywords.json.sha256", sha256(KEYWORDS)),
+    ]);
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cache = cache_for(tmp.path(), &cdn);
+
+    let err = cache.ensure_json("keywords").unwrap_err();
+    assert!(matches!(err, MtgjsonError::Integrity(_)), "{:?}", err);
+    assert!(!tmp.path().join("Keywords.json").exists());
+    assert!(!tmp.path().join("Keywords.json.tmp").exists());
+}
+
+#[test]
+fn download_rejects_unparseable_checksum() {
+    let cdn = Cdn::start(vec![
+        ("Keywords.json", KEYWORDS.to_vec()),
+        ("Keywords.json.sha256", b"<html>proxy error</html>".to_vec()),
+    ]);
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cache = cache_for(tmp.path(), &cdn);
+
+    let err = cache.ensure_json("keywords").unwrap_err();
+    assert!(matches!(err, MtgjsonError::Integrity(_)), "{:?}", err);
+}
+
+#[test]
+fn download_without_published_checksum_is_kept_unchecked() {
+    let cdn = Cdn::start(vec![("Keywords.json", KEYWORDS.to_vec())]);
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cache = cache_for(tmp.path(), &cdn);
+
+    cache.ensure_json("keywords").unwrap();
+    assert!(!tmp.path().join("Keywords.json.sha256").exists());
+}
+
+// ---------------------------------------------------------------------------
+// verify()
+// ---------------------------------------------------------------------------
+
+#[test]
+fn verify_reports_and_evicts_corrupt_files() {
+    let cdn = Cdn::start(vec![
+        ("Keywords.json", KEYWORDS.to_vec()),
+        ("Keywords.json.sha256", sha256(KEYWORDS)),
+        ("Meta.json", META.to_vec()),
+        ("Meta.json.sha256", sha256(META)),
+    ]);
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cache = cache_for(tmp.path(), &cdn);
+    cache.ensure_json("keywords").unwrap();
+    cache.ensure_json("meta").unwrap();
+
+    // Truncated after the fact, e.g. by a full disk
+    fs::write(tmp.path().join("Keywords.json"), &KEYWORDS[..10]).unwrap();
+
+    let report = cache.verify(false).unwrap();
+    assert!(!report.is_ok());
+    assert_eq!(report.corrupt, vec!["Keywords.json"]);
+    assert_eq!(report.verified, vec!["Meta.json"]);
+    assert!(tmp.path().join("Keywords.json").exists());
+
+    let report = cache.verify(true).unwrap();
+    assert!(report.evicted);
+    assert_eq!(report.corrupt, vec!["Keywords.json"]);
+    assert!(!tmp.path().join("Keywords.json").exists());
+    assert!(!tmp.path().join("Keywords.json.sha256").exists());
+
+    // The next access downloads a good copy again
+    cache.ensure_json("keywords").unwrap();
+    assert!(cache.verify(false).unwrap().is_ok());
+}
+
+#[test]
+fn verify_fetches_missing_checksums_unless_offline() {
+    let cdn = Cdn::start(vec![
+        ("Meta.json", META.to_vec()),
+        ("Keywords.json", KEYWORDS.to_vec()),
+    ]);
+    let tmp = tempfile::tempdir().unwrap();
+    cache_for(tmp.path(), &cdn).ensure_json("keywords").unwrap();
+    assert!(!tmp.path().join("Keywords.json.sha256").exists());
+    cdn.set("Keywords.json.sha256", sha256(KEYWORDS));
+
+    let mut offline = cache_for(tmp.path(), &cdn);
+    offline.offline = true;
+    let report = offline.verify(false).unwrap();
+    assert_eq!(report.unchecked, vec!["Keywords.json"]);
+    assert!(report.verified.is_empty());
+
+    let report = cache_for(tmp.path(), &cdn).verify(false).unwrap();
+    assert_eq!(report.verified, vec!["Keywords.json"]);
+    assert!(report.unchecked.is_empty());
+    assert!(tmp.path().join("Keywords.json.sha256").exists());
+}
+
+#[test]
+fn verify_skips_published_checksums_of_other_versions() {
+    // Cached for an older version; the CDN now publishes a newer file
+    let tmp = tempfile::tempdir().unwrap();
+    fs::create_dir_all(tmp.path().join("decks")).unwrap();
+    fs::write(tmp.path().join("decks/Starter_M10.json"), "{}").unwrap();
+    fs::write(tmp.path().join("version.txt"), "5.2.1+20231201").unwrap();
+
+    let cdn = Cdn::start(vec![
+        ("Meta.json", META.to_vec()),
+        ("decks/Starter_M10.json.sha256", sha256(b"{\"data\": {}}")),
+    ]);
+    let report = cache_for(tmp.path(), &cdn).verify(true).unwrap();
+    assert_eq!(report.unchecked, vec!["decks/Starter_M10.json"]);
+    assert!(report.corrupt.is_empty());
+    assert!(tmp.path().join("decks/Starter_M10.json").exists());
+    assert!(!tmp.path().join("decks/Starter_M10.json.sha256").exists());
+}
+
+#[test]
+fn verify_evict_forgets_manifest_records() {
+    let cdn = Cdn::start(vec![
+        ("Meta.json", META.to_vec()),
+        ("Keywords.json", KEYWORDS.to_vec()),
+        ("Keywords.json.sha256", sha256(KEYWORDS)),
+    ]);
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cache = cache_for(tmp.path(), &cdn);
+    cache.ensure_json("keywords").unwrap();
+    assert!(cache.manifest().files.contains_key("Keywords.json"));
+
+    fs::write(tmp.path().join("Keywords.json"), &KEYWORDS[..10]).unwrap();
+    cache.verify(true).unwrap();
+    assert!(!cache.manifest().files.contains_key("Keywords.json"));
+}
+
+#[test]
+fn verify_keeps_corrupt_files_backing_views() {
+    let cards = parquet("SELECT 'card-1' AS uuid, 'Lightning Bolt' AS name");
+    let base = serve(vec![
+        ("Meta.json", meta("5.2.2+20240101")),
+        ("parquet/cards.parquet.sha256", sha256(&cards)),
+        ("parquet/cards.parquet", cards),
+    ]);
+    let tmp = tempfile::tempdir().unwrap();
+    let sdk = MtgjsonSdk::builder()
+        .cache_dir(tmp.path())
+        .cdn_base(base)
+        .build()
+        .unwrap();
+    sdk.connection().unwrap().ensure_views(&["cards"]).unwrap();
+    let path = tmp.path().join("parquet/cards.parquet");
+    let mut corrupt = fs::read(&path).unwrap();
+    corrupt[0] ^= 0xff;
+    fs::write(&path, corrupt).unwrap();
+
+    let report = sdk.connection().unwrap().cache().verify(true).unwrap();
+    assert_eq!(report.corrupt, vec!["parquet/cards.parquet"]);
+    assert_eq!(report.in_use, vec!["parquet/cards.parquet"]);
+    assert!(path.exists());
+
+    sdk.connection().unwrap().reset_views();
+    let report = sdk.connection().unwrap().cache().verify(true).unwrap();
+    assert!(report.in_use.is_empty());
+    assert!(!path.exists());
+}
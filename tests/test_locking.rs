@@ -0,0 +1,145 @@
+//! Cache locking between processes sharing one cache directory.
+//!
+//! Each test spawns this test binary again as child processes, running one of
+//! the `child_*` tests with [`CHILD_ENV`] set. Without it the `child_*` tests
+//! do nothing.
+
+#[allow(dead_code)]
+mod common;
+
+use common::cdn::Cdn;
+use mtgjson_sdk::{CacheManager, MtgjsonError, MtgjsonSdk};
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+const CHILD_ENV: &str = "MTGJSON_TEST_CHILD";
+const DIR_ENV: &str = "MTGJSON_TEST_CACHE_DIR";
+const CDN_ENV: &str = "MTGJSON_TEST_CDN";
+const READY_ENV: &str = "MTGJSON_TEST_READY";
+
+fn spawn(test: &str, vars: &[(&str, &str)]) -> Child {
+    Command::new(env::current_exe().unwrap())
+        .args(["--exact", test, "--nocapture"])
+        .env(CHILD_ENV, "1")
+        .envs(vars.iter().copied())
+        .stdout(Stdio::null())
+        .spawn()
+        .unwrap()
+}
+
+fn write_cards(dir: &Path) {
+    let parquet = dir.join("parquet");
+    fs::create_dir_all(&parquet).unwrap();
+    let path = parquet.join("cards.parquet");
+    duckdb::Connection::open_in_memory()
+        .unwrap()
+        .execute_batch(&format!(
+            "COPY (SELECT 'card-1' AS uuid, 'Lightning Bolt' AS name) TO '{}' (FORMAT PARQUET)",
+            path.to_string_lossy().replace('\\', "/")
+        ))
+        .unwrap();
+}
+
+#[test]
+fn child_download() {
+    if env::var(CHILD_ENV).is_err() {
+        return;
+    }
+    let dir = env::var(DIR_ENV).unwrap();
+    let mut cache = CacheManager::new(Some(dir.into()), false, Duration::from_secs(30)).unwrap();
+    cache.cdn_base = env::var(CDN_ENV).unwrap();
+    cache.ensure_json("keywords").unwrap();
+}
+
+#[test]
+fn child_hold_views() {
+    if env::var(CHILD_ENV).is_err() {
+        return;
+    }
+    let sdk = MtgjsonSdk::builder()
+        .cache_dir(env::var(DIR_ENV).unwrap())
+        .offline(true)
+        .build()
+        .unwrap();
+    sdk.connection().unwrap().ensure_views(&["cards"]).unwrap();
+    fs::write(env::var(READY_ENV).unwrap(), "").unwrap();
+    std::thread::sleep(Duration::from_secs(2));
+}
+
+#[test]
+fn concurrent_processes_download_once() {
+    let body = format!(
+        r#"{{"data": {{"abilityWords": [{}]}}}}"#,
+        vec![r#""Landfall""#; 100_000].join(",")
+    );
+    let cdn = Cdn::start(vec![
+        ("Meta.json", br#"{"data": {"version": "5.2.2+20240101"}}"#.to_vec()),
+        ("Keywords.json", body.into_bytes()),
+    ]);
+    let tmp = tempfile::tempdir().unwrap();
+    let dir = tmp.path().to_str().unwrap();
+
+    let children: Vec<Child> = (0..4)
+        .map(|_| spawn("child_download", &[(DIR_ENV, dir), (CDN_ENV, &cdn.base)]))
+        .collect();
+    for mut child in children {
+        assert!(child.wait().unwrap().success());
+    }
+
+    assert_eq!(cdn.requests("Keywords.json").len(), 1);
+    let cache = CacheManager::new(Some(tmp.path().into()), true, Duration::from_secs(10)).unwrap();
+    assert!(cache.manifest().files.contains_key("Keywords.json"));
+}
+
+#[test]
+fn clear_waits_for_views_in_other_processes() {
+    let tmp = tempfile::tempdir().unwrap();
+    write_cards(tmp.path());
+    let cards = tmp.path().join("parquet/cards.parquet");
+    let marker = tempfile::tempdir().unwrap();
+    let ready = marker.path().join("ready");
+
+    let mut child = spawn(
+        "child_hold_views",
+        &[
+            (DIR_ENV, tmp.path().to_str().unwrap()),
+            (READY_ENV, ready.to_str().unwrap()),
+        ],
+    );
+    let start = Instant::now();
+    while !ready.exists() {
+        assert!(start.elapsed() < Duration::from_secs(60), "child never registered its view");
+        std::thread::sleep(Duration::from_millis(20));
+    }
+
+    let mut cache = CacheManager::new(Some(tmp.path().into()), true, Duration::from_secs(10)).unwrap();
+    cache.lock_timeout = Duration::from_millis(100);
+    assert!(matches!(cache.clear(), Err(MtgjsonError::LockTimeout(_))));
+    assert!(cards.exists());
+
+    // Succeeds once the child exits and releases its views
+    cache.lock_timeout = Duration::from_secs(60);
+    cache.clear().unwrap();
+    assert!(child.wait().unwrap().success());
+    assert!(!cards.exists());
+}
+
+#[test]
+fn own_views_do_not_block_clear() {
+    let tmp = tempfile::tempdir().unwrap();
+    write_cards(tmp.path());
+    let sdk = MtgjsonSdk::builder()
+        .cache_dir(tmp.path())
+        .offline(true)
+        .lock_timeout(Duration::from_millis(100))
+        .build()
+        .unwrap();
+    sdk.connection().unwrap().ensure_views(&["cards"]).unwrap();
+
+    sdk.connection().unwrap().cache().clear().unwrap();
+    assert!(!tmp.path().join("parquet/cards.parquet").exists());
+    assert!(tmp.path().join("locks").exists());
+}
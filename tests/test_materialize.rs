@@ -0,0 +1,121 @@
+//! Views materialized into a persistent database per MTGJSON version,
+//! against a local HTTP stand-in for the CDN.
+
+#[allow(dead_code)]
+mod common;
+
+use common::cdn::{meta, parquet, Cdn};
+use mtgjson_sdk::MtgjsonSdk;
+use std::fs;
+use std::path::Path;
+
+const CARDS: &str = "parquet/cards.parquet";
+
+fn start() -> Cdn {
+    Cdn::start(vec![
+        ("Meta.json", meta("5.2.2+20240101")),
+        (
+            CARDS,
+            parquet("SELECT 'card-1' AS uuid, 'Lightning Bolt' AS name, 'A25' AS setCode"),
+        ),
+    ])
+}
+
+fn build(dir: &Path, cdn: &Cdn) -> MtgjsonSdk {
+    MtgjsonSdk::builder()
+        .cache_dir(dir)
+        .cdn_base(cdn.base.clone())
+        .materialize(true)
+        .build()
+        .unwrap()
+}
+
+fn names(sdk: &MtgjsonSdk) -> Vec<serde_json::Value> {
+    sdk.connection().unwrap().ensure_views(&["cards"]).unwrap();
+    sdk.sql("SELECT name FROM cards ORDER BY name", &[])
+        .unwrap()
+        .into_iter()
+        .map(|row| row["name"].clone())
+        .collect()
+}
+
+#[test]
+fn views_are_materialized_and_reopened() {
+    let cdn = start();
+    let tmp = tempfile::tempdir().unwrap();
+    let database = tmp.path().join("databases/5.2.2+20240101.duckdb");
+
+    let sdk = build(tmp.path(), &cdn);
+    assert_eq!(names(&sdk), vec!["Lightning Bolt"]);
+    let indexes = sdk
+        .sql(
+            "SELECT index_name FROM duckdb_indexes() WHERE table_name = 'cards' \
+             ORDER BY index_name",
+            &[],
+        )
+        .unwrap();
+    let indexes: Vec<_> = indexes.iter().map(|r| r["index_name"].clone()).collect();
+    assert_eq!(indexes, vec!["cards_name_idx", "cards_setCode_idx", "cards_uuid_idx"]);
+    sdk.close();
+    assert!(database.exists());
+
+    // The next run reads the database, not the parquet file or the CDN
+    fs::remove_file(tmp.path().join(CARDS)).unwrap();
+    let sdk = MtgjsonSdk::builder()
+        .cache_dir(tmp.path())
+        .offline(true)
+        .materialize(true)
+        .build()
+        .unwrap();
+    assert_eq!(names(&sdk), vec!["Lightning Bolt"]);
+    assert_eq!(cdn.requests(CARDS).len(), 1);
+}
+
+#[test]
+fn refresh_builds_a_database_for_the_new_version() {
+    let cdn = start();
+    let tmp = tempfile::tempdir().unwrap();
+    let sdk = build(tmp.path(), &cdn);
+    assert_eq!(names(&sdk), vec!["Lightning Bolt"]);
+
+    cdn.set("Meta.json", meta("5.2.3+20240201"));
+    cdn.set(
+        CARDS,
+        parquet(
+            "SELECT * FROM (VALUES ('card-1', 'Lightning Bolt', 'A25'), \
+             ('card-2', 'Counterspell', 'A25')) t(uuid, name, setCode)",
+        ),
+    );
+    assert!(sdk.refresh().unwrap());
+    assert_eq!(names(&sdk), vec!["Counterspell", "Lightning Bolt"]);
+
+    let databases = tmp.path().join("databases");
+    assert!(databases.join("5.2.3+20240201.duckdb").exists());
+    assert!(!databases.join("5.2.2+20240101.duckdb").exists());
+}
+
+#[test]
+fn materialized_database_counts_towards_the_quota() {
+    let cdn = start();
+    let tmp = tempfile::tempdir().unwrap();
+    let sdk = build(tmp.path(), &cdn);
+    names(&sdk);
+
+    let stats = sdk.cache_stats().unwrap();
+    let database = stats
+        .files
+        .iter()
+        .find(|f| f.path == "databases/5.2.2+20240101.duckdb")
+        .unwrap();
+    assert!(database.in_use && !database.archived);
+    assert_eq!(database.version.as_deref(), Some("5.2.2+20240101"));
+    assert!(database.size > 0);
+    assert!(stats.total_size >= database.size);
+
+    // Attached, so kept even when everything else goes
+    let mut cache = sdk.connection().unwrap().cache();
+    cache.quota = Some(1);
+    let evicted = cache.enforce_quota().unwrap();
+    assert!(!evicted.iter().any(|f| f.starts_with("databases/")));
+    assert!(tmp.path().join("databases/5.2.2+20240101.duckdb").exists());
+}
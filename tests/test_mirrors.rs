@@ -0,0 +1,97 @@
+//! CDN base URL and mirror fallback tests, against local HTTP stand-ins.
+
+#[allow(dead_code)]
+mod common;
+
+use common::cdn::{serve, unreachable};
+use mtgjson_sdk::{CacheManager, MtgjsonError, MtgjsonSdk};
+use std::fs;
+use std::time::Duration;
+
+const META: &[u8] = br#"{"data": {"version": "5.2.2+20240101"}}"#;
+const KEYWORDS: &[u8] = br#"{"data": {"abilityWords": ["Landfall"]}}"#;
+
+fn cache_with(dir: &std::path::Path, base: String, mirrors: Vec<String>) -> CacheManager {
+    let mut cache =
+        CacheManager::new(Some(dir.to_path_buf()), false, Duration::from_secs(10)).unwrap();
+    cache.cdn_base = base;
+    cache.mirrors = mirrors;
+    cache.retry_backoff = Duration::from_millis(1);
+    cache
+}
+
+#[test]
+fn falls_back_to_mirror_when_base_is_down() {
+    let mirror = serve(vec![
+        ("Meta.json", META.to_vec()),
+        ("Keywords.json", KEYWORDS.to_vec()),
+    ]);
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cache = cache_with(tmp.path(), unreachable(), vec![format!("{}/", mirror)]);
+
+    assert_eq!(
+        cache.remote_version().unwrap().as_deref(),
+        Some("5.2.2+20240101")
+    );
+    let data = cache.load_json("keywords").unwrap();
+    assert_eq!(data["data"]["abilityWords"][0], "Landfall");
+}
+
+#[test]
+fn mirrors_are_tried_in_order() {
+    // The base is missing the file and the first mirror serves a corrupt copy
+    let base = serve(vec![("Meta.json", META.to_vec())]);
+    let bad = serve(vec![
+        ("Keywords.json", b"truncated".to_vec()),
+        (
+            "Keywords.json.sha256",
+            b"0000000000000000000000000000000000000000000000000000000000000000".to_vec(),
+        ),
+    ]);
+    let good = serve(vec![("Keywords.json", KEYWORDS.to_vec())]);
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cache = cache_with(tmp.path(), base, vec![bad, good]);
+
+    let path = cache.ensure_json("keywords").unwrap();
+    assert_eq!(fs::read(path).unwrap(), KEYWORDS);
+}
+
+#[test]
+fn last_error_is_returned_when_all_fail() {
+    let bad = serve(vec![
+        ("Keywords.json", b"truncated".to_vec()),
+        (
+            "Keywords.json.sha256",
+            b"0000000000000000000000000000000000000000000000000000000000000000".to_vec(),
+        ),
+    ]);
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cache = cache_with(tmp.path(), unreachable(), vec![bad]);
+
+    let err = cache.ensure_json("keywords").unwrap_err();
+    assert!(matches!(err, MtgjsonError::Integrity(_)), "{:?}", err);
+    assert_eq!(cache.remote_version().unwrap(), None);
+}
+
+#[test]
+fn builder_sets_base_and_mirrors() {
+    let mirror = serve(vec![("Meta.json", META.to_vec())]);
+    let down = unreachable();
+    let tmp = tempfile::tempdir().unwrap();
+
+    let sdk = MtgjsonSdk::builder()
+        .cache_dir(tmp.path())
+        .cdn_base(down.clone())
+        .mirrors([mirror.clone()])
+        .retry_backoff(Duration::from_millis(1))
+        .build()
+        .unwrap();
+    {
+        let cache = sdk.connection().unwrap().cache();
+        assert_eq!(cache.cdn_base, down);
+        assert_eq!(cache.mirrors, vec![mirror]);
+    }
+
+    let meta = sdk.meta().unwrap();
+    assert_eq!(meta["data"]["version"], "5.2.2+20240101");
+}
@@ -0,0 +1,99 @@
+//! Concurrent downloads of the files behind several views, against a local
+//! HTTP stand-in for the CDN that answers slowly.
+
+#[allow(dead_code)]
+mod common;
+
+use common::cdn::{cache_for, meta, parquet, Cdn};
+use mtgjson_sdk::{MtgjsonError, MtgjsonSdk};
+use std::time::Duration;
+
+const FILES: [&str; 3] = [
+    "parquet/cards.parquet",
+    "parquet/sets.parquet",
+    "parquet/cardLegalities.parquet",
+];
+
+/// Serve cards, sets and legalities, each answered after a delay.
+fn start() -> Cdn {
+    let cdn = Cdn::start(vec![
+        ("Meta.json", meta("5.2.2+20240101")),
+        (FILES[0], parquet("SELECT 'card-1' AS uuid, 'Lightning Bolt' AS name, 'A25' AS setCode")),
+        (FILES[1], parquet("SELECT 'A25' AS code, 'Masters 25' AS name")),
+        (FILES[2], parquet("SELECT 'card-1' AS uuid, 'Legal' AS modern")),
+    ]);
+    for file in FILES {
+        cdn.delay(file, Duration::from_millis(300));
+    }
+    cdn
+}
+
+#[test]
+fn views_are_downloaded_concurrently() {
+    let cdn = start();
+    let tmp = tempfile::tempdir().unwrap();
+    let sdk = MtgjsonSdk::builder()
+        .cache_dir(tmp.path())
+        .cdn_base(cdn.base.clone())
+        .build()
+        .unwrap();
+
+    sdk.connection()
+        .unwrap()
+        .ensure_views(&["cards", "sets", "card_legalities"])
+        .unwrap();
+    assert_eq!(cdn.max_in_flight(), 3);
+    let rows = sdk
+        .sql(
+            "SELECT s.name AS set_name, l.status FROM cards c \
+             JOIN sets s ON c.setCode = s.code \
+             JOIN card_legalities l ON c.uuid = l.uuid",
+            &[],
+        )
+        .unwrap();
+    assert_eq!(rows[0]["set_name"], "Masters 25");
+    assert_eq!(rows[0]["status"], "Legal");
+
+    let manifest = sdk.connection().unwrap().cache().manifest();
+    for file in FILES {
+        assert_eq!(cdn.requests(file).len(), 1);
+        assert!(manifest.files.contains_key(file));
+    }
+}
+
+#[test]
+fn concurrency_is_limited() {
+    let cdn = start();
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cache = cache_for(tmp.path(), &cdn);
+    cache.download_concurrency = 2;
+
+    let paths = cache
+        .ensure_parquets(&["cards", "sets", "card_legalities"])
+        .unwrap();
+    assert_eq!(cdn.max_in_flight(), 2);
+    for (path, file) in paths.iter().zip(FILES) {
+        assert_eq!(*path, tmp.path().join(file));
+        assert!(path.exists());
+        assert!(!path.with_extension("parquet.tmp").exists());
+    }
+}
+
+#[test]
+fn one_failed_download_does_not_stop_the_others() {
+    let cdn = start();
+    cdn.set(FILES[1], Vec::new());
+    cdn.set(&format!("{}.sha256", FILES[1]), vec![b'0'; 64]);
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cache = cache_for(tmp.path(), &cdn);
+    cache.max_retries = 0;
+
+    let err = cache
+        .ensure_parquets(&["cards", "sets", "card_legalities"])
+        .unwrap_err();
+    assert!(matches!(err, MtgjsonError::Integrity(_)), "{:?}", err);
+    assert!(tmp.path().join(FILES[0]).exists());
+    assert!(!tmp.path().join(FILES[1]).exists());
+    assert!(tmp.path().join(FILES[2]).exists());
+    assert!(cache.manifest().files.contains_key(FILES[2]));
+}
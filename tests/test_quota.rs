@@ -0,0 +1,158 @@
+//! Cache size reports and quota eviction, against a local HTTP stand-in for
+//! the CDN.
+
+#[allow(dead_code)]
+mod common;
+
+use common::cdn::{cache_for, meta, parquet, Cdn};
+use mtgjson_sdk::MtgjsonSdk;
+use std::fs;
+use std::path::Path;
+
+const OLD: &str = "5.2.2+20240101";
+const NEW: &str = "5.2.3+20240201";
+
+/// A JSON file of exactly `size` bytes.
+fn json(size: usize) -> Vec<u8> {
+    let body = format!(r#"{{"data": {{"x": "{}"}}}}"#, "a".repeat(size - 19));
+    assert_eq!(body.len(), size);
+    body.into_bytes()
+}
+
+fn start() -> Cdn {
+    Cdn::start(vec![
+        ("Meta.json", meta(OLD)),
+        ("Keywords.json", json(1000)),
+        ("CardTypes.json", json(2000)),
+        ("EnumValues.json", json(3000)),
+    ])
+}
+
+/// Overwrite the recorded last access time of `filename`.
+fn set_last_accessed(dir: &Path, filename: &str, at: u64) {
+    let path = dir.join("cache_manifest.json");
+    let mut manifest: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    manifest["files"][filename]["last_accessed"] = at.into();
+    fs::write(path, manifest.to_string()).unwrap();
+}
+
+#[test]
+fn stats_report_cached_files() {
+    let cdn = start();
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cache = cache_for(tmp.path(), &cdn);
+    cache.ensure_json("keywords").unwrap();
+    cache.ensure_json("card_types").unwrap();
+
+    let stats = cache.stats().unwrap();
+    let paths: Vec<&str> = stats.files.iter().map(|f| f.path.as_str()).collect();
+    assert_eq!(paths, ["CardTypes.json", "Keywords.json"]);
+    assert_eq!(stats.total_size, 3000);
+    assert_eq!(stats.quota, None);
+
+    let keywords = &stats.files[1];
+    assert_eq!(keywords.size, 1000);
+    assert_eq!(keywords.version.as_deref(), Some(OLD));
+    assert!(!keywords.archived && !keywords.in_use);
+    assert!(keywords.downloaded_at.is_some());
+    assert!(keywords.last_accessed >= keywords.downloaded_at);
+}
+
+#[test]
+fn least_recently_used_files_are_evicted() {
+    let cdn = start();
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cache = cache_for(tmp.path(), &cdn);
+    cache.ensure_json("keywords").unwrap();
+    cache.ensure_json("card_types").unwrap();
+    set_last_accessed(tmp.path(), "Keywords.json", 200);
+    set_last_accessed(tmp.path(), "CardTypes.json", 100);
+
+    // Room for EnumValues.json and one more file
+    cache.quota = Some(5500);
+    cache.ensure_json("enum_values").unwrap();
+    assert!(!tmp.path().join("CardTypes.json").exists());
+    assert!(tmp.path().join("Keywords.json").exists());
+    assert!(tmp.path().join("EnumValues.json").exists());
+    assert!(!cache.manifest().files.contains_key("CardTypes.json"));
+    assert_eq!(cache.stats().unwrap().total_size, 4000);
+
+    // With no file to keep, everything not in use goes, least recently used first
+    cache.quota = Some(100);
+    assert_eq!(cache.enforce_quota().unwrap(), ["Keywords.json", "EnumValues.json"]);
+    assert_eq!(cache.stats().unwrap().total_size, 0);
+}
+
+#[test]
+fn files_backing_views_are_not_evicted() {
+    let cdn = start();
+    cdn.set(
+        "parquet/cards.parquet",
+        parquet("SELECT 'card-1' AS uuid, 'Lightning Bolt' AS name"),
+    );
+    let tmp = tempfile::tempdir().unwrap();
+    let sdk = MtgjsonSdk::builder()
+        .cache_dir(tmp.path())
+        .cdn_base(cdn.base.clone())
+        .cache_quota(1)
+        .build()
+        .unwrap();
+    let cards = tmp.path().join("parquet/cards.parquet");
+    sdk.connection().unwrap().ensure_views(&["cards"]).unwrap();
+    sdk.enums().keywords().unwrap();
+    assert!(cards.exists());
+
+    let stats = sdk.cache_stats().unwrap();
+    let in_use: Vec<&str> = stats
+        .files
+        .iter()
+        .filter(|f| f.in_use)
+        .map(|f| f.path.as_str())
+        .collect();
+    assert_eq!(in_use, ["parquet/cards.parquet"]);
+    assert_eq!(stats.quota, Some(1));
+
+    sdk.connection().unwrap().reset_views();
+    let evicted = sdk.connection().unwrap().cache().enforce_quota().unwrap();
+    assert!(evicted.contains(&"parquet/cards.parquet".to_string()));
+    assert!(!cards.exists());
+}
+
+#[test]
+fn archived_versions_are_evicted_whole() {
+    let cdn = start();
+    cdn.set(
+        "parquet/cards.parquet",
+        parquet("SELECT 'card-1' AS uuid, 'Lightning Bolt' AS name"),
+    );
+    let tmp = tempfile::tempdir().unwrap();
+    let sdk = MtgjsonSdk::builder()
+        .cache_dir(tmp.path())
+        .cdn_base(cdn.base.clone())
+        .build()
+        .unwrap();
+    sdk.connection().unwrap().ensure_views(&["cards"]).unwrap();
+    cdn.set("Meta.json", meta(NEW));
+    cdn.set(
+        "parquet/cards.parquet",
+        parquet("SELECT 'card-1' AS uuid, 'Lightning Strike' AS name"),
+    );
+    assert!(sdk.refresh().unwrap());
+
+    let stats = sdk.cache_stats().unwrap();
+    let archived: Vec<(&str, Option<&str>)> = stats
+        .files
+        .iter()
+        .filter(|f| f.archived)
+        .map(|f| (f.path.as_str(), f.version.as_deref()))
+        .collect();
+    let archived_cards = format!("versions/{}/parquet/cards.parquet", OLD);
+    assert_eq!(archived, [(archived_cards.as_str(), Some(OLD))]);
+
+    let mut cache = sdk.connection().unwrap().cache();
+    cache.quota = Some(stats.total_size - 1);
+    assert_eq!(cache.enforce_quota().unwrap(), [format!("versions/{}", OLD)]);
+    assert!(cache.archived_versions().unwrap().is_empty());
+    assert!(tmp.path().join("parquet/cards.parquet").exists());
+}
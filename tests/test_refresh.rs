@@ -0,0 +1,253 @@
+//! Per-file manifest and incremental refresh with conditional GETs, against
+//! a local HTTP stand-in for the CDN.
+
+#[allow(dead_code)]
+mod common;
+
+use common::cdn::{cache_for, meta, parquet, Cdn};
+use mtgjson_sdk::MtgjsonSdk;
+use std::fs;
+
+fn start() -> Cdn {
+    Cdn::start(vec![
+        ("Meta.json", meta("5.2.2+20240101")),
+        ("Keywords.json", br#"{"data": {"abilityWords": ["Landfall"]}}"#.to_vec()),
+        ("CardTypes.json", br#"{"data": {"artifact": {}}}"#.to_vec()),
+        ("EnumValues.json", br#"{"data": {}}"#.to_vec()),
+    ])
+}
+
+fn conditional(cdn: &Cdn, path: &str) -> Vec<Option<String>> {
+    cdn.requests(path)
+        .into_iter()
+        .map(|r| r.headers.get("if-none-match").cloned())
+        .collect()
+}
+
+#[test]
+fn downloads_are_recorded_in_manifest() {
+    let cdn = start();
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cache = cache_for(tmp.path(), &cdn);
+    cache.ensure_json("keywords").unwrap();
+
+    let manifest = cache.manifest();
+    let record = &manifest.files["Keywords.json"];
+    assert_eq!(record.version.as_deref(), Some("5.2.2+20240101"));
+    assert_eq!(
+        record.size,
+        fs::metadata(tmp.path().join("Keywords.json")).unwrap().len()
+    );
+    assert!(record.etag.is_some());
+    assert!(record.downloaded_at > 0);
+    assert!(tmp.path().join("cache_manifest.json").exists());
+}
+
+#[test]
+fn refresh_refetches_only_stale_files() {
+    let cdn = start();
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cache = cache_for(tmp.path(), &cdn);
+    cache.ensure_json("keywords").unwrap();
+    cache.ensure_json("card_types").unwrap();
+
+    // Same version: nothing is requested
+    assert!(cache.refresh().unwrap().is_empty());
+    assert_eq!(cdn.requests("Keywords.json").len(), 1);
+
+    // New build: CardTypes.json changed, Keywords.json did not
+    cdn.set("Meta.json", meta("5.2.3+20240201"));
+    cdn.set("CardTypes.json", br#"{"data": {"battle": {}}}"#.to_vec());
+    let updated = cache.refresh().unwrap();
+    assert_eq!(updated, vec!["CardTypes.json"]);
+
+    assert_eq!(conditional(&cdn, "Keywords.json").len(), 2);
+    assert!(conditional(&cdn, "Keywords.json")[1].is_some());
+    assert!(conditional(&cdn, "CardTypes.json")[1].is_some());
+    // Never cached, so never fetched
+    assert!(cdn.requests("EnumValues.json").is_empty());
+
+    let manifest = cache.manifest();
+    for file in ["Keywords.json", "CardTypes.json"] {
+        assert_eq!(
+            manifest.files[file].version.as_deref(),
+            Some("5.2.3+20240201")
+        );
+    }
+    assert_eq!(
+        fs::read_to_string(tmp.path().join("version.txt")).unwrap(),
+        "5.2.3+20240201"
+    );
+    let card_types = cache.load_json("card_types").unwrap();
+    assert!(card_types["data"].get("battle").is_some());
+}
+
+#[test]
+fn stale_file_is_revalidated_on_access() {
+    let cdn = start();
+    let tmp = tempfile::tempdir().unwrap();
+    cache_for(tmp.path(), &cdn).ensure_json("keywords").unwrap();
+
+    // A new process sees a new version and revalidates on first use
+    cdn.set("Meta.json", meta("5.2.3+20240201"));
+    let mut cache = cache_for(tmp.path(), &cdn);
+    cache.ensure_json("keywords").unwrap();
+    assert!(conditional(&cdn, "Keywords.json")[1].is_some());
+    assert_eq!(
+        cache.manifest().files["Keywords.json"].version.as_deref(),
+        Some("5.2.3+20240201")
+    );
+}
+
+#[test]
+fn sdk_refresh_reports_changes() {
+    let cdn = start();
+    let tmp = tempfile::tempdir().unwrap();
+    let sdk = MtgjsonSdk::builder()
+        .cache_dir(tmp.path())
+        .cdn_base(cdn.base.clone())
+        .build()
+        .unwrap();
+    sdk.meta().unwrap();
+
+    assert!(!sdk.refresh().unwrap());
+    cdn.set("Meta.json", meta("5.2.3+20240201"));
+    assert!(sdk.refresh().unwrap());
+    assert_eq!(sdk.meta().unwrap()["data"]["version"], "5.2.3+20240201");
+}
+
+#[test]
+fn refresh_archives_replaced_files() {
+    let cdn = start();
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cache = cache_for(tmp.path(), &cdn);
+    cache.ensure_json("keywords").unwrap();
+    let old = fs::read(tmp.path().join("Keywords.json")).unwrap();
+
+    cdn.set("Meta.json", meta("5.2.3+20240201"));
+    cdn.set("Keywords.json", br#"{"data": {"abilityWords": ["Raid"]}}"#.to_vec());
+    assert_eq!(cache.refresh().unwrap(), vec!["Keywords.json"]);
+
+    assert_eq!(cache.archived_versions().unwrap(), vec!["5.2.2+20240101"]);
+    let archived = tmp.path().join("versions/5.2.2+20240101/Keywords.json");
+    assert_eq!(fs::read(archived).unwrap(), old);
+    assert!(!tmp.path().join("staging").exists());
+    // Archives are not part of the cache itself
+    let report = cache.verify(false).unwrap();
+    assert_eq!(report.verified.len() + report.unchecked.len(), 2);
+}
+
+#[test]
+fn failed_refresh_leaves_cache_untouched() {
+    let cdn = start();
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cache = cache_for(tmp.path(), &cdn);
+    cache.max_retries = 0;
+    cache.ensure_json("keywords").unwrap();
+    cache.ensure_json("card_types").unwrap();
+    let old_types = fs::read(tmp.path().join("CardTypes.json")).unwrap();
+
+    cdn.set("Meta.json", meta("5.2.3+20240201"));
+    cdn.set("CardTypes.json", br#"{"data": {"battle": {}}}"#.to_vec());
+    cdn.set("Keywords.json", br#"{"data": {"abilityWords": ["Raid"]}}"#.to_vec());
+    cdn.cut("Keywords.json", 1, 5);
+    assert!(cache.refresh().is_err());
+
+    // CardTypes.json was staged but never swapped in
+    assert_eq!(fs::read(tmp.path().join("CardTypes.json")).unwrap(), old_types);
+    assert_eq!(
+        fs::read_to_string(tmp.path().join("version.txt")).unwrap(),
+        "5.2.2+20240101"
+    );
+    assert!(cache.archived_versions().unwrap().is_empty());
+
+    // The next refresh resumes and completes the swap
+    assert_eq!(cache.refresh().unwrap().len(), 2);
+    let card_types = cache.load_json("card_types").unwrap();
+    assert!(card_types["data"].get("battle").is_some());
+}
+
+#[test]
+fn rollback_restores_previous_version() {
+    let cdn = start();
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cache = cache_for(tmp.path(), &cdn);
+    cache.ensure_json("keywords").unwrap();
+    let old = fs::read(tmp.path().join("Keywords.json")).unwrap();
+
+    assert_eq!(cache.rollback().unwrap(), None);
+    cdn.set("Meta.json", meta("5.2.3+20240201"));
+    cdn.set("Keywords.json", br#"{"data": {"abilityWords": ["Raid"]}}"#.to_vec());
+    cache.refresh().unwrap();
+
+    assert_eq!(cache.rollback().unwrap().as_deref(), Some("5.2.2+20240101"));
+    assert_eq!(fs::read(tmp.path().join("Keywords.json")).unwrap(), old);
+    assert_eq!(
+        cache.manifest().files["Keywords.json"].version.as_deref(),
+        Some("5.2.2+20240101")
+    );
+    assert!(cache.archived_versions().unwrap().is_empty());
+
+    // The restored file is served without revalidating
+    let requests = cdn.requests("Keywords.json").len();
+    let keywords = cache.load_json("keywords").unwrap();
+    assert_eq!(keywords["data"]["abilityWords"][0], "Landfall");
+    assert_eq!(cdn.requests("Keywords.json").len(), requests);
+}
+
+#[test]
+fn old_versions_are_pruned() {
+    let cdn = start();
+    let tmp = tempfile::tempdir().unwrap();
+    let mut cache = cache_for(tmp.path(), &cdn);
+    cache.keep_versions = 1;
+    cache.ensure_json("keywords").unwrap();
+
+    for (version, word) in [("5.2.3+20240201", "Raid"), ("5.2.4+20240301", "Threshold")] {
+        cdn.set("Meta.json", meta(version));
+        let body = format!(r#"{{"data": {{"abilityWords": ["{}"]}}}}"#, word);
+        cdn.set("Keywords.json", body.into_bytes());
+        cache.refresh().unwrap();
+    }
+    assert_eq!(cache.archived_versions().unwrap(), vec!["5.2.3+20240201"]);
+    assert!(!tmp.path().join("versions/5.2.2+20240101").exists());
+}
+
+#[test]
+fn sdk_refresh_swaps_views_and_rolls_back() {
+    let cdn = Cdn::start(vec![
+        ("Meta.json", meta("5.2.2+20240101")),
+        (
+            "parquet/cards.parquet",
+            parquet("SELECT 'card-1' AS uuid, 'Lightning Bolt' AS name"),
+        ),
+    ]);
+    let tmp = tempfile::tempdir().unwrap();
+    let sdk = MtgjsonSdk::builder()
+        .cache_dir(tmp.path())
+        .cdn_base(cdn.base.clone())
+        .build()
+        .unwrap();
+    let names = |sdk: &MtgjsonSdk| {
+        sdk.connection().unwrap().ensure_views(&["cards"]).unwrap();
+        sdk.sql("SELECT name FROM cards", &[]).unwrap()[0]["name"].clone()
+    };
+    assert_eq!(names(&sdk), "Lightning Bolt");
+
+    // The new file has an extra column; the view picks up the new schema
+    cdn.set("Meta.json", meta("5.2.3+20240201"));
+    cdn.set(
+        "parquet/cards.parquet",
+        parquet("SELECT 'card-1' AS uuid, 'Lightning Strike' AS name, 2.0 AS manaValue"),
+    );
+    assert!(sdk.refresh().unwrap());
+    assert!(sdk.views().contains(&"cards".to_string()));
+    assert_eq!(names(&sdk), "Lightning Strike");
+    let rows = sdk.sql("SELECT manaValue FROM cards", &[]).unwrap();
+    assert_eq!(rows[0]["manaValue"], 2.0);
+
+    assert_eq!(sdk.rollback().unwrap().as_deref(), Some("5.2.2+20240101"));
+    assert_eq!(names(&sdk), "Lightning Bolt");
+    assert!(sdk.sql("SELECT manaValue FROM cards", &[]).is_err());
+    assert_eq!(sdk.rollback().unwrap(), None);
+}
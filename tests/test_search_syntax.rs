@@ -0,0 +1,204 @@
+//! Tests for the Scryfall-style search syntax and `CardQuery::search_query`.
+
+#[allow(dead_code)]
+mod common;
+
+use mtgjson_sdk::queries::cards::CardQuery;
+use mtgjson_sdk::queries::search_syntax;
+use mtgjson_sdk::{Connection, MtgjsonError, SqlValue};
+
+// ---------------------------------------------------------------------------
+// Fixtures
+// ---------------------------------------------------------------------------
+
+/// Writes the parquet files behind `cards`, `card_legalities` (wide format)
+/// and `sets`; see [`common::setup_parquet_db`].
+const PARQUET_SQL: &str = r#"
+    COPY (
+        SELECT * FROM (VALUES
+            ('uuid-bolt', 'Lightning Bolt', 'A25', '141', 'Instant', 'normal',
+             'R', 'R', 1.0, NULL, NULL, 'uncommon',
+             'Lightning Bolt deals 3 damage to any target.', 'paper, mtgo', NULL, false),
+            ('uuid-counter', 'Counterspell', 'A25', '50', 'Instant', 'normal',
+             'U', 'U', 2.0, NULL, NULL, 'uncommon',
+             'Counter target spell.', 'paper, mtgo', NULL, false),
+            ('uuid-ragavan', 'Ragavan, Nimble Pilferer', 'MH2', '138', 'Legendary Creature - Monkey Pirate', 'normal',
+             'R', 'R', 1.0, '2', '1', 'mythic',
+             'Whenever Ragavan deals combat damage to a player, create a Treasure token.', 'paper', 'Dash', false),
+            ('uuid-gitrog', 'The Gitrog Monster', 'SOI', '245', 'Legendary Creature - Frog Horror', 'normal',
+             'B, G', 'B, G', 5.0, '6', '6', 'mythic',
+             'Deathtouch. Whenever one or more land cards are put into your graveyard, draw a card.', 'paper', 'Deathtouch', true),
+            ('uuid-ornithopter', 'Ornithopter', 'M15', '223', 'Artifact Creature - Thopter', 'normal',
+             '', '', 0.0, '0', '2', 'uncommon',
+             'Flying', 'paper, mtgo', 'Flying', false)
+        ) AS t(uuid, name, setCode, number, "type", layout,
+               colors, colorIdentity, manaValue, power, toughness, rarity,
+               "text", availability, keywords, isPromo)
+    ) TO '{dir}/cards.parquet' (FORMAT PARQUET);
+
+    COPY (
+        SELECT * FROM (VALUES
+            ('uuid-bolt', 'Legal', 'Legal'),
+            ('uuid-counter', 'Legal', 'Legal'),
+            ('uuid-ragavan', 'Banned', 'Legal'),
+            ('uuid-gitrog', 'Legal', 'Legal'),
+            ('uuid-ornithopter', 'Legal', 'Legal')
+        ) AS t(uuid, modern, vintage)
+    ) TO '{dir}/cardLegalities.parquet' (FORMAT PARQUET);
+
+    COPY (
+        SELECT * FROM (VALUES
+            ('A25', 'Masters 25', 'masters', '2018-03-16'),
+            ('MH2', 'Modern Horizons 2', 'draft_innovation', '2021-06-18'),
+            ('SOI', 'Shadows over Innistrad', 'expansion', '2016-04-08'),
+            ('M15', 'Magic 2015', 'core', '2014-07-18')
+        ) AS t(code, name, "type", releaseDate)
+    ) TO '{dir}/sets.parquet' (FORMAT PARQUET);
+"#;
+
+fn names(conn: &Connection, query: &str) -> Vec<String> {
+    let cq = CardQuery::new(conn);
+    cq.typed()
+        .search_query(query, None, None)
+        .unwrap()
+        .into_iter()
+        .map(|c| c.name)
+        .collect()
+}
+
+fn error_message(query: &str) -> String {
+    match search_syntax::parse(query) {
+        Err(MtgjsonError::InvalidArgument(msg)) => msg,
+        other => panic!("expected InvalidArgument for {:?}, got {:?}", query, other),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Parsing
+// ---------------------------------------------------------------------------
+
+#[test]
+fn parse_binds_values_as_params() {
+    let parsed = search_syntax::parse(r#"t:creature o:"draw a card" mv<=3"#).unwrap();
+    let (sql, params) = parsed.to_sql();
+    assert!(!sql.contains("draw a card"));
+    assert_eq!(
+        params,
+        vec![
+            SqlValue::from("creature"),
+            SqlValue::from("draw a card"),
+            SqlValue::Float(3.0),
+        ]
+    );
+}
+
+#[test]
+fn parse_reports_views_for_subqueries() {
+    let parsed = search_syntax::parse("f:modern or st:core").unwrap();
+    assert_eq!(parsed.views(), vec!["cards", "card_legalities", "sets"]);
+}
+
+#[test]
+fn parse_errors_include_position() {
+    assert!(error_message("").contains("position 0"));
+    assert!(error_message("t:creature foo:bar").contains("unknown search key 'foo' at position 11"));
+    assert!(error_message("mv<=x").contains("position 4"));
+    assert!(error_message("c:rx").contains("invalid color 'x' at position 3"));
+    assert!(error_message(r#"o:"draw"#).contains("unterminated quoted string at position 2"));
+    assert!(error_message("(t:instant").contains("unclosed '(' at position 0"));
+    assert!(error_message("t:instant)").contains("unexpected ')' at position 9"));
+    assert!(error_message("o>3").contains("position 1"));
+    assert!(error_message("bolt or").contains("unexpected end of query at position 7"));
+}
+
+// ---------------------------------------------------------------------------
+// Execution
+// ---------------------------------------------------------------------------
+
+#[test]
+fn search_query_bare_words_match_name() {
+    let (conn, _tmp) = common::setup_parquet_db(PARQUET_SQL);
+    assert_eq!(names(&conn, "bolt"), vec!["Lightning Bolt"]);
+    assert_eq!(names(&conn, "!Counterspell"), vec!["Counterspell"]);
+}
+
+#[test]
+fn search_query_text_terms_are_not_like_patterns() {
+    let (conn, _tmp) = common::setup_parquet_db(PARQUET_SQL);
+    assert!(names(&conn, "o:100%").is_empty());
+    assert!(names(&conn, "light_ing").is_empty());
+    assert!(names(&conn, "o:%").is_empty());
+    assert_eq!(names(&conn, "o:deathtouch."), vec!["The Gitrog Monster"]);
+}
+
+#[test]
+fn search_query_type_and_mana_value() {
+    let (conn, _tmp) = common::setup_parquet_db(PARQUET_SQL);
+    assert_eq!(
+        names(&conn, "t:creature mv<=3"),
+        vec!["Ornithopter", "Ragavan, Nimble Pilferer"]
+    );
+}
+
+#[test]
+fn search_query_colors() {
+    let (conn, _tmp) = common::setup_parquet_db(PARQUET_SQL);
+    assert_eq!(names(&conn, "c:bg"), vec!["The Gitrog Monster"]);
+    assert_eq!(names(&conn, "c=c"), vec!["Ornithopter"]);
+    assert_eq!(names(&conn, "c:m"), vec!["The Gitrog Monster"]);
+    assert_eq!(
+        names(&conn, "id:r"),
+        vec!["Lightning Bolt", "Ornithopter", "Ragavan, Nimble Pilferer"]
+    );
+}
+
+#[test]
+fn search_query_rarity_comparison() {
+    let (conn, _tmp) = common::setup_parquet_db(PARQUET_SQL);
+    assert_eq!(
+        names(&conn, "r>=rare"),
+        vec!["Ragavan, Nimble Pilferer", "The Gitrog Monster"]
+    );
+}
+
+#[test]
+fn search_query_boolean_operators() {
+    let (conn, _tmp) = common::setup_parquet_db(PARQUET_SQL);
+    assert_eq!(
+        names(&conn, "(bolt or counterspell) -c:u"),
+        vec!["Lightning Bolt"]
+    );
+    assert_eq!(
+        names(&conn, "not t:creature and o:damage"),
+        vec!["Lightning Bolt"]
+    );
+}
+
+#[test]
+fn search_query_legalities_and_sets() {
+    let (conn, _tmp) = common::setup_parquet_db(PARQUET_SQL);
+    assert_eq!(names(&conn, "banned:modern"), vec!["Ragavan, Nimble Pilferer"]);
+    assert_eq!(
+        names(&conn, "f:modern t:creature"),
+        vec!["Ornithopter", "The Gitrog Monster"]
+    );
+    assert_eq!(names(&conn, "st:core"), vec!["Ornithopter"]);
+    assert_eq!(names(&conn, "year>=2021"), vec!["Ragavan, Nimble Pilferer"]);
+    assert_eq!(names(&conn, "s:soi"), vec!["The Gitrog Monster"]);
+}
+
+#[test]
+fn search_query_keywords_and_flags() {
+    let (conn, _tmp) = common::setup_parquet_db(PARQUET_SQL);
+    assert_eq!(names(&conn, "kw:flying"), vec!["Ornithopter"]);
+    assert_eq!(names(&conn, "is:promo"), vec!["The Gitrog Monster"]);
+    assert_eq!(names(&conn, "pow>=6"), vec!["The Gitrog Monster"]);
+}
+
+#[test]
+fn search_query_invalid_is_error() {
+    let (conn, _tmp) = common::setup_parquet_db(PARQUET_SQL);
+    let cq = CardQuery::new(&conn);
+    let err = cq.search_query("t:", None, None).unwrap_err();
+    assert!(matches!(err, MtgjsonError::InvalidArgument(_)));
+}
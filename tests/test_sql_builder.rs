@@ -1,6 +1,6 @@
 //! Unit tests for the SqlBuilder query construction.
 
-use mtgjson_sdk::SqlBuilder;
+use mtgjson_sdk::{SqlBuilder, SqlValue};
 
 // ---------------------------------------------------------------------------
 // Basic construction
@@ -55,7 +55,7 @@ fn where_in_adds_in_clause() {
 #[test]
 fn where_in_empty_produces_false() {
     let (sql, params) = SqlBuilder::new("cards")
-        .where_in("uuid", &[])
+        .where_in::<&str>("uuid", &[])
         .build();
     assert!(sql.contains("WHERE FALSE"));
     assert!(params.is_empty());
@@ -64,19 +64,19 @@ fn where_in_empty_produces_false() {
 #[test]
 fn where_gte_adds_comparison() {
     let (sql, params) = SqlBuilder::new("cards")
-        .where_gte("manaValue", "3")
+        .where_gte("manaValue", 3)
         .build();
     assert!(sql.contains("manaValue >= ?"));
-    assert_eq!(params, vec!["3"]);
+    assert_eq!(params, vec![SqlValue::Int(3)]);
 }
 
 #[test]
 fn where_lte_adds_comparison() {
     let (sql, params) = SqlBuilder::new("cards")
-        .where_lte("manaValue", "5")
+        .where_lte("manaValue", 2.5)
         .build();
     assert!(sql.contains("manaValue <= ?"));
-    assert_eq!(params, vec!["5"]);
+    assert_eq!(params, vec![SqlValue::Float(2.5)]);
 }
 
 #[test]
@@ -109,7 +109,7 @@ fn where_or_creates_or_group() {
 #[test]
 fn where_or_empty_is_noop() {
     let (sql, params) = SqlBuilder::new("cards")
-        .where_or(&[])
+        .where_or::<&str>(&[])
         .build();
     assert!(!sql.contains("WHERE"));
     assert!(params.is_empty());
@@ -119,7 +119,7 @@ fn where_or_empty_is_noop() {
 fn where_clause_appends_params_in_order() {
     let (sql, params) = SqlBuilder::new("cards")
         .where_eq("setCode", "A25")
-        .where_clause("list_contains(colors, ?)", &["R"])
+        .where_clause("list_contains(colors, ?)", &["R".into()])
         .build();
     assert!(sql.contains("setCode = ?"));
     assert!(sql.contains("list_contains(colors, ?)"));
@@ -168,10 +168,10 @@ fn having_adds_clause_with_params() {
     let (sql, params) = SqlBuilder::new("cards")
         .select(&["setCode", "COUNT(*) AS cnt"])
         .group_by(&["setCode"])
-        .having("COUNT(*) > ?", &["5"])
+        .having("COUNT(*) > ?", &[5.into()])
         .build();
     assert!(sql.contains("HAVING COUNT(*) > ?"));
-    assert_eq!(params, vec!["5"]);
+    assert_eq!(params, vec![SqlValue::Int(5)]);
 }
 
 #[test]
@@ -180,9 +180,9 @@ fn having_params_ordered_after_where_params() {
         .select(&["setCode", "COUNT(*) AS cnt"])
         .where_eq("rarity", "uncommon")
         .group_by(&["setCode"])
-        .having("COUNT(*) > ?", &["2"])
+        .having("COUNT(*) > ?", &[2.into()])
         .build();
-    assert_eq!(params, vec!["uncommon", "2"]);
+    assert_eq!(params, vec!["uncommon".into(), SqlValue::Int(2)]);
 }
 
 // ---------------------------------------------------------------------------
@@ -236,7 +236,7 @@ fn combined_builder_chains_correctly() {
     let (sql, params) = SqlBuilder::new("cards")
         .where_eq("setCode", "MH3")
         .where_like("name", "Lightning%")
-        .where_gte("manaValue", "1")
+        .where_gte("manaValue", 1)
         .order_by(&["name ASC"])
         .limit(10)
         .offset(0)
@@ -251,7 +251,7 @@ fn combined_builder_chains_correctly() {
     assert_eq!(params.len(), 3);
     assert_eq!(params[0], "MH3");
     assert_eq!(params[1], "Lightning%");
-    assert_eq!(params[2], "1");
+    assert_eq!(params[2], SqlValue::Int(1));
 }
 
 #[test]
@@ -270,7 +270,7 @@ fn full_query_with_join_and_grouping() {
         .join("JOIN sets s ON c.setCode = s.code")
         .where_eq("s.type", "masters")
         .group_by(&["c.setCode"])
-        .having("COUNT(*) >= ?", &["10"])
+        .having("COUNT(*) >= ?", &[10.into()])
         .order_by(&["cnt DESC"])
         .limit(5)
         .build();
@@ -283,5 +283,5 @@ fn full_query_with_join_and_grouping() {
     assert!(sql.contains("HAVING COUNT(*) >= ?"));
     assert!(sql.contains("ORDER BY cnt DESC"));
     assert!(sql.contains("LIMIT 5"));
-    assert_eq!(params, vec!["masters", "10"]);
+    assert_eq!(params, vec!["masters".into(), SqlValue::Int(10)]);
 }
@@ -0,0 +1,169 @@
+//! Composable `SqlExpr` conditions, CTEs and unions on `SqlBuilder`.
+
+#[allow(dead_code)]
+mod common;
+
+use mtgjson_sdk::{SqlBuilder, SqlExpr, SqlValue};
+
+fn names(conn: &mtgjson_sdk::Connection, qb: &SqlBuilder) -> Vec<String> {
+    let (sql, params) = qb.build();
+    conn.execute(&sql, &params)
+        .unwrap()
+        .iter()
+        .map(|r| r["name"].as_str().unwrap().to_string())
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Rendering
+// ---------------------------------------------------------------------------
+
+#[test]
+fn nested_groups_are_parenthesized() {
+    let expr = SqlExpr::col("rarity").eq("rare").and(
+        SqlExpr::col("manaValue")
+            .lte(2)
+            .or(SqlExpr::col("power").is_null()),
+    );
+    let (sql, params) = expr.to_sql();
+    assert_eq!(
+        sql,
+        r#"("rarity" = ? AND ("manaValue" <= ? OR "power" IS NULL))"#
+    );
+    assert_eq!(params, vec!["rare".into(), SqlValue::Int(2)]);
+}
+
+#[test]
+fn same_operator_groups_are_flattened() {
+    let expr = SqlExpr::col("a")
+        .eq(1)
+        .and(SqlExpr::col("b").eq(2))
+        .and(SqlExpr::col("c").eq(3));
+    assert_eq!(expr.to_sql().0, r#"("a" = ? AND "b" = ? AND "c" = ?)"#);
+}
+
+#[test]
+fn not_between_and_in_list() {
+    let (sql, params) = (!SqlExpr::col("cards.manaValue").between(1, 3)).to_sql();
+    assert_eq!(sql, r#"NOT ("cards"."manaValue" BETWEEN ? AND ?)"#);
+    assert_eq!(params, vec![SqlValue::Int(1), SqlValue::Int(3)]);
+
+    let (sql, _) = SqlExpr::col("setCode").in_list(["A25", "MH2"]).to_sql();
+    assert_eq!(sql, r#""setCode" IN (?, ?)"#);
+
+    let (sql, params) = SqlExpr::col("setCode").in_list(Vec::<&str>::new()).to_sql();
+    assert_eq!(sql, "FALSE");
+    assert!(params.is_empty());
+}
+
+#[test]
+fn empty_groups_are_constants() {
+    assert_eq!(SqlExpr::all([]).to_sql().0, "TRUE");
+    assert_eq!(SqlExpr::any([]).to_sql().0, "FALSE");
+}
+
+#[test]
+fn column_names_are_quoted() {
+    let (sql, params) = SqlExpr::col(r#"name" = 'x' OR "1"#).eq("y").to_sql();
+    assert_eq!(sql, r#""name"" = 'x' OR ""1" = ?"#);
+    assert_eq!(params, vec![SqlValue::from("y")]);
+}
+
+#[test]
+fn params_follow_clause_order() {
+    let mut inner = SqlBuilder::new("sets");
+    inner.select(&["code"]).where_eq("type", "masters");
+
+    let (sql, params) = SqlBuilder::new("cards")
+        .order_by_expr(SqlExpr::func("length", [SqlExpr::val("x")]), true)
+        .having("COUNT(*) > ?", &[1.into()])
+        .where_eq("rarity", "uncommon")
+        .with("m", &inner)
+        .build();
+    assert!(sql.starts_with("WITH \"m\" AS (\nSELECT code\nFROM sets"));
+    assert!(sql.ends_with("ORDER BY length(?) DESC"));
+    assert_eq!(
+        params,
+        vec!["masters".into(), "uncommon".into(), SqlValue::Int(1), "x".into()]
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Execution
+// ---------------------------------------------------------------------------
+
+#[test]
+fn where_expr_runs_nested_conditions() {
+    let (conn, _tmp) = common::setup_sample_db();
+
+    let mut qb = SqlBuilder::new("cards");
+    qb.select(&["name"])
+        .where_expr(
+            SqlExpr::col("setCode")
+                .eq("MH2")
+                .or(SqlExpr::col("manaValue").lt(2).and(SqlExpr::col("power").is_null())),
+        )
+        .order_by(&["name"]);
+    assert_eq!(names(&conn, &qb), vec!["Fire // Ice", "Lightning Bolt"]);
+}
+
+#[test]
+fn exists_and_in_subqueries() {
+    let (conn, _tmp) = common::setup_sample_db();
+
+    let mut masters = SqlBuilder::new("sets s");
+    masters
+        .select(&["1"])
+        .where_expr(SqlExpr::col("s.code").eq(SqlExpr::col("cards.setCode")))
+        .where_eq("s.type", "masters");
+
+    let mut qb = SqlBuilder::new("cards");
+    qb.select(&["name"])
+        .where_expr(SqlExpr::exists(&masters))
+        .order_by(&["name"]);
+    assert_eq!(names(&conn, &qb), vec!["Counterspell", "Lightning Bolt"]);
+
+    let mut codes = SqlBuilder::new("sets");
+    codes.select(&["code"]).where_eq("type", "masters");
+    let mut qb = SqlBuilder::new("cards");
+    qb.select(&["name"])
+        .where_expr(!SqlExpr::col("setCode").in_query(&codes));
+    assert_eq!(names(&conn, &qb), vec!["Fire // Ice"]);
+}
+
+#[test]
+fn ctes_and_unions() {
+    let (conn, _tmp) = common::setup_sample_db();
+
+    let mut cheap = SqlBuilder::new("cards");
+    cheap.select(&["name", "manaValue"]).where_lte("manaValue", 1);
+
+    let mut blue = SqlBuilder::new("cards");
+    blue.select(&["name", "manaValue"])
+        .where_clause("list_contains(colors, ?)", &["U".into()])
+        .order_by(&["name"])
+        .limit(1);
+
+    let mut qb = SqlBuilder::new("cheap");
+    qb.with("cheap", &cheap)
+        .select(&["name", "manaValue"])
+        .union(&blue)
+        .order_by(&["name"]);
+    assert_eq!(names(&conn, &qb), vec!["Counterspell", "Lightning Bolt"]);
+}
+
+#[test]
+fn fuzzy_ordering_binds_its_value() {
+    let (conn, _tmp) = common::setup_sample_db();
+
+    let mut qb = SqlBuilder::new("cards");
+    qb.select(&["name"]).order_by_expr(
+        SqlExpr::func(
+            "jaro_winkler_similarity",
+            [SqlExpr::col("name"), SqlExpr::val("Counterspel'; DROP TABLE cards; --")],
+        ),
+        true,
+    );
+    assert_eq!(names(&conn, &qb)[0], "Counterspell");
+    assert_eq!(names(&conn, &SqlBuilder::new("cards")).len(), 3);
+}
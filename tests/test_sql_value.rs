@@ -0,0 +1,115 @@
+//! Typed parameter binding through `SqlValue`.
+
+#[allow(dead_code)]
+mod common;
+
+use mtgjson_sdk::{MtgjsonError, SqlBuilder, SqlValue};
+use serde_json::json;
+
+#[test]
+fn numbers_and_booleans_bind_natively() {
+    let (conn, _tmp) = common::setup_sample_db();
+
+    let rows = conn
+        .execute(
+            "SELECT name FROM cards WHERE manaValue <= ? AND isPromo = ? ORDER BY name",
+            &[1.5.into(), false.into()],
+        )
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0]["name"], "Lightning Bolt");
+
+    let types = conn
+        .execute_scalar("SELECT typeof(?) || ',' || typeof(?)", &[3.into(), 2.5.into()])
+        .unwrap();
+    assert_eq!(types, Some(json!("BIGINT,DOUBLE")));
+}
+
+#[test]
+fn null_binds_from_none() {
+    let (conn, _tmp) = common::setup_sample_db();
+
+    let value = conn
+        .execute_scalar("SELECT ? IS NULL", &[None::<&str>.into()])
+        .unwrap();
+    assert_eq!(value, Some(json!(true)));
+}
+
+#[test]
+fn dates_bind_as_date() {
+    let (conn, _tmp) = common::setup_sample_db();
+
+    let rows = conn
+        .execute(
+            "SELECT code FROM sets WHERE CAST(releaseDate AS DATE) < ?",
+            &[SqlValue::Date("2019-01-01".into())],
+        )
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0]["code"], "A25");
+
+    let err = conn
+        .execute("SELECT ?", &[SqlValue::Date("not a date".into())])
+        .unwrap_err();
+    assert!(matches!(err, MtgjsonError::DuckDb(_)), "{:?}", err);
+}
+
+#[test]
+fn lists_bind_as_list() {
+    let (conn, _tmp) = common::setup_sample_db();
+
+    let rows = conn
+        .execute(
+            "SELECT uuid FROM cards WHERE list_contains(?, uuid) ORDER BY uuid",
+            &[vec!["card-uuid-001", "card-uuid-003"].into()],
+        )
+        .unwrap();
+    let uuids: Vec<_> = rows.iter().map(|r| r["uuid"].clone()).collect();
+    assert_eq!(uuids, vec!["card-uuid-001", "card-uuid-003"]);
+
+    let len = conn
+        .execute_scalar("SELECT len(?) + ?", &[vec![1, 2, 3].into(), 1.into()])
+        .unwrap();
+    assert_eq!(len, Some(json!(4)));
+}
+
+#[test]
+fn placeholders_in_literals_and_comments_are_skipped() {
+    let (conn, _tmp) = common::setup_sample_db();
+
+    let value = conn
+        .execute_scalar(
+            "SELECT /* ? */ '?' || ? -- ?\n",
+            &[SqlValue::Date("2024-01-01".into())],
+        )
+        .unwrap();
+    assert_eq!(value, Some(json!("?2024-01-01")));
+}
+
+#[test]
+fn numbered_placeholders_reject_expanding_values() {
+    let (conn, _tmp) = common::setup_sample_db();
+
+    let err = conn
+        .execute("SELECT ?1", &[vec!["a"].into()])
+        .unwrap_err();
+    assert!(matches!(err, MtgjsonError::InvalidArgument(_)), "{:?}", err);
+}
+
+#[test]
+fn builder_params_keep_their_types() {
+    let (conn, _tmp) = common::setup_sample_db();
+
+    let (sql, params) = SqlBuilder::new("cards")
+        .select(&["name"])
+        .where_gte("manaValue", 2)
+        .where_eq("isPromo", false)
+        .where_in("setCode", &["MH2"])
+        .build();
+    assert_eq!(params[0], SqlValue::Int(2));
+    assert_eq!(params[1], SqlValue::Bool(false));
+
+    let rows = conn.execute(&sql, &params).unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0]["name"], "Fire // Ice");
+}
@@ -0,0 +1,164 @@
+//! Streaming execution tests: `Connection::stream`, `execute_iter` and the
+//! `scan` methods on the query interfaces.
+
+mod common;
+
+use mtgjson_sdk::queries::cards::CardQuery;
+use mtgjson_sdk::queries::prices::PriceQuery;
+
+// ---------------------------------------------------------------------------
+// Connection::stream / execute_iter
+// ---------------------------------------------------------------------------
+
+#[test]
+fn stream_matches_execute() {
+    let (conn, _tmp) = common::setup_sample_db();
+    let sql = "SELECT * FROM cards ORDER BY uuid";
+
+    let collected = conn.execute(sql, &[]).unwrap();
+    let streamed: Vec<_> = conn
+        .stream(sql, &[])
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(streamed, collected);
+}
+
+#[test]
+fn stream_binds_params_and_reports_columns() {
+    let (conn, _tmp) = common::setup_sample_db();
+
+    let stream = conn
+        .stream(
+            "SELECT uuid, name FROM cards WHERE setCode = ? ORDER BY uuid;",
+            &["A25".into()],
+        )
+        .unwrap();
+    assert_eq!(stream.columns(), ["uuid", "name"]);
+    let rows: Vec<_> = stream.map(|r| r.unwrap()).collect();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0]["name"], "Lightning Bolt");
+}
+
+#[test]
+fn stream_empty_result() {
+    let (conn, _tmp) = common::setup_sample_db();
+
+    let mut stream = conn
+        .stream("SELECT * FROM cards WHERE uuid = ?", &["missing".into()])
+        .unwrap();
+    assert!(stream.next().is_none());
+}
+
+#[test]
+fn stream_spans_multiple_chunks() {
+    let (conn, _tmp) = common::setup_sample_db();
+
+    // DuckDB chunks hold 2048 rows; make sure iteration crosses boundaries.
+    let mut count = 0i64;
+    let mut sum = 0i64;
+    for row in conn.stream("SELECT range AS n FROM range(10000)", &[]).unwrap() {
+        let row = row.unwrap();
+        count += 1;
+        sum += row["n"].as_i64().unwrap();
+    }
+    assert_eq!(count, 10000);
+    assert_eq!(sum, 9999 * 10000 / 2);
+}
+
+#[test]
+fn stream_converts_nested_and_temporal_types() {
+    let (conn, _tmp) = common::setup_sample_db();
+
+    let row = conn
+        .stream(
+            "SELECT ['a', 'b'] AS list, {'x': 1} AS obj, DATE '2024-06-01' AS d, \
+             TIMESTAMP '2024-06-01 12:34:56' AS ts, 1.25::DECIMAL(5, 2) AS dec, \
+             MAP {'k': 2} AS m, NULL AS nothing",
+            &[],
+        )
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(row["list"], serde_json::json!(["a", "b"]));
+    assert_eq!(row["obj"], serde_json::json!({"x": 1}));
+    assert_eq!(row["d"], "2024-06-01");
+    assert_eq!(row["ts"], "2024-06-01 12:34:56");
+    assert_eq!(row["dec"], 1.25);
+    assert_eq!(row["m"], serde_json::json!({"k": 2}));
+    assert!(row["nothing"].is_null());
+}
+
+#[test]
+fn execute_iter_deserializes_rows() {
+    let (conn, _tmp) = common::setup_sample_db();
+
+    #[derive(serde::Deserialize)]
+    struct Row {
+        uuid: String,
+        name: String,
+    }
+
+    let rows: Vec<Row> = conn
+        .execute_iter("SELECT uuid, name FROM cards ORDER BY uuid", &[])
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[2].uuid, "card-uuid-003");
+    assert_eq!(rows[2].name, "Fire // Ice");
+}
+
+// ---------------------------------------------------------------------------
+// Query interface scans
+// ---------------------------------------------------------------------------
+
+#[test]
+fn card_scan_streams_all_or_one_set() {
+    let (conn, _tmp) = common::setup_sample_db();
+    let cq = CardQuery::new(&conn);
+
+    assert_eq!(cq.scan(None).unwrap().count(), 3);
+    let a25: Vec<_> = cq.scan(Some("A25")).unwrap().map(|r| r.unwrap()).collect();
+    assert_eq!(a25.len(), 2);
+    assert!(a25.iter().all(|c| c["setCode"] == "A25"));
+}
+
+#[test]
+fn price_scan_filters_by_date() {
+    let (conn, tmp) = common::setup_sample_db();
+
+    let rows = [
+        ("card-uuid-001", "2024-01-01", 1.0),
+        ("card-uuid-001", "2024-02-01", 1.5),
+        ("card-uuid-002", "2024-03-01", 2.0),
+    ];
+    let ndjson: String = rows
+        .iter()
+        .map(|(uuid, date, price)| {
+            serde_json::json!({
+                "uuid": uuid, "source": "paper", "provider": "tcgplayer",
+                "currency": "USD", "price_type": "retail", "finish": "normal",
+                "date": date, "price": price
+            })
+            .to_string()
+                + "\n"
+        })
+        .collect();
+    let path = tmp.path().join("all_prices.ndjson");
+    std::fs::write(&path, ndjson).unwrap();
+    conn.register_table_from_ndjson("all_prices", &path.to_string_lossy())
+        .unwrap();
+
+    let pq = PriceQuery::new(&conn);
+    let rows: Vec<_> = pq
+        .typed()
+        .scan(Some("2024-02-01"), None)
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(rows.len(), 2);
+    assert!(rows.iter().all(|r| r.date.as_str() >= "2024-02-01"));
+    assert_eq!(rows[0].category, "retail");
+}
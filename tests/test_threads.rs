@@ -0,0 +1,69 @@
+//! Sharing one SDK across threads: cloned connections, the shared view
+//! registry and cache.
+
+#[allow(dead_code)]
+mod common;
+
+use common::cdn::{meta, parquet, Cdn};
+use mtgjson_sdk::{Connection, MtgjsonSdk};
+use std::thread;
+
+fn assert_send_sync<T: Send + Sync>() {}
+fn assert_send<T: Send>() {}
+
+#[test]
+fn sdk_is_send_and_sync() {
+    assert_send_sync::<MtgjsonSdk>();
+    assert_send::<Connection>();
+}
+
+#[test]
+fn clones_share_views_and_tables() {
+    let (conn, _tmp) = common::setup_sample_db();
+    let clone = conn.try_clone().unwrap();
+
+    assert!(clone.has_view("cards"));
+    let count = clone.execute_scalar("SELECT COUNT(*) FROM cards", &[]).unwrap();
+    assert_eq!(count, Some(serde_json::json!(3)));
+
+    clone.reset_views();
+    assert!(!conn.has_view("cards"));
+}
+
+#[test]
+fn threads_query_through_their_own_connections() {
+    let cdn = Cdn::start(vec![
+        ("Meta.json", meta("5.2.2+20240101")),
+        (
+            "parquet/cards.parquet",
+            parquet("SELECT 'card-' || i AS uuid, 'Card ' || i AS name FROM range(100) t(i)"),
+        ),
+    ]);
+    let tmp = tempfile::tempdir().unwrap();
+    let sdk = MtgjsonSdk::builder()
+        .cache_dir(tmp.path())
+        .cdn_base(cdn.base.clone())
+        .build()
+        .unwrap();
+
+    let main = sdk.connection().unwrap() as *const Connection as usize;
+    thread::scope(|s| {
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                s.spawn(|| {
+                    let conn = sdk.connection().unwrap();
+                    conn.ensure_views(&["cards"]).unwrap();
+                    let rows = sdk.sql("SELECT COUNT(*) AS n FROM cards", &[]).unwrap();
+                    assert_eq!(rows[0]["n"], 100);
+                    conn as *const Connection as usize
+                })
+            })
+            .collect();
+        for handle in handles {
+            assert_ne!(handle.join().unwrap(), main);
+        }
+    });
+
+    assert_eq!(cdn.requests("parquet/cards.parquet").len(), 1);
+    assert_eq!(sdk.views(), vec!["cards".to_string()]);
+}
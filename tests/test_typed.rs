@@ -0,0 +1,246 @@
+//! Typed query integration tests against parquet-backed views.
+//!
+//! Unlike the NDJSON fixtures in `common`, these tests write real parquet files
+//! into the cache directory so that views go through the connection's
+//! CSV-to-array and JSON-cast rewriting before being deserialized into models.
+
+#[allow(dead_code)]
+mod common;
+
+use mtgjson_sdk::queries::cards::{CardQuery, SearchCardsParams};
+use mtgjson_sdk::queries::prices::PriceQuery;
+use mtgjson_sdk::queries::sealed::SealedQuery;
+use mtgjson_sdk::queries::sets::SetQuery;
+use mtgjson_sdk::queries::tokens::TokenQuery;
+
+// ---------------------------------------------------------------------------
+// Fixtures
+// ---------------------------------------------------------------------------
+
+/// Writes the parquet files behind `cards`, `sets`, `tokens` and
+/// `all_prices_today`; see [`common::setup_parquet_db`].
+const PARQUET_SQL: &str = r#"
+    COPY (
+        SELECT * FROM (VALUES
+            ('card-uuid-001', 'Lightning Bolt', 'A25', '141', 'Instant', 'normal',
+             'Instant', '', '', 'R', 'R', 'mtgo, paper', 'nonfoil, foil', NULL::VARCHAR,
+             'A25, M10', 1.0, 'Lightning Bolt deals 3 damage to any target.',
+             'uncommon', false, NULL,
+             '{"scryfallId": "scryfall-001"}', '{"modern": "Legal", "vintage": "Restricted"}',
+             NULL, NULL::VARCHAR),
+            ('card-uuid-002', 'Balloon Stand', 'UNF', '203', 'Artifact - Attraction', 'normal',
+             'Artifact', 'Attraction', '', '', '', 'paper', 'nonfoil', NULL,
+             'UNF', 0.0, 'Visit - Create a 1/1 red Balloon artifact creature token.',
+             'common', false, '1, 3',
+             '{"scryfallId": "scryfall-002"}', NULL,
+             '{"brawl": false, "commander": false, "oathbreaker": false}', NULL)
+        ) AS t(uuid, name, setCode, number, "type", layout,
+               types, subtypes, supertypes, colors, colorIdentity, availability, finishes, keywords,
+               printings, manaValue, "text",
+               rarity, isPromo, attractionLights,
+               identifiers, legalities,
+               leadershipSkills, faceName)
+    ) TO '{dir}/cards.parquet' (FORMAT PARQUET);
+
+    COPY (
+        SELECT * FROM (VALUES
+            ('A25', 'Masters 25', 'masters', '2018-03-16', 249, 249, 'A25',
+             'English, Japanese', '{"French": "Masters 25"}',
+             '[{"uuid": "sealed-uuid-001", "name": "Masters 25 Booster Box", "category": "booster_box", "cardCount": 360}]')
+        ) AS t(code, name, "type", releaseDate, baseSetSize, totalSetSize, keyruneCode,
+               languages, translations,
+               sealedProduct)
+    ) TO '{dir}/sets.parquet' (FORMAT PARQUET);
+
+    COPY (
+        SELECT * FROM (VALUES
+            ('token-uuid-001', 'Soldier', 'A25', 'T1', 'Token Creature - Soldier', 'token',
+             'W', 'Creature', 'Soldier', 'Raise the Alarm',
+             '{"reverseRelated": ["Raise the Alarm"]}', '[{"uuid": "product-001"}]')
+        ) AS t(uuid, name, setCode, number, "type", layout,
+               colors, types, subtypes, reverseRelated,
+               relatedCards, tokenProducts)
+    ) TO '{dir}/tokens.parquet' (FORMAT PARQUET);
+
+    COPY (
+        SELECT * FROM (VALUES
+            ('card-uuid-001', 'paper', 'tcgplayer', 'USD', 'retail', 'normal', DATE '2024-06-01', 1.25),
+            ('card-uuid-001', 'paper', 'cardkingdom', 'USD', 'retail', 'normal', DATE '2024-06-01', 0.99)
+        ) AS t(uuid, source, provider, currency, price_type, finish, date, price)
+    ) TO '{dir}/AllPricesToday.parquet' (FORMAT PARQUET);
+"#;
+
+// ---------------------------------------------------------------------------
+// Cards
+// ---------------------------------------------------------------------------
+
+#[test]
+fn typed_card_splits_csv_list_columns() {
+    let (conn, _tmp) = common::setup_parquet_db(PARQUET_SQL);
+    let cq = CardQuery::new(&conn);
+
+    let card = cq.typed().get_by_uuid("card-uuid-001").unwrap().unwrap();
+    assert_eq!(card.name, "Lightning Bolt");
+    assert_eq!(card.type_field, "Instant");
+    assert_eq!(card.colors, vec!["R"]);
+    assert_eq!(card.availability, vec!["mtgo", "paper"]);
+    assert_eq!(card.finishes, vec!["nonfoil", "foil"]);
+    assert_eq!(card.printings, Some(vec!["A25".to_string(), "M10".to_string()]));
+    assert!(card.subtypes.is_empty());
+    assert_eq!(card.keywords, Some(Vec::new()));
+    assert_eq!(card.mana_value, Some(1.0));
+}
+
+#[test]
+fn typed_card_parses_json_cast_columns() {
+    let (conn, _tmp) = common::setup_parquet_db(PARQUET_SQL);
+    let cq = CardQuery::new(&conn);
+
+    let card = cq.typed().get_by_uuid("card-uuid-001").unwrap().unwrap();
+    let identifiers = card.identifiers.unwrap();
+    assert_eq!(identifiers["scryfallId"], "scryfall-001");
+    let legalities = card.legalities.unwrap();
+    assert_eq!(legalities["vintage"], "Restricted");
+    assert!(card.leadership_skills.is_none());
+
+    let other = cq.typed().get_by_uuid("card-uuid-002").unwrap().unwrap();
+    assert_eq!(other.leadership_skills.unwrap()["commander"], false);
+}
+
+#[test]
+fn typed_card_parses_numeric_list_columns() {
+    let (conn, _tmp) = common::setup_parquet_db(PARQUET_SQL);
+    let cq = CardQuery::new(&conn);
+
+    let card = cq.typed().get_by_uuid("card-uuid-002").unwrap().unwrap();
+    assert_eq!(card.attraction_lights, Some(vec![1, 3]));
+}
+
+#[test]
+fn typed_card_get_by_name_and_search() {
+    let (conn, _tmp) = common::setup_parquet_db(PARQUET_SQL);
+    let cq = CardQuery::new(&conn);
+
+    let bolts = cq.typed().get_by_name("Lightning Bolt", Some("A25")).unwrap();
+    assert_eq!(bolts.len(), 1);
+    assert_eq!(bolts[0].uuid, "card-uuid-001");
+
+    let results = cq
+        .typed()
+        .search(&SearchCardsParams {
+            colors: Some(vec!["R".to_string()]),
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].set_code, "A25");
+}
+
+#[test]
+fn typed_card_get_atomic_returns_atomic_model() {
+    let (conn, _tmp) = common::setup_parquet_db(PARQUET_SQL);
+    let cq = CardQuery::new(&conn);
+
+    let atomic = cq.typed().get_atomic("Lightning Bolt").unwrap();
+    assert_eq!(atomic.len(), 1);
+    assert_eq!(atomic[0].name, "Lightning Bolt");
+    assert!(atomic[0].first_printing.is_none());
+}
+
+#[test]
+fn untyped_card_lists_are_json_arrays() {
+    let (conn, _tmp) = common::setup_parquet_db(PARQUET_SQL);
+    let cq = CardQuery::new(&conn);
+
+    let card = cq.get_by_uuid("card-uuid-001").unwrap().unwrap();
+    assert_eq!(card["availability"], serde_json::json!(["mtgo", "paper"]));
+}
+
+// ---------------------------------------------------------------------------
+// Sets and sealed products
+// ---------------------------------------------------------------------------
+
+#[test]
+fn typed_set_deserializes_lists_and_translations() {
+    let (conn, _tmp) = common::setup_parquet_db(PARQUET_SQL);
+    let sq = SetQuery::new(&conn);
+
+    let set = sq.typed().get("a25").unwrap().unwrap();
+    assert_eq!(set.code, "A25");
+    assert_eq!(set.base_set_size, 249);
+    assert_eq!(
+        set.languages,
+        Some(vec!["English".to_string(), "Japanese".to_string()])
+    );
+    assert_eq!(set.translations.unwrap()["French"], "Masters 25");
+    assert_eq!(set.sealed_product.unwrap().len(), 1);
+    assert!(set.cards.is_empty());
+}
+
+#[test]
+fn typed_set_list_returns_models() {
+    let (conn, _tmp) = common::setup_parquet_db(PARQUET_SQL);
+    let sq = SetQuery::new(&conn);
+
+    let sets = sq.typed().list(None, None, None, None).unwrap();
+    assert_eq!(sets.len(), 1);
+    assert_eq!(sets[0].name, "Masters 25");
+}
+
+#[test]
+fn typed_sealed_products_tagged_with_set_code() {
+    let (conn, _tmp) = common::setup_parquet_db(PARQUET_SQL);
+    let sq = SealedQuery::new(&conn);
+
+    let products = sq.typed().get("A25").unwrap();
+    assert_eq!(products.len(), 1);
+    assert_eq!(products[0].uuid, "sealed-uuid-001");
+    assert_eq!(products[0].card_count, Some(360));
+    assert_eq!(products[0].set_code.as_deref(), Some("A25"));
+}
+
+// ---------------------------------------------------------------------------
+// Tokens
+// ---------------------------------------------------------------------------
+
+#[test]
+fn typed_token_deserializes_view_columns() {
+    let (conn, _tmp) = common::setup_parquet_db(PARQUET_SQL);
+    let tq = TokenQuery::new(&conn);
+
+    let token = tq.typed().get_by_uuid("token-uuid-001").unwrap().unwrap();
+    assert_eq!(token.name, "Soldier");
+    assert_eq!(token.colors, vec!["W"]);
+    assert_eq!(
+        token.reverse_related,
+        Some(vec!["Raise the Alarm".to_string()])
+    );
+    assert_eq!(token.related_cards.unwrap()["reverseRelated"][0], "Raise the Alarm");
+    assert_eq!(token.token_products.unwrap()[0]["uuid"], "product-001");
+}
+
+// ---------------------------------------------------------------------------
+// Prices
+// ---------------------------------------------------------------------------
+
+#[test]
+fn typed_price_rows_convert_dates_and_price_type() {
+    let (conn, _tmp) = common::setup_parquet_db(PARQUET_SQL);
+    let pq = PriceQuery::new(&conn);
+
+    let rows = pq.typed().today("card-uuid-001").unwrap();
+    assert_eq!(rows.len(), 2);
+    assert!(rows.iter().all(|r| r.date == "2024-06-01"));
+    assert!(rows.iter().all(|r| r.category == "retail"));
+}
+
+#[test]
+fn typed_cheapest_printing_flattens_card_and_price() {
+    let (conn, _tmp) = common::setup_parquet_db(PARQUET_SQL);
+    let pq = PriceQuery::new(&conn);
+
+    let cheapest = pq.typed().cheapest_printing("Lightning Bolt").unwrap().unwrap();
+    assert_eq!(cheapest.card.uuid, "card-uuid-001");
+    assert_eq!(cheapest.provider, "cardkingdom");
+    assert!((cheapest.price - 0.99).abs() < f64::EPSILON);
+}
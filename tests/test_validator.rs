@@ -0,0 +1,216 @@
+//! Deck validation tests for constructed, Vintage and Commander rules.
+
+#[allow(dead_code)]
+mod common;
+
+use mtgjson_sdk::formats::{DeckEntry, Section};
+use mtgjson_sdk::validator::{DeckValidator, Violation};
+
+// ---------------------------------------------------------------------------
+// Fixtures
+// ---------------------------------------------------------------------------
+
+/// Writes the parquet files behind `cards` and `card_legalities` (wide
+/// format); see [`common::setup_parquet_db`].
+const PARQUET_SQL: &str = r#"
+    COPY (
+        SELECT * FROM (VALUES
+            ('uuid-bolt', 'Lightning Bolt', 'A25', '141', 'Instant', 'R', false, NULL),
+            ('uuid-counter', 'Counterspell', 'A25', '50', 'Instant', 'U', false, NULL),
+            ('uuid-mountain', 'Mountain', 'M21', '269', 'Basic Land — Mountain', 'R', false, NULL),
+            ('uuid-island', 'Island', 'M21', '263', 'Basic Land — Island', 'U', false, NULL),
+            ('uuid-rats', 'Relentless Rats', 'M11', '111', 'Creature — Rat', 'B', true, NULL),
+            ('uuid-ragavan', 'Ragavan, Nimble Pilferer', 'MH2', '138', 'Legendary Creature — Monkey Pirate', 'R', false,
+             '{"brawl": false, "commander": true, "oathbreaker": false}'),
+            ('uuid-lotus', 'Black Lotus', 'LEA', '232', 'Artifact', '', false, NULL),
+            ('uuid-krenko', 'Krenko, Mob Boss', 'M13', '139', 'Legendary Creature — Goblin Warrior', 'R', false,
+             '{"brawl": false, "commander": true, "oathbreaker": false}')
+        ) AS t(uuid, name, setCode, number, "type", colorIdentity, hasAlternativeDeckLimit, leadershipSkills)
+    ) TO '{dir}/cards.parquet' (FORMAT PARQUET);
+
+    COPY (
+        SELECT * FROM (VALUES
+            ('uuid-bolt', 'Legal', 'Legal', 'Legal'),
+            ('uuid-counter', 'Legal', 'Legal', 'Legal'),
+            ('uuid-mountain', 'Legal', 'Legal', 'Legal'),
+            ('uuid-island', 'Legal', 'Legal', 'Legal'),
+            ('uuid-rats', 'Legal', 'Legal', 'Legal'),
+            ('uuid-ragavan', 'Banned', 'Legal', 'Legal'),
+            ('uuid-lotus', NULL, 'Restricted', 'Banned'),
+            ('uuid-krenko', 'Legal', 'Legal', 'Legal')
+        ) AS t(uuid, modern, vintage, commander)
+    ) TO '{dir}/cardLegalities.parquet' (FORMAT PARQUET);
+"#;
+
+fn card(quantity: i64, name: &str, section: Section) -> DeckEntry {
+    DeckEntry {
+        quantity,
+        name: name.to_string(),
+        set_code: None,
+        number: None,
+        section,
+        uuid: None,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Constructed
+// ---------------------------------------------------------------------------
+
+#[test]
+fn valid_modern_deck() {
+    let (conn, _tmp) = common::setup_parquet_db(PARQUET_SQL);
+    let deck = vec![
+        card(4, "Lightning Bolt", Section::Main),
+        card(56, "Mountain", Section::Main),
+        card(4, "Counterspell", Section::Sideboard),
+    ];
+
+    let report = DeckValidator::new(&conn).validate(&deck, "Modern").unwrap();
+    assert!(report.is_valid(), "{:?}", report.violations);
+    assert_eq!(report.format, "modern");
+    assert_eq!((report.deck_size, report.sideboard_size), (60, 4));
+}
+
+#[test]
+fn modern_violations_are_all_reported() {
+    let (conn, _tmp) = common::setup_parquet_db(PARQUET_SQL);
+    let deck = vec![
+        card(3, "Lightning Bolt", Section::Main),
+        card(2, "Lightning Bolt", Section::Sideboard),
+        card(1, "Ragavan, Nimble Pilferer", Section::Main),
+        card(1, "Black Lotus", Section::Main),
+        card(30, "Mountain", Section::Main),
+        card(20, "Relentless Rats", Section::Main),
+        card(14, "Island", Section::Sideboard),
+        card(1, "Counterspell", Section::Companion),
+        card(1, "Nonexistent Card", Section::Main),
+    ];
+
+    let report = DeckValidator::new(&conn).validate(&deck, "modern").unwrap();
+    let v = &report.violations;
+    assert!(v.contains(&Violation::UnknownCard {
+        card: "Nonexistent Card".to_string()
+    }));
+    assert!(v.contains(&Violation::DeckTooSmall { count: 56, min: 60 }));
+    assert!(v.contains(&Violation::SideboardTooLarge { count: 17, max: 15 }));
+    assert!(v.contains(&Violation::TooManyCopies {
+        name: "Lightning Bolt".to_string(),
+        count: 5,
+        max: 4
+    }));
+    assert!(v.contains(&Violation::NotLegal {
+        name: "Ragavan, Nimble Pilferer".to_string(),
+        status: "Banned".to_string()
+    }));
+    assert!(v.contains(&Violation::NotLegal {
+        name: "Black Lotus".to_string(),
+        status: "Not Legal".to_string()
+    }));
+    // Basic lands and alternative deck limits are exempt from the 4-of rule
+    assert_eq!(v.len(), 6, "{:?}", v);
+}
+
+#[test]
+fn vintage_restricted_limit() {
+    let (conn, _tmp) = common::setup_parquet_db(PARQUET_SQL);
+    let validator = DeckValidator::new(&conn);
+
+    let mut deck = vec![
+        card(1, "Black Lotus", Section::Main),
+        card(59, "Mountain", Section::Main),
+    ];
+    assert!(validator.validate(&deck, "vintage").unwrap().is_valid());
+
+    deck[0].quantity = 2;
+    let report = validator.validate(&deck, "vintage").unwrap();
+    assert_eq!(
+        report.violations,
+        vec![Violation::Restricted {
+            name: "Black Lotus".to_string(),
+            count: 2
+        }]
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Commander
+// ---------------------------------------------------------------------------
+
+#[test]
+fn valid_commander_deck() {
+    let (conn, _tmp) = common::setup_parquet_db(PARQUET_SQL);
+    let deck = vec![
+        DeckEntry {
+            uuid: Some("uuid-krenko".to_string()),
+            ..card(1, "", Section::Commander)
+        },
+        card(1, "Lightning Bolt", Section::Main),
+        card(98, "Mountain", Section::Main),
+    ];
+
+    let report = DeckValidator::new(&conn).validate(&deck, "commander").unwrap();
+    assert!(report.is_valid(), "{:?}", report.violations);
+    assert_eq!(report.deck_size, 100);
+}
+
+#[test]
+fn commander_singleton_identity_and_size() {
+    let (conn, _tmp) = common::setup_parquet_db(PARQUET_SQL);
+    let deck = vec![
+        card(1, "Krenko, Mob Boss", Section::Commander),
+        card(2, "Lightning Bolt", Section::Main),
+        card(1, "Counterspell", Section::Main),
+        card(1, "Black Lotus", Section::Main),
+        card(90, "Mountain", Section::Main),
+        card(1, "Island", Section::Sideboard),
+    ];
+
+    let report = DeckValidator::new(&conn).validate(&deck, "commander").unwrap();
+    let v = &report.violations;
+    assert!(v.contains(&Violation::WrongDeckSize {
+        count: 95,
+        expected: 100
+    }));
+    assert!(v.contains(&Violation::SideboardTooLarge { count: 1, max: 0 }));
+    assert!(v.contains(&Violation::ColorIdentity {
+        name: "Counterspell".to_string(),
+        colors: vec!["U".to_string()]
+    }));
+    assert!(v.contains(&Violation::ColorIdentity {
+        name: "Island".to_string(),
+        colors: vec!["U".to_string()]
+    }));
+    assert!(v.contains(&Violation::TooManyCopies {
+        name: "Lightning Bolt".to_string(),
+        count: 2,
+        max: 1
+    }));
+    assert!(v.contains(&Violation::NotLegal {
+        name: "Black Lotus".to_string(),
+        status: "Banned".to_string()
+    }));
+    assert_eq!(v.len(), 6, "{:?}", v);
+}
+
+#[test]
+fn commander_must_have_leadership_skill() {
+    let (conn, _tmp) = common::setup_parquet_db(PARQUET_SQL);
+    let validator = DeckValidator::new(&conn);
+
+    let no_commander = vec![card(100, "Mountain", Section::Main)];
+    let report = validator.validate(&no_commander, "commander").unwrap();
+    assert_eq!(report.violations, vec![Violation::CommanderCount { count: 0 }]);
+
+    let bolt_commander = vec![
+        card(1, "Lightning Bolt", Section::Commander),
+        card(99, "Mountain", Section::Main),
+    ];
+    let report = validator.validate(&bolt_commander, "commander").unwrap();
+    assert_eq!(
+        report.violations,
+        vec![Violation::InvalidCommander {
+            name: "Lightning Bolt".to_string()
+        }]
+    );
+}
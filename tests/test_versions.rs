@@ -0,0 +1,136 @@
+//! Side-by-side versions: pinning an archived MTGJSON version and attaching
+//! several versions to one connection, against a local HTTP stand-in for the
+//! CDN.
+
+#[allow(dead_code)]
+mod common;
+
+use common::cdn::{meta, parquet, Cdn};
+use mtgjson_sdk::{MtgjsonError, MtgjsonSdk};
+use std::path::Path;
+
+const OLD: &str = "5.2.2+20240101";
+const NEW: &str = "5.2.3+20240201";
+
+fn cards(name: &str) -> Vec<u8> {
+    parquet(&format!(
+        "SELECT * FROM (VALUES ('card-1', '{}'), ('card-2', 'Shock')) t(uuid, name)",
+        name
+    ))
+}
+
+/// Cache `OLD` and refresh to `NEW`, which renames card-1.
+fn two_versions(dir: &Path) -> Cdn {
+    let cdn = Cdn::start(vec![
+        ("Meta.json", meta(OLD)),
+        ("Keywords.json", br#"{"data": {"abilityWords": ["Landfall"]}}"#.to_vec()),
+        ("parquet/cards.parquet", cards("Lightning Bolt")),
+    ]);
+    let sdk = MtgjsonSdk::builder()
+        .cache_dir(dir)
+        .cdn_base(cdn.base.clone())
+        .build()
+        .unwrap();
+    sdk.connection().unwrap().ensure_views(&["cards"]).unwrap();
+    sdk.enums().keywords().unwrap();
+
+    cdn.set("Meta.json", meta(NEW));
+    cdn.set("parquet/cards.parquet", cards("Lightning Strike"));
+    assert!(sdk.refresh().unwrap());
+    cdn
+}
+
+fn card_name(sdk: &MtgjsonSdk) -> serde_json::Value {
+    sdk.connection().unwrap().ensure_views(&["cards"]).unwrap();
+    sdk.sql("SELECT name FROM cards WHERE uuid = 'card-1'", &[])
+        .unwrap()[0]["name"]
+        .clone()
+}
+
+#[test]
+fn archive_holds_unchanged_files_too() {
+    let tmp = tempfile::tempdir().unwrap();
+    two_versions(tmp.path());
+    let archive = tmp.path().join("versions").join(OLD);
+    assert!(archive.join("parquet/cards.parquet").exists());
+    // Keywords.json did not change, but is still part of the old version
+    assert!(archive.join("Keywords.json").exists());
+}
+
+#[test]
+fn pinned_sdk_serves_archived_version() {
+    let tmp = tempfile::tempdir().unwrap();
+    let cdn = two_versions(tmp.path());
+    let pinned = |version: &str| {
+        MtgjsonSdk::builder()
+            .cache_dir(tmp.path())
+            .cdn_base(cdn.base.clone())
+            .pin_version(version)
+            .build()
+            .unwrap()
+    };
+
+    let old = pinned(OLD);
+    assert_eq!(card_name(&old), "Lightning Bolt");
+    assert_eq!(old.enums().keywords().unwrap()["abilityWords"][0], "Landfall");
+    assert!(!old.refresh().unwrap());
+
+    let requests = cdn.requests("parquet/cards.parquet").len();
+    assert_eq!(card_name(&pinned(NEW)), "Lightning Strike");
+    assert_eq!(cdn.requests("parquet/cards.parquet").len(), requests);
+}
+
+#[test]
+fn pinning_an_uncached_version_fails() {
+    let tmp = tempfile::tempdir().unwrap();
+    let cdn = two_versions(tmp.path());
+    let sdk = MtgjsonSdk::builder()
+        .cache_dir(tmp.path())
+        .cdn_base(cdn.base.clone())
+        .pin_version("5.1.0+20230101")
+        .build()
+        .unwrap();
+    let err = sdk.connection().unwrap().ensure_views(&["cards"]).unwrap_err();
+    assert!(matches!(err, MtgjsonError::NotFound(_)), "{}", err);
+
+    let invalid = MtgjsonSdk::builder()
+        .cache_dir(tmp.path())
+        .pin_version("../5.2.2")
+        .build();
+    assert!(matches!(invalid, Err(MtgjsonError::InvalidArgument(_))));
+}
+
+#[test]
+fn attached_versions_can_be_compared() {
+    let tmp = tempfile::tempdir().unwrap();
+    let cdn = two_versions(tmp.path());
+    let sdk = MtgjsonSdk::builder()
+        .cache_dir(tmp.path())
+        .cdn_base(cdn.base.clone())
+        .build()
+        .unwrap();
+    let conn = sdk.connection().unwrap();
+    conn.attach_version("v_old", OLD, &["cards"]).unwrap();
+    conn.attach_version("v_new", NEW, &["cards"]).unwrap();
+
+    let rows = sdk
+        .sql(
+            "SELECT o.name AS old_name, n.name AS new_name \
+             FROM v_old.cards o JOIN v_new.cards n USING (uuid) \
+             WHERE o.name IS DISTINCT FROM n.name",
+            &[],
+        )
+        .unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0]["old_name"], "Lightning Bolt");
+    assert_eq!(rows[0]["new_name"], "Lightning Strike");
+
+    assert!(matches!(
+        conn.attach_version("v-old", OLD, &["cards"]),
+        Err(MtgjsonError::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        conn.attach_version("v_gone", "5.1.0+20230101", &["cards"]),
+        Err(MtgjsonError::NotFound(_))
+    ));
+}